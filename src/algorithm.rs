@@ -0,0 +1,209 @@
+use std::{fmt, str::FromStr};
+
+/// A compression algorithm, for picking which to use at runtime, e.g. from a config file or a
+/// `Content-Encoding` header, with the `AnyEncoder` and `AnyDecoder` types in each IO module.
+///
+/// Only the algorithms whose features are enabled are available, along with
+/// [`Identity`](Self::Identity) which passes data through unchanged.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// No compression, data is passed through unchanged.
+    Identity,
+    /// The BGZF variant of gzip.
+    #[cfg(feature = "bgzf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bgzf")))]
+    Bgzf,
+    /// Brotli.
+    #[cfg(feature = "brotli")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "brotli")))]
+    Brotli,
+    /// Bzip2.
+    #[cfg(feature = "bzip2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bzip2")))]
+    Bzip2,
+    /// Raw deflate, without the zlib header and footer.
+    #[cfg(feature = "deflate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "deflate")))]
+    Deflate,
+    /// Gzip.
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    Gzip,
+    /// LZ4.
+    #[cfg(feature = "lz4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lz4")))]
+    Lz4,
+    /// Legacy LZMA, which is only available for decoding with just the `lzma-rs` feature.
+    #[cfg(any(feature = "lzma", feature = "lzma-rs"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "lzma", feature = "lzma-rs"))))]
+    Lzma,
+    /// Snappy.
+    #[cfg(feature = "snappy")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "snappy")))]
+    Snappy,
+    /// Xz, which is only available for decoding with just the `lzma-rs` feature.
+    #[cfg(any(feature = "xz", feature = "lzma-rs"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "xz", feature = "lzma-rs"))))]
+    Xz,
+    /// Zlib, which is what HTTP calls `deflate`.
+    #[cfg(feature = "zlib")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zlib")))]
+    Zlib,
+    /// Zstandard, which is only available for decoding with just the `ruzstd` feature.
+    #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "zstd", feature = "ruzstd"))))]
+    Zstd,
+}
+
+impl Algorithm {
+    /// The canonical name of this algorithm, the same as the feature enabling it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Identity => "identity",
+            #[cfg(feature = "bgzf")]
+            Self::Bgzf => "bgzf",
+            #[cfg(feature = "brotli")]
+            Self::Brotli => "brotli",
+            #[cfg(feature = "bzip2")]
+            Self::Bzip2 => "bzip2",
+            #[cfg(feature = "deflate")]
+            Self::Deflate => "deflate",
+            #[cfg(feature = "gzip")]
+            Self::Gzip => "gzip",
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => "lz4",
+            #[cfg(any(feature = "lzma", feature = "lzma-rs"))]
+            Self::Lzma => "lzma",
+            #[cfg(feature = "snappy")]
+            Self::Snappy => "snappy",
+            #[cfg(any(feature = "xz", feature = "lzma-rs"))]
+            Self::Xz => "xz",
+            #[cfg(feature = "zlib")]
+            Self::Zlib => "zlib",
+            #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Parses a single HTTP `Content-Encoding` token, ignoring case and surrounding whitespace.
+    ///
+    /// Returns `None` for unknown tokens and those whose algorithm is not enabled. Note that
+    /// `deflate` here means [`Zlib`](Self::Zlib), as that is what HTTP uses.
+    pub fn from_content_encoding(token: &str) -> Option<Self> {
+        let token = token.trim();
+        if token.eq_ignore_ascii_case("identity") {
+            return Some(Self::Identity);
+        }
+        #[cfg(feature = "brotli")]
+        {
+            if token.eq_ignore_ascii_case("br") {
+                return Some(Self::Brotli);
+            }
+        }
+        #[cfg(feature = "gzip")]
+        {
+            if token.eq_ignore_ascii_case("gzip") || token.eq_ignore_ascii_case("x-gzip") {
+                return Some(Self::Gzip);
+            }
+        }
+        #[cfg(feature = "zlib")]
+        {
+            if token.eq_ignore_ascii_case("deflate") {
+                return Some(Self::Zlib);
+            }
+        }
+        #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+        {
+            if token.eq_ignore_ascii_case("zstd") {
+                return Some(Self::Zstd);
+            }
+        }
+        None
+    }
+
+    /// The HTTP `Content-Encoding` token for this algorithm, if it has one.
+    ///
+    /// BGZF streams are valid gzip so are labelled `gzip`.
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Self::Identity => Some("identity"),
+            #[cfg(feature = "bgzf")]
+            Self::Bgzf => Some("gzip"),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Some("br"),
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Some("gzip"),
+            #[cfg(feature = "zlib")]
+            Self::Zlib => Some("deflate"),
+            #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+            Self::Zstd => Some("zstd"),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the [`name`](Algorithm::name) of an algorithm, ignoring case, or one of the common
+/// aliases `br`, `bz2`, `gz`, `x-gzip` and `zst`.
+///
+/// Unlike [`Algorithm::from_content_encoding`], `deflate` means raw
+/// [`Deflate`](Algorithm::Deflate) here.
+impl FromStr for Algorithm {
+    type Err = ParseAlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let algorithm = match s.to_ascii_lowercase().as_str() {
+            "identity" => Self::Identity,
+            #[cfg(feature = "bgzf")]
+            "bgzf" => Self::Bgzf,
+            #[cfg(feature = "brotli")]
+            "brotli" | "br" => Self::Brotli,
+            #[cfg(feature = "bzip2")]
+            "bzip2" | "bz2" => Self::Bzip2,
+            #[cfg(feature = "deflate")]
+            "deflate" => Self::Deflate,
+            #[cfg(feature = "gzip")]
+            "gzip" | "gz" | "x-gzip" => Self::Gzip,
+            #[cfg(feature = "lz4")]
+            "lz4" => Self::Lz4,
+            #[cfg(any(feature = "lzma", feature = "lzma-rs"))]
+            "lzma" => Self::Lzma,
+            #[cfg(feature = "snappy")]
+            "snappy" => Self::Snappy,
+            #[cfg(any(feature = "xz", feature = "lzma-rs"))]
+            "xz" => Self::Xz,
+            #[cfg(feature = "zlib")]
+            "zlib" => Self::Zlib,
+            #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+            "zstd" | "zst" => Self::Zstd,
+            _ => return Err(ParseAlgorithmError { name: s.to_owned() }),
+        };
+        Ok(algorithm)
+    }
+}
+
+/// The error returned when parsing an [`Algorithm`] fails, because the name is unknown or its
+/// feature is not enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseAlgorithmError {
+    pub(crate) name: String,
+}
+
+impl fmt::Display for ParseAlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown or disabled compression algorithm `{}`",
+            self.name
+        )
+    }
+}
+
+impl std::error::Error for ParseAlgorithmError {}
@@ -0,0 +1,55 @@
+//! Types which are specific to the BGZF format.
+
+use std::fmt;
+
+/// A BGZF virtual file offset, identifying a position in the uncompressed data by the offset of
+/// the compressed block containing it and the offset within that block's uncompressed data.
+///
+/// This is packed into a `u64` the same way as in BAM indexes and htslib, the compressed offset in
+/// the upper 48 bits and the uncompressed offset in the lower 16 bits.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct VirtualOffset(u64);
+
+impl VirtualOffset {
+    /// Creates a virtual offset from the start of a compressed block and an offset into its
+    /// uncompressed data.
+    ///
+    /// # Panics
+    ///
+    /// If `compressed` does not fit in 48 bits.
+    pub fn new(compressed: u64, uncompressed: u16) -> Self {
+        assert!(compressed < 1 << 48, "compressed offset out of range");
+        Self(compressed << 16 | u64::from(uncompressed))
+    }
+
+    /// The offset of the start of the compressed block in the BGZF file.
+    pub fn compressed(self) -> u64 {
+        self.0 >> 16
+    }
+
+    /// The offset into the uncompressed data of the block.
+    pub fn uncompressed(self) -> u16 {
+        self.0 as u16
+    }
+}
+
+impl From<u64> for VirtualOffset {
+    fn from(offset: u64) -> Self {
+        Self(offset)
+    }
+}
+
+impl From<VirtualOffset> for u64 {
+    fn from(offset: VirtualOffset) -> Self {
+        offset.0
+    }
+}
+
+impl fmt::Debug for VirtualOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VirtualOffset")
+            .field(&self.compressed())
+            .field(&self.uncompressed())
+            .finish()
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{
+    codec::{Decode, Encode},
+    util::PartialBuffer,
+    Algorithm, Level,
+};
+use std::io::{Error, ErrorKind, Result};
+
+#[derive(Debug)]
+enum EncoderKind {
+    Identity,
+    #[cfg(feature = "bgzf")]
+    Bgzf(Box<crate::codec::BgzfEncoder>),
+    #[cfg(feature = "brotli")]
+    Brotli(Box<crate::codec::BrotliEncoder>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(Box<crate::codec::BzEncoder>),
+    #[cfg(feature = "deflate")]
+    Deflate(Box<crate::codec::DeflateEncoder>),
+    #[cfg(feature = "gzip")]
+    Gzip(Box<crate::codec::GzipEncoder>),
+    #[cfg(feature = "lz4")]
+    Lz4(Box<crate::codec::Lz4Encoder>),
+    #[cfg(feature = "lzma")]
+    Lzma(Box<crate::codec::LzmaEncoder>),
+    #[cfg(feature = "snappy")]
+    Snappy(Box<crate::codec::SnappyEncoder>),
+    #[cfg(feature = "xz")]
+    Xz(Box<crate::codec::XzEncoder>),
+    #[cfg(feature = "zlib")]
+    Zlib(Box<crate::codec::ZlibEncoder>),
+    #[cfg(feature = "zstd")]
+    Zstd(Box<crate::codec::ZstdEncoder>),
+}
+
+#[derive(Debug)]
+enum DecoderKind {
+    Identity,
+    #[cfg(feature = "bgzf")]
+    Bgzf(Box<crate::codec::BgzfDecoder>),
+    #[cfg(feature = "brotli")]
+    Brotli(Box<crate::codec::BrotliDecoder>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(Box<crate::codec::BzDecoder>),
+    #[cfg(feature = "deflate")]
+    Deflate(Box<crate::codec::DeflateDecoder>),
+    #[cfg(feature = "gzip")]
+    Gzip(Box<crate::codec::GzipDecoder>),
+    #[cfg(feature = "lz4")]
+    Lz4(Box<crate::codec::Lz4Decoder>),
+    #[cfg(any(feature = "lzma", feature = "lzma-rs"))]
+    Lzma(Box<crate::codec::LzmaDecoder>),
+    #[cfg(feature = "snappy")]
+    Snappy(Box<crate::codec::SnappyDecoder>),
+    #[cfg(any(feature = "xz", feature = "lzma-rs"))]
+    Xz(Box<crate::codec::XzDecoder>),
+    #[cfg(feature = "zlib")]
+    Zlib(Box<crate::codec::ZlibDecoder>),
+    #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+    Zstd(Box<crate::codec::ZstdDecoder>),
+}
+
+/// Runs `$body` with `$codec` bound to the wrapped encoder, or `$identity` for
+/// [`EncoderKind::Identity`].
+macro_rules! with_encoder {
+    ($kind:expr, $codec:ident => $body:expr, Identity => $identity:expr) => {
+        match $kind {
+            EncoderKind::Identity => $identity,
+            #[cfg(feature = "bgzf")]
+            EncoderKind::Bgzf($codec) => $body,
+            #[cfg(feature = "brotli")]
+            EncoderKind::Brotli($codec) => $body,
+            #[cfg(feature = "bzip2")]
+            EncoderKind::Bzip2($codec) => $body,
+            #[cfg(feature = "deflate")]
+            EncoderKind::Deflate($codec) => $body,
+            #[cfg(feature = "gzip")]
+            EncoderKind::Gzip($codec) => $body,
+            #[cfg(feature = "lz4")]
+            EncoderKind::Lz4($codec) => $body,
+            #[cfg(feature = "lzma")]
+            EncoderKind::Lzma($codec) => $body,
+            #[cfg(feature = "snappy")]
+            EncoderKind::Snappy($codec) => $body,
+            #[cfg(feature = "xz")]
+            EncoderKind::Xz($codec) => $body,
+            #[cfg(feature = "zlib")]
+            EncoderKind::Zlib($codec) => $body,
+            #[cfg(feature = "zstd")]
+            EncoderKind::Zstd($codec) => $body,
+        }
+    };
+}
+
+/// Runs `$body` with `$codec` bound to the wrapped decoder, or `$identity` for
+/// [`DecoderKind::Identity`].
+macro_rules! with_decoder {
+    ($kind:expr, $codec:ident => $body:expr, Identity => $identity:expr) => {
+        match $kind {
+            DecoderKind::Identity => $identity,
+            #[cfg(feature = "bgzf")]
+            DecoderKind::Bgzf($codec) => $body,
+            #[cfg(feature = "brotli")]
+            DecoderKind::Brotli($codec) => $body,
+            #[cfg(feature = "bzip2")]
+            DecoderKind::Bzip2($codec) => $body,
+            #[cfg(feature = "deflate")]
+            DecoderKind::Deflate($codec) => $body,
+            #[cfg(feature = "gzip")]
+            DecoderKind::Gzip($codec) => $body,
+            #[cfg(feature = "lz4")]
+            DecoderKind::Lz4($codec) => $body,
+            #[cfg(any(feature = "lzma", feature = "lzma-rs"))]
+            DecoderKind::Lzma($codec) => $body,
+            #[cfg(feature = "snappy")]
+            DecoderKind::Snappy($codec) => $body,
+            #[cfg(any(feature = "xz", feature = "lzma-rs"))]
+            DecoderKind::Xz($codec) => $body,
+            #[cfg(feature = "zlib")]
+            DecoderKind::Zlib($codec) => $body,
+            #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+            DecoderKind::Zstd($codec) => $body,
+        }
+    };
+}
+
+#[derive(Debug)]
+pub struct AnyEncoder {
+    algorithm: Algorithm,
+    kind: EncoderKind,
+}
+
+impl AnyEncoder {
+    /// Fails for algorithms which only have a decoder enabled.
+    pub(crate) fn new(algorithm: Algorithm, level: Level) -> Result<Self> {
+        let kind = match algorithm {
+            Algorithm::Identity => EncoderKind::Identity,
+            #[cfg(feature = "bgzf")]
+            Algorithm::Bgzf => EncoderKind::Bgzf(Box::new(crate::codec::BgzfEncoder::new(
+                level.into_flate2(),
+            ))),
+            #[cfg(feature = "brotli")]
+            Algorithm::Brotli => {
+                let params = brotli::enc::backward_references::BrotliEncoderParams::default();
+                EncoderKind::Brotli(Box::new(crate::codec::BrotliEncoder::new(
+                    level.into_brotli(params),
+                )))
+            }
+            #[cfg(feature = "bzip2")]
+            Algorithm::Bzip2 => EncoderKind::Bzip2(Box::new(crate::codec::BzEncoder::new(
+                level.into_bzip2(),
+                0,
+            ))),
+            #[cfg(feature = "deflate")]
+            Algorithm::Deflate => EncoderKind::Deflate(Box::new(
+                crate::codec::DeflateEncoder::new(level.into_flate2()),
+            )),
+            #[cfg(feature = "gzip")]
+            Algorithm::Gzip => EncoderKind::Gzip(Box::new(crate::codec::GzipEncoder::new(
+                level.into_flate2(),
+            ))),
+            #[cfg(feature = "lz4")]
+            Algorithm::Lz4 => {
+                EncoderKind::Lz4(Box::new(crate::codec::Lz4Encoder::new(level.into_lz4())))
+            }
+            #[cfg(feature = "lzma")]
+            Algorithm::Lzma => {
+                EncoderKind::Lzma(Box::new(crate::codec::LzmaEncoder::new(level.into_xz2())))
+            }
+            #[cfg(feature = "snappy")]
+            Algorithm::Snappy => EncoderKind::Snappy(Box::new(crate::codec::SnappyEncoder::new())),
+            #[cfg(feature = "xz")]
+            Algorithm::Xz => {
+                EncoderKind::Xz(Box::new(crate::codec::XzEncoder::new(level.into_xz2())))
+            }
+            #[cfg(feature = "zlib")]
+            Algorithm::Zlib => EncoderKind::Zlib(Box::new(crate::codec::ZlibEncoder::new(
+                level.into_flate2(),
+            ))),
+            #[cfg(feature = "zstd")]
+            Algorithm::Zstd => {
+                EncoderKind::Zstd(Box::new(crate::codec::ZstdEncoder::new(level.into_zstd())))
+            }
+            #[allow(unreachable_patterns)]
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("no {} encoder is enabled", algorithm),
+                ))
+            }
+        };
+
+        Ok(Self { algorithm, kind })
+    }
+
+    pub(crate) fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Whether [`new`](Self::new) can succeed for `algorithm`.
+    pub(crate) fn is_supported(algorithm: Algorithm) -> bool {
+        match algorithm {
+            #[cfg(all(feature = "lzma-rs", not(feature = "lzma")))]
+            Algorithm::Lzma => false,
+            #[cfg(all(feature = "lzma-rs", not(feature = "xz")))]
+            Algorithm::Xz => false,
+            #[cfg(all(feature = "ruzstd", not(feature = "zstd")))]
+            Algorithm::Zstd => false,
+            _ => true,
+        }
+    }
+}
+
+impl Encode for AnyEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        with_encoder!(&mut self.kind, codec => codec.reinit(), Identity => Ok(()))
+    }
+
+    fn can_reinit(&self) -> bool {
+        with_encoder!(&self.kind, codec => codec.can_reinit(), Identity => true)
+    }
+
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        with_encoder!(
+            &mut self.kind,
+            codec => codec.encode(input, output),
+            Identity => {
+                output.copy_unwritten_from(input);
+                Ok(())
+            }
+        )
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        with_encoder!(&mut self.kind, codec => codec.flush(output), Identity => Ok(true))
+    }
+
+    fn flush_with_mode(
+        &mut self,
+        mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        with_encoder!(
+            &mut self.kind,
+            codec => codec.flush_with_mode(mode, output),
+            Identity => Ok(true)
+        )
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        with_encoder!(&mut self.kind, codec => codec.finish(output), Identity => Ok(true))
+    }
+}
+
+#[derive(Debug)]
+pub struct AnyDecoder {
+    algorithm: Algorithm,
+    kind: DecoderKind,
+}
+
+impl AnyDecoder {
+    pub(crate) fn new(algorithm: Algorithm) -> Self {
+        let kind = match algorithm {
+            Algorithm::Identity => DecoderKind::Identity,
+            #[cfg(feature = "bgzf")]
+            Algorithm::Bgzf => DecoderKind::Bgzf(Box::new(crate::codec::BgzfDecoder::new())),
+            #[cfg(feature = "brotli")]
+            Algorithm::Brotli => DecoderKind::Brotli(Box::new(crate::codec::BrotliDecoder::new())),
+            #[cfg(feature = "bzip2")]
+            Algorithm::Bzip2 => DecoderKind::Bzip2(Box::new(crate::codec::BzDecoder::new())),
+            #[cfg(feature = "deflate")]
+            Algorithm::Deflate => {
+                DecoderKind::Deflate(Box::new(crate::codec::DeflateDecoder::new()))
+            }
+            #[cfg(feature = "gzip")]
+            Algorithm::Gzip => DecoderKind::Gzip(Box::new(crate::codec::GzipDecoder::new())),
+            #[cfg(feature = "lz4")]
+            Algorithm::Lz4 => DecoderKind::Lz4(Box::new(crate::codec::Lz4Decoder::new())),
+            #[cfg(any(feature = "lzma", feature = "lzma-rs"))]
+            Algorithm::Lzma => DecoderKind::Lzma(Box::new(crate::codec::LzmaDecoder::new())),
+            #[cfg(feature = "snappy")]
+            Algorithm::Snappy => DecoderKind::Snappy(Box::new(crate::codec::SnappyDecoder::new())),
+            #[cfg(any(feature = "xz", feature = "lzma-rs"))]
+            Algorithm::Xz => DecoderKind::Xz(Box::new(crate::codec::XzDecoder::new())),
+            #[cfg(feature = "zlib")]
+            Algorithm::Zlib => DecoderKind::Zlib(Box::new(crate::codec::ZlibDecoder::new())),
+            #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+            Algorithm::Zstd => DecoderKind::Zstd(Box::new(crate::codec::ZstdDecoder::new())),
+        };
+
+        Self { algorithm, kind }
+    }
+
+    pub(crate) fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+}
+
+impl Decode for AnyDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(self.algorithm)
+    }
+
+    fn reinit(&mut self) -> Result<()> {
+        with_decoder!(&mut self.kind, codec => codec.reinit(), Identity => Ok(()))
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        with_decoder!(
+            &mut self.kind,
+            codec => codec.decode(input, output),
+            Identity => {
+                // There is no end marker, the data continues until the input ends
+                output.copy_unwritten_from(input);
+                Ok(false)
+            }
+        )
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        with_decoder!(&mut self.kind, codec => codec.flush(output), Identity => Ok(true))
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        with_decoder!(&mut self.kind, codec => codec.finish(output), Identity => Ok(true))
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    codec::{AnyDecoder, Decode},
+    error,
+    util::PartialBuffer,
+    Algorithm,
+};
+use std::io::Result;
+
+/// The magic bytes each format with one starts with.
+const SIGNATURES: &[(&[u8], Algorithm)] = &[
+    #[cfg(feature = "gzip")]
+    (&[0x1f, 0x8b], Algorithm::Gzip),
+    #[cfg(any(feature = "zstd", feature = "ruzstd"))]
+    (&[0x28, 0xb5, 0x2f, 0xfd], Algorithm::Zstd),
+    #[cfg(any(feature = "xz", feature = "lzma-rs"))]
+    (&[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00], Algorithm::Xz),
+    #[cfg(feature = "bzip2")]
+    (b"BZh", Algorithm::Bzip2),
+    #[cfg(feature = "lz4")]
+    (&[0x04, 0x22, 0x4d, 0x18], Algorithm::Lz4),
+    #[cfg(feature = "snappy")]
+    (b"\xff\x06\x00\x00sNaPpY", Algorithm::Snappy),
+];
+
+/// Enough to match the longest signature.
+const PREFIX_LEN: usize = 10;
+
+/// Whether `header` is a valid zlib header followed by the start of a deflate block. Zlib has no
+/// fixed magic bytes, just a check that only 1 in 31 random headers will pass, so this also
+/// requires there to be no preset dictionary, which could not be decoded anyway, and the first
+/// block to not be of the reserved type.
+#[cfg(feature = "zlib")]
+fn is_zlib_header(header: [u8; 3]) -> bool {
+    let [cmf, flg, block] = header;
+    let check = u16::from_be_bytes([cmf, flg]) % 31;
+    cmf & 0x0f == 8 && cmf >> 4 <= 7 && check == 0 && flg & 0x20 == 0 && (block >> 1) & 0x03 != 3
+}
+
+#[derive(Debug)]
+pub struct AutoDecoder {
+    fallback: Option<Algorithm>,
+    // The first bytes of the stream, read one at a time until there are enough to detect the
+    // format, then given to the decoder
+    prefix: PartialBuffer<Vec<u8>>,
+    inner: Option<AnyDecoder>,
+    // Whether the end of the stream was reached within the prefix
+    prefix_done: bool,
+}
+
+impl AutoDecoder {
+    pub(crate) fn new(fallback: Option<Algorithm>) -> Self {
+        Self {
+            fallback,
+            prefix: PartialBuffer::new(Vec::with_capacity(PREFIX_LEN)),
+            inner: None,
+            prefix_done: false,
+        }
+    }
+
+    pub(crate) fn detected(&self) -> Option<Algorithm> {
+        self.inner.as_ref().map(AnyDecoder::algorithm)
+    }
+
+    /// Returns `None` if more of the prefix is needed, which can only happen before `eof`.
+    fn detect(&self, eof: bool) -> Result<Option<Algorithm>> {
+        let prefix = self.prefix.unwritten();
+        let mut undecided = false;
+
+        for (signature, algorithm) in SIGNATURES {
+            if prefix.starts_with(signature) {
+                return Ok(Some(*algorithm));
+            }
+            undecided |= signature.starts_with(prefix);
+        }
+
+        #[cfg(feature = "zlib")]
+        match prefix {
+            [a, b, c, ..] if is_zlib_header([*a, *b, *c]) => return Ok(Some(Algorithm::Zlib)),
+            [_, _, _, ..] => {}
+            _ => undecided = true,
+        }
+
+        if undecided && !eof {
+            return Ok(None);
+        }
+
+        match self.fallback {
+            Some(fallback) => Ok(Some(fallback)),
+            None => Err(error::unrecognized_format()),
+        }
+    }
+
+    /// Passes the prefix on to `inner` once it has been detected, returning whether it has all
+    /// been decoded or the end of the stream was reached within it.
+    fn decode_prefix(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if !self.prefix_done && !self.prefix.unwritten().is_empty() {
+            let inner = self.inner.as_mut().unwrap();
+            self.prefix_done = inner.decode(&mut self.prefix, output)?;
+        }
+        Ok(self.prefix_done || self.prefix.unwritten().is_empty())
+    }
+}
+
+impl Decode for AutoDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        self.detected()
+    }
+
+    fn reinit(&mut self) -> Result<()> {
+        self.prefix_done = false;
+        match &mut self.inner {
+            Some(inner) => inner.reinit(),
+            None => Ok(()),
+        }
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // Taking no more of the stream than is needed to detect the format means no member can
+        // end within the prefix, which would leave the rest of it unchecked as trailing data
+        while self.inner.is_none() {
+            if !self.prefix.unwritten().is_empty() {
+                if let Some(algorithm) = self.detect(false)? {
+                    self.inner = Some(AnyDecoder::new(algorithm));
+                    break;
+                }
+            }
+            match input.unwritten().first() {
+                Some(&byte) => {
+                    self.prefix.get_mut().push(byte);
+                    input.advance(1);
+                }
+                None => return Ok(false),
+            }
+        }
+
+        if !self.decode_prefix(output)? {
+            return Ok(false);
+        }
+        if self.prefix_done {
+            return Ok(true);
+        }
+
+        // The adapters never decode empty input, and some decoders rely on that
+        if input.unwritten().is_empty() {
+            return Ok(false);
+        }
+
+        self.inner.as_mut().unwrap().decode(input, output)
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        match &mut self.inner {
+            Some(inner) => inner.flush(output),
+            None => Ok(true),
+        }
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if self.inner.is_none() {
+            let algorithm = self.detect(true)?.unwrap();
+            self.inner = Some(AnyDecoder::new(algorithm));
+        }
+
+        if !self.decode_prefix(output)? {
+            return Ok(false);
+        }
+
+        self.inner.as_mut().unwrap().finish(output)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    codec::{Decode, GzipDecoder},
+    util::PartialBuffer,
+    Algorithm,
+};
+use std::{cmp::min, io::Result};
+
+#[derive(Debug)]
+pub struct BgzfDecoder {
+    inner: GzipDecoder,
+    // Uncompressed bytes still to be discarded when starting from a virtual offset.
+    skip: usize,
+    // Uncompressed bytes in the current block so far, the empty block is the end of file marker.
+    block_len: usize,
+    // Whether the input is between blocks, so can cleanly end here.
+    at_boundary: bool,
+}
+
+impl BgzfDecoder {
+    pub(crate) fn new() -> Self {
+        Self::with_skip(0)
+    }
+
+    pub(crate) fn with_skip(skip: u16) -> Self {
+        Self {
+            inner: GzipDecoder::with_algorithm(Algorithm::Bgzf),
+            skip: usize::from(skip),
+            block_len: 0,
+            at_boundary: false,
+        }
+    }
+
+    /// Runs `f` against either `output` or a scratch buffer if we're still skipping data.
+    fn with_output(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        f: impl FnOnce(&mut GzipDecoder, &mut PartialBuffer<&mut [u8]>) -> Result<bool>,
+    ) -> Result<bool> {
+        let mut scratch = [0; 1024];
+
+        let (done, len) = if self.skip > 0 {
+            let mut buffer = PartialBuffer::new(&mut scratch[..min(self.skip, 1024)]);
+            let done = f(&mut self.inner, &mut buffer)?;
+            let len = buffer.written().len();
+            self.skip -= len;
+            (done, len)
+        } else {
+            let mut buffer = PartialBuffer::new(output.unwritten_mut());
+            let done = f(&mut self.inner, &mut buffer)?;
+            let len = buffer.written().len();
+            output.advance(len);
+            (done, len)
+        };
+
+        self.block_len += len;
+
+        Ok(done)
+    }
+}
+
+impl Decode for BgzfDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Bgzf)
+    }
+
+    fn reinit(&mut self) -> Result<()> {
+        self.inner.reinit()?;
+        self.block_len = 0;
+        self.at_boundary = false;
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            let prior = input.written().len();
+
+            if self.with_output(output, |inner, output| inner.decode(input, output))? {
+                // Each block is an independent gzip member
+                let eof = self.block_len == 0;
+                self.inner.reinit()?;
+                self.block_len = 0;
+                self.at_boundary = true;
+
+                if eof {
+                    return Ok(true);
+                }
+            } else if input.written().len() != prior {
+                self.at_boundary = false;
+            }
+
+            if input.unwritten().is_empty() || output.unwritten().is_empty() {
+                return Ok(false);
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.with_output(output, |inner, output| inner.flush(output))
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // The end of file marker is optional, so stopping between any two blocks is fine.
+        if self.at_boundary {
+            return Ok(true);
+        }
+
+        self.with_output(output, |inner, output| inner.finish(output))
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    bgzf::VirtualOffset,
+    codec::{
+        bgzf::{EOF_MARKER, HEADER, MAX_BLOCK_DATA_SIZE, MAX_BLOCK_SIZE},
+        Encode,
+    },
+    util::PartialBuffer,
+};
+use std::{cmp::min, io::Result, mem};
+
+use flate2::{Compress, Compression, Crc, FlushCompress, Status};
+
+#[derive(Debug)]
+pub struct BgzfEncoder {
+    compress: Compress,
+    // Uncompressed data waiting to fill a block.
+    block: Vec<u8>,
+    // Compressed block waiting to be written out.
+    buffer: PartialBuffer<Vec<u8>>,
+    // Offset the next block will start at, the total size of all blocks so far.
+    compressed_offset: u64,
+    finished: bool,
+}
+
+impl BgzfEncoder {
+    pub(crate) fn new(level: Compression) -> Self {
+        Self {
+            compress: Compress::new(level, false),
+            block: Vec::with_capacity(MAX_BLOCK_DATA_SIZE),
+            buffer: PartialBuffer::new(Vec::new()),
+            compressed_offset: 0,
+            finished: false,
+        }
+    }
+
+    /// The virtual offset that the next byte of input will be at.
+    pub(crate) fn virtual_offset(&self) -> VirtualOffset {
+        VirtualOffset::new(self.compressed_offset, self.block.len() as u16)
+    }
+
+    /// Compresses the current block as a complete gzip member into `buffer`.
+    fn write_block(&mut self) -> Result<()> {
+        let mut output = mem::take(&mut self.buffer).into_inner();
+        output.clear();
+        output.extend_from_slice(&HEADER);
+        output.resize(MAX_BLOCK_SIZE - 8, 0);
+
+        self.compress.reset();
+        let status = self.compress.compress(
+            &self.block,
+            &mut output[HEADER.len()..],
+            FlushCompress::Finish,
+        )?;
+
+        let len = if status == Status::StreamEnd {
+            self.compress.total_out()
+        } else {
+            // Incompressible data can expand past the block size limit, so store it instead.
+            let mut stored = Compress::new(Compression::none(), false);
+            let status = stored.compress(
+                &self.block,
+                &mut output[HEADER.len()..],
+                FlushCompress::Finish,
+            )?;
+            // A stored block of `MAX_BLOCK_DATA_SIZE` bytes always fits.
+            assert_eq!(status, Status::StreamEnd);
+            stored.total_out()
+        };
+
+        output.truncate(HEADER.len() + len as usize);
+
+        let mut crc = Crc::new();
+        crc.update(&self.block);
+        output.extend_from_slice(&crc.sum().to_le_bytes());
+        output.extend_from_slice(&(self.block.len() as u32).to_le_bytes());
+
+        let block_size = (output.len() - 1) as u16;
+        output[16..18].copy_from_slice(&block_size.to_le_bytes());
+
+        self.compressed_offset += output.len() as u64;
+        self.block.clear();
+        self.buffer = output.into();
+
+        Ok(())
+    }
+}
+
+impl Encode for BgzfEncoder {
+    /// Starts a new file after the end of file marker, the offsets keep counting from the start
+    /// of the output so that they remain valid for all of it.
+    fn reinit(&mut self) -> Result<()> {
+        self.finished = false;
+        Ok(())
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        loop {
+            output.copy_unwritten_from(&mut self.buffer);
+
+            if !self.buffer.unwritten().is_empty() || input.unwritten().is_empty() {
+                return Ok(());
+            }
+
+            let len = min(
+                MAX_BLOCK_DATA_SIZE - self.block.len(),
+                input.unwritten().len(),
+            );
+            self.block.extend_from_slice(&input.unwritten()[..len]);
+            input.advance(len);
+
+            if self.block.len() == MAX_BLOCK_DATA_SIZE {
+                self.write_block()?;
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            output.copy_unwritten_from(&mut self.buffer);
+
+            if !self.buffer.unwritten().is_empty() {
+                return Ok(false);
+            }
+
+            if self.block.is_empty() {
+                return Ok(true);
+            }
+
+            self.write_block()?;
+        }
+    }
+
+    fn flush_with_mode(
+        &mut self,
+        _mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // Every flush ends the current block, and blocks are compressed independently of each
+        // other, so this is already a full flush.
+        self.flush(output)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            output.copy_unwritten_from(&mut self.buffer);
+
+            if !self.buffer.unwritten().is_empty() {
+                return Ok(false);
+            }
+
+            if !self.block.is_empty() {
+                self.write_block()?;
+            } else if !self.finished {
+                self.buffer = EOF_MARKER.to_vec().into();
+                self.compressed_offset += EOF_MARKER.len() as u64;
+                self.finished = true;
+            } else {
+                return Ok(true);
+            }
+        }
+    }
+}
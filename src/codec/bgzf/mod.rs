@@ -0,0 +1,22 @@
+mod decoder;
+mod encoder;
+
+pub(crate) use self::{decoder::BgzfDecoder, encoder::BgzfEncoder};
+
+// The most uncompressed data put in one block, the same as htslib uses so that even incompressible
+// data fits in a block.
+const MAX_BLOCK_DATA_SIZE: usize = 0xff00;
+
+// The largest total size of a block, so that its size fits in the `BC` subfield.
+const MAX_BLOCK_SIZE: usize = 0x1_0000;
+
+// Fixed gzip header with the `BC` extra subfield, the block size is filled in at offset 16.
+const HEADER: [u8; 18] = [
+    0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 0x06, 0, b'B', b'C', 0x02, 0, 0, 0,
+];
+
+// An empty block which marks the end of the file.
+const EOF_MARKER: [u8; 28] = [
+    0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 0x06, 0, b'B', b'C', 0x02, 0, 0x1b, 0, 0x03, 0, 0,
+    0, 0, 0, 0, 0, 0, 0,
+];
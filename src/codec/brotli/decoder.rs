@@ -1,8 +1,5 @@
-use crate::{codec::Decode, util::PartialBuffer};
-use std::{
-    fmt,
-    io::{Error, ErrorKind, Result},
-};
+use crate::{codec::Decode, error, util::PartialBuffer, Algorithm};
+use std::{fmt, io::Result};
 
 use brotli::{enc::StandardAlloc, BrotliDecompressStream, BrotliResult, BrotliState};
 
@@ -43,7 +40,10 @@ impl BrotliDecoder {
             &mut self.state,
         ) {
             BrotliResult::ResultFailure => {
-                return Err(Error::new(ErrorKind::Other, "brotli error"))
+                return Err(error::corrupt(
+                    Algorithm::Brotli,
+                    format!("{:?}", self.state.error_code),
+                ))
             }
             status => status,
         };
@@ -56,6 +56,10 @@ impl BrotliDecoder {
 }
 
 impl Decode for BrotliDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Brotli)
+    }
+
     fn reinit(&mut self) -> Result<()> {
         self.state = BrotliState::new(
             StandardAlloc::default(),
@@ -95,10 +99,7 @@ impl Decode for BrotliDecoder {
         match self.decode(&mut PartialBuffer::new(&[][..]), output)? {
             BrotliResult::ResultSuccess => Ok(true),
             BrotliResult::NeedsMoreOutput => Ok(false),
-            BrotliResult::NeedsMoreInput => Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "reached unexpected EOF",
-            )),
+            BrotliResult::NeedsMoreInput => Err(error::truncated(Algorithm::Brotli)),
             BrotliResult::ResultFailure => unreachable!(),
         }
     }
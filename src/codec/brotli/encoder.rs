@@ -60,6 +60,15 @@ impl BrotliEncoder {
 }
 
 impl Encode for BrotliEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        *self = Self::new(self.state.params.clone());
+        Ok(())
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
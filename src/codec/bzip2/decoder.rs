@@ -1,4 +1,4 @@
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{codec::Decode, error, util::PartialBuffer, Algorithm};
 use std::fmt;
 use std::io::{Error, ErrorKind, Result};
 
@@ -37,7 +37,12 @@ impl BzDecoder {
         let status = self
             .decompress
             .decompress(input.unwritten(), output.unwritten_mut())
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            .map_err(|e| match e {
+                bzip2::Error::Data | bzip2::Error::DataMagic => {
+                    error::corrupt(Algorithm::Bzip2, e.to_string())
+                }
+                e => Error::new(ErrorKind::Other, e),
+            })?;
 
         input.advance((self.decompress.total_in() - prior_in) as usize);
         output.advance((self.decompress.total_out() - prior_out) as usize);
@@ -47,6 +52,10 @@ impl BzDecoder {
 }
 
 impl Decode for BzDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Bzip2)
+    }
+
     fn reinit(&mut self) -> Result<()> {
         self.decompress = Decompress::new(false);
         Ok(())
@@ -75,7 +84,7 @@ impl Decode for BzDecoder {
 
             // There was insufficient memory in the input or output buffer to complete
             // the request, but otherwise everything went normally.
-            Status::MemNeeded => Err(Error::new(ErrorKind::Other, "out of memory")),
+            Status::MemNeeded => Err(error::memory_limit(Algorithm::Bzip2)),
         }
     }
 
@@ -1,4 +1,4 @@
-use crate::{codec::Encode, util::PartialBuffer};
+use crate::{codec::Encode, error, util::PartialBuffer, Algorithm};
 use std::fmt;
 use std::io::{Error, ErrorKind, Result};
 
@@ -6,6 +6,9 @@ use bzip2::{Action, Compress, Compression, Status};
 
 pub struct BzEncoder {
     compress: Compress,
+    // To create the stream again for each new member.
+    level: Compression,
+    work_factor: u32,
 }
 
 impl fmt::Debug for BzEncoder {
@@ -43,6 +46,8 @@ impl BzEncoder {
     pub(crate) fn new(level: Compression, work_factor: u32) -> Self {
         Self {
             compress: Compress::new(level, work_factor),
+            level,
+            work_factor,
         }
     }
 
@@ -68,6 +73,15 @@ impl BzEncoder {
 }
 
 impl Encode for BzEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.compress = Compress::new(self.level, self.work_factor);
+        Ok(())
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -91,7 +105,7 @@ impl Encode for BzEncoder {
 
             // There was insufficient memory in the input or output buffer to complete
             // the request, but otherwise everything went normally.
-            Status::MemNeeded => Err(Error::new(ErrorKind::Other, "out of memory")),
+            Status::MemNeeded => Err(error::memory_limit(Algorithm::Bzip2)),
         }
     }
 
@@ -117,10 +131,20 @@ impl Encode for BzEncoder {
 
             // There was insufficient memory in the input or output buffer to complete
             // the request, but otherwise everything went normally.
-            Status::MemNeeded => Err(Error::new(ErrorKind::Other, "out of memory")),
+            Status::MemNeeded => Err(error::memory_limit(Algorithm::Bzip2)),
         }
     }
 
+    fn flush_with_mode(
+        &mut self,
+        _mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // Every flush ends the current block, and blocks are compressed independently of each
+        // other, so this is already a full flush.
+        self.flush(output)
+    }
+
     fn finish(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
@@ -143,7 +167,7 @@ impl Encode for BzEncoder {
 
             // There was insufficient memory in the input or output buffer to complete
             // the request, but otherwise everything went normally.
-            Status::MemNeeded => Err(Error::new(ErrorKind::Other, "out of memory")),
+            Status::MemNeeded => Err(error::memory_limit(Algorithm::Bzip2)),
         }
     }
 }
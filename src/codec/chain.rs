@@ -0,0 +1,231 @@
+use crate::{
+    codec::{AnyDecoder, Decode},
+    util::PartialBuffer,
+    Algorithm,
+};
+use std::io::Result;
+
+/// How much decoded data is buffered between each pair of stages.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Decoding,
+    Finishing,
+    Done,
+}
+
+#[derive(Debug)]
+struct Stage {
+    decoder: AnyDecoder,
+    state: State,
+}
+
+impl Stage {
+    /// Runs this stage once, returning whether any progress was made.
+    fn step(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        eof: bool,
+        flush: bool,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let prior = (input.written().len(), output.written().len(), self.state);
+
+        if self.state == State::Decoding {
+            if !input.unwritten().is_empty() {
+                if self.decoder.decode(input, output)? {
+                    self.state = State::Finishing;
+                }
+            } else if eof {
+                self.state = State::Finishing;
+            } else if flush {
+                self.decoder.flush(output)?;
+            }
+        }
+
+        if self.state == State::Finishing && self.decoder.finish(output)? {
+            self.state = State::Done;
+        }
+
+        Ok(prior != (input.written().len(), output.written().len(), self.state))
+    }
+}
+
+/// The output of one stage waiting to be given to the next.
+#[derive(Debug)]
+struct Buffer {
+    data: Vec<u8>,
+    start: usize,
+    end: usize,
+}
+
+impl Buffer {
+    fn new() -> Self {
+        Self {
+            data: vec![0; BUFFER_SIZE],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    fn read<T>(&mut self, f: impl FnOnce(&mut PartialBuffer<&[u8]>) -> Result<T>) -> Result<T> {
+        let mut input = PartialBuffer::new(&self.data[self.start..self.end]);
+        let result = f(&mut input);
+        self.start += input.written().len();
+        result
+    }
+
+    fn write<T>(
+        &mut self,
+        f: impl FnOnce(&mut PartialBuffer<&mut [u8]>) -> Result<T>,
+    ) -> Result<T> {
+        // Make as much room as possible first
+        self.data.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+
+        let mut output = PartialBuffer::new(&mut self.data[self.end..]);
+        let result = f(&mut output);
+        self.end += output.written().len();
+        result
+    }
+}
+
+/// Decodes data which has had several algorithms applied to it in turn, such as an HTTP body with
+/// multiple content codings.
+#[derive(Debug)]
+pub struct ChainDecoder {
+    // In the order they are undone, the reverse of the order they were applied.
+    stages: Vec<Stage>,
+    // Between each pair of stages, so one fewer than there are stages.
+    buffers: Vec<Buffer>,
+}
+
+impl ChainDecoder {
+    /// `algorithms` are in the order they were applied, if there are none the data is passed
+    /// through unchanged.
+    pub(crate) fn new(algorithms: &[Algorithm]) -> Self {
+        let mut stages: Vec<_> = algorithms
+            .iter()
+            .rev()
+            .map(|&algorithm| Stage {
+                decoder: AnyDecoder::new(algorithm),
+                state: State::Decoding,
+            })
+            .collect();
+
+        if stages.is_empty() {
+            stages.push(Stage {
+                decoder: AnyDecoder::new(Algorithm::Identity),
+                state: State::Decoding,
+            });
+        }
+
+        let buffers = (1..stages.len()).map(|_| Buffer::new()).collect();
+
+        Self { stages, buffers }
+    }
+
+    /// Runs the stages until none of them can make any more progress.
+    fn run(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        eof: bool,
+        flush: bool,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        loop {
+            let mut progress = false;
+
+            for i in 0..self.stages.len() {
+                let upstream_done = if i == 0 {
+                    eof
+                } else {
+                    self.stages[i - 1].state == State::Done
+                };
+
+                let stage = &mut self.stages[i];
+                let (before, after) = self.buffers.split_at_mut(i);
+
+                // An inner coding can have several members, such as a gzip body from pigz, so once
+                // one ends any more data from the stage before it starts the next. The first stage
+                // is left done, whatever follows it is for the caller to deal with.
+                if stage.state == State::Done
+                    && matches!(before.last(), Some(buffer) if !buffer.is_empty())
+                {
+                    stage.decoder.reinit()?;
+                    stage.state = State::Decoding;
+                }
+
+                progress |= match (before.last_mut(), after.first_mut()) {
+                    (None, None) => stage.step(input, upstream_done, flush, output)?,
+                    (None, Some(next)) => {
+                        next.write(|next| stage.step(input, upstream_done, flush, next))?
+                    }
+                    (Some(previous), None) => previous
+                        .read(|previous| stage.step(previous, upstream_done, flush, output))?,
+                    (Some(previous), Some(next)) => previous.read(|previous| {
+                        next.write(|next| stage.step(previous, upstream_done, flush, next))
+                    })?,
+                };
+            }
+
+            // Anything left after the end of the data is left for the next member, if any
+            if !progress || self.is_done() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Whether every stage is done, not just the last, as earlier stages may still have more
+    /// members of a later stage's coding to give it.
+    fn is_done(&self) -> bool {
+        self.stages.iter().all(|stage| stage.state == State::Done)
+            && self.buffers.iter().all(Buffer::is_empty)
+    }
+}
+
+impl Decode for ChainDecoder {
+    fn reinit(&mut self) -> Result<()> {
+        // Stages still partway through their data are left to carry on with it
+        for stage in &mut self.stages {
+            if stage.state == State::Done {
+                stage.decoder.reinit()?;
+                stage.state = State::Decoding;
+            }
+        }
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.run(input, false, false, output)?;
+        Ok(self.is_done())
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.run(&mut PartialBuffer::new(&[][..]), false, true, output)?;
+        Ok(self.buffers.iter().all(Buffer::is_empty) && !output.unwritten().is_empty())
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if !self.is_done() {
+            self.run(&mut PartialBuffer::new(&[][..]), true, false, output)?;
+        }
+        Ok(self.is_done())
+    }
+}
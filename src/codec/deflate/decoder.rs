@@ -1,4 +1,4 @@
-use crate::util::PartialBuffer;
+use crate::{util::PartialBuffer, Algorithm};
 use std::io::Result;
 
 #[derive(Debug)]
@@ -9,12 +9,22 @@ pub struct DeflateDecoder {
 impl DeflateDecoder {
     pub(crate) fn new() -> Self {
         Self {
-            inner: crate::codec::FlateDecoder::new(false),
+            inner: crate::codec::FlateDecoder::new(false, Algorithm::Deflate),
         }
     }
+
+    pub(crate) fn with_dictionary(dictionary: &[u8]) -> Result<Self> {
+        Ok(Self {
+            inner: crate::codec::FlateDecoder::with_dictionary(dictionary, Algorithm::Deflate)?,
+        })
+    }
 }
 
 impl crate::codec::Decode for DeflateDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Deflate)
+    }
+
     fn reinit(&mut self) -> Result<()> {
         self.inner.reinit()?;
         Ok(())
@@ -14,9 +14,23 @@ impl DeflateEncoder {
             inner: crate::codec::FlateEncoder::new(level, false),
         }
     }
+
+    pub(crate) fn with_dictionary(level: Compression, dictionary: &[u8]) -> Result<Self> {
+        Ok(Self {
+            inner: crate::codec::FlateEncoder::with_dictionary(level, dictionary)?,
+        })
+    }
 }
 
 impl Encode for DeflateEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.inner.reinit()
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -32,6 +46,14 @@ impl Encode for DeflateEncoder {
         self.inner.flush(output)
     }
 
+    fn flush_with_mode(
+        &mut self,
+        mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.inner.flush_with_mode(mode, output)
+    }
+
     fn finish(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
@@ -1,22 +1,76 @@
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{
+    codec::{flate::window, Decode},
+    error,
+    util::PartialBuffer,
+    Algorithm,
+};
 use std::io::{Error, ErrorKind, Result};
 
+#[cfg(feature = "miniz_oxide")]
+use crate::codec::flate::miniz::{Decompress, FlushDecompress, Status};
+#[cfg(not(feature = "miniz_oxide"))]
 use flate2::{Decompress, FlushDecompress, Status};
 
 #[derive(Debug)]
 pub struct FlateDecoder {
+    // Which format the deflate stream is part of, for errors.
+    algorithm: Algorithm,
     zlib_header: bool,
     decompress: Decompress,
+    // Set again after each reinit, for raw deflate streams only.
+    dictionary: Option<Vec<u8>>,
 }
 
 impl FlateDecoder {
-    pub(crate) fn new(zlib_header: bool) -> Self {
+    pub(crate) fn new(zlib_header: bool, algorithm: Algorithm) -> Self {
         Self {
+            algorithm,
             zlib_header,
             decompress: Decompress::new(zlib_header),
+            dictionary: None,
         }
     }
 
+    /// Creates a raw deflate decoder for data which may refer back to data in `dictionary`.
+    pub(crate) fn with_dictionary(dictionary: &[u8], algorithm: Algorithm) -> Result<Self> {
+        let mut this = Self::new(false, algorithm);
+        this.set_dictionary(dictionary)?;
+        this.dictionary = Some(window(dictionary).to_vec());
+        Ok(this)
+    }
+
+    /// Sets the preset dictionary of a raw deflate stream, before anything has been decoded.
+    pub(crate) fn set_dictionary(&mut self, dictionary: &[u8]) -> Result<()> {
+        // Not all backends support setting a dictionary, but decoding a stored block containing
+        // it then throwing the output away is equivalent.
+        let dictionary = window(dictionary);
+        if dictionary.is_empty() {
+            return Ok(());
+        }
+
+        let len = dictionary.len() as u16;
+
+        let mut block = vec![0x00];
+        block.extend_from_slice(&len.to_le_bytes());
+        block.extend_from_slice(&(!len).to_le_bytes());
+        block.extend_from_slice(dictionary);
+
+        let mut input = PartialBuffer::new(block);
+        let mut output = PartialBuffer::new(vec![0; dictionary.len()]);
+        while !input.unwritten().is_empty() {
+            let prior = input.written().len();
+            self.decode(&mut input, &mut output, FlushDecompress::None)?;
+            if input.written().len() == prior {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "failed to set deflate dictionary",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     fn decode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -26,9 +80,10 @@ impl FlateDecoder {
         let prior_in = self.decompress.total_in();
         let prior_out = self.decompress.total_out();
 
-        let status =
-            self.decompress
-                .decompress(input.unwritten(), output.unwritten_mut(), flush)?;
+        let status = self
+            .decompress
+            .decompress(input.unwritten(), output.unwritten_mut(), flush)
+            .map_err(|e| error::from_library(self.algorithm, e))?;
 
         input.advance((self.decompress.total_in() - prior_in) as usize);
         output.advance((self.decompress.total_out() - prior_out) as usize);
@@ -40,6 +95,10 @@ impl FlateDecoder {
 impl Decode for FlateDecoder {
     fn reinit(&mut self) -> Result<()> {
         self.decompress.reset(self.zlib_header);
+        if let Some(dictionary) = self.dictionary.take() {
+            self.set_dictionary(&dictionary)?;
+            self.dictionary = Some(dictionary);
+        }
         Ok(())
     }
 
@@ -91,7 +150,8 @@ impl Decode for FlateDecoder {
         )? {
             Status::Ok => Ok(false),
             Status::StreamEnd => Ok(true),
-            Status::BufError => Err(Error::new(ErrorKind::Other, "unexpected BufError")),
+            // There is no more input, so the stream must have been cut short
+            Status::BufError => Err(error::truncated(self.algorithm)),
         }
     }
 }
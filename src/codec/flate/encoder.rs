@@ -1,22 +1,74 @@
-use crate::{codec::Encode, util::PartialBuffer};
+use crate::{
+    codec::{flate::window, Encode},
+    util::PartialBuffer,
+    FlushMode,
+};
 use std::io::{Error, ErrorKind, Result};
 
-use flate2::{Compress, Compression, FlushCompress, Status};
+#[cfg(feature = "miniz_oxide")]
+use crate::codec::flate::miniz::{Compress, FlushCompress, Status};
+use flate2::Compression;
+#[cfg(not(feature = "miniz_oxide"))]
+use flate2::{Compress, FlushCompress, Status};
+
+/// Orders the supported flush modes by how much they write out, each doing everything the ones
+/// before it do.
+fn strength(mode: FlushMode) -> u8 {
+    match mode {
+        FlushMode::Partial => 0,
+        FlushMode::Sync => 1,
+        _ => 2,
+    }
+}
 
 #[derive(Debug)]
 pub struct FlateEncoder {
     compress: Compress,
-    flushed: bool,
+    // The strongest flush done since anything was last encoded, if any.
+    flushed: Option<FlushMode>,
+    // Compressed again after each reinit.
+    dictionary: Option<Vec<u8>>,
 }
 
 impl FlateEncoder {
     pub(crate) fn new(level: Compression, zlib_header: bool) -> Self {
         Self {
             compress: Compress::new(level, zlib_header),
-            flushed: true,
+            flushed: Some(FlushMode::Full),
+            dictionary: None,
         }
     }
 
+    /// Creates a raw deflate encoder which may refer back to data in `dictionary`, which the
+    /// decoder must be given too.
+    pub(crate) fn with_dictionary(level: Compression, dictionary: &[u8]) -> Result<Self> {
+        let mut this = Self::new(level, false);
+        this.dictionary = Some(window(dictionary).to_vec());
+        this.prime()?;
+        Ok(this)
+    }
+
+    /// Not all backends support setting a dictionary, but compressing it then throwing the
+    /// output away is equivalent, as long as it's flushed to a byte boundary.
+    fn prime(&mut self) -> Result<()> {
+        let dictionary = match self.dictionary.take() {
+            Some(dictionary) => dictionary,
+            None => return Ok(()),
+        };
+
+        let mut input = PartialBuffer::new(&dictionary[..]);
+        loop {
+            let mut output = PartialBuffer::new([0; 1024]);
+            self.encode(&mut input, &mut output, FlushCompress::Sync)?;
+            if input.unwritten().is_empty() && !output.unwritten().is_empty() {
+                break;
+            }
+        }
+
+        self.dictionary = Some(dictionary);
+        Ok(())
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -38,12 +90,22 @@ impl FlateEncoder {
 }
 
 impl Encode for FlateEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.compress.reset();
+        self.flushed = Some(FlushMode::Full);
+        self.prime()
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<()> {
-        self.flushed = false;
+        self.flushed = None;
         match self.encode(input, output, FlushCompress::None)? {
             Status::Ok => Ok(()),
             Status::StreamEnd => unreachable!(),
@@ -55,17 +117,29 @@ impl Encode for FlateEncoder {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
+        self.flush_with_mode(FlushMode::Sync, output)
+    }
+
+    fn flush_with_mode(
+        &mut self,
+        mode: FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let flush = match mode {
+            FlushMode::Partial => FlushCompress::Partial,
+            FlushMode::Sync => FlushCompress::Sync,
+            FlushMode::Full => FlushCompress::Full,
+            _ => return Err(crate::flush::unsupported(mode)),
+        };
+
         // We need to keep track of whether we've already flushed otherwise we'll just keep writing
-        // out sync blocks continuously and probably never complete flushing.
-        if self.flushed {
+        // out sync blocks continuously and probably never complete flushing, but a stronger flush
+        // than the last one still has to be done.
+        if matches!(self.flushed, Some(flushed) if strength(flushed) >= strength(mode)) {
             return Ok(true);
         }
 
-        self.encode(
-            &mut PartialBuffer::new(&[][..]),
-            output,
-            FlushCompress::Sync,
-        )?;
+        self.encode(&mut PartialBuffer::new(&[][..]), output, flush)?;
 
         loop {
             let old_len = output.written().len();
@@ -79,7 +153,7 @@ impl Encode for FlateEncoder {
             }
         }
 
-        self.flushed = true;
+        self.flushed = Some(mode);
         Ok(!output.unwritten().is_empty())
     }
 
@@ -87,7 +161,7 @@ impl Encode for FlateEncoder {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.flushed = false;
+        self.flushed = None;
         match self.encode(
             &mut PartialBuffer::new(&[][..]),
             output,
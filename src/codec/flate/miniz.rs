@@ -0,0 +1,192 @@
+//! Stand-ins for the parts of `flate2`'s low-level API that `FlateEncoder` and `FlateDecoder`
+//! use, driving `miniz_oxide` directly so that these streams are pure Rust however `flate2`'s
+//! backend is configured by other crates in the dependency graph.
+
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+use flate2::Compression;
+use miniz_oxide::{
+    deflate::{
+        core::{create_comp_flags_from_zip_params, CompressorOxide},
+        stream::deflate,
+    },
+    inflate::stream::{inflate, InflateState},
+    DataFormat, MZError, MZFlush, MZStatus, StreamResult,
+};
+
+// `MZ_DEFAULT_WINDOW_BITS` in `miniz.h`, negated to select a raw deflate stream.
+const WINDOW_BITS: i32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Status {
+    Ok,
+    BufError,
+    StreamEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FlushCompress {
+    None,
+    Partial,
+    Sync,
+    Full,
+    Finish,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FlushDecompress {
+    None,
+    Sync,
+    Finish,
+}
+
+impl From<FlushCompress> for MZFlush {
+    fn from(flush: FlushCompress) -> Self {
+        match flush {
+            FlushCompress::None => MZFlush::None,
+            FlushCompress::Partial => MZFlush::Partial,
+            FlushCompress::Sync => MZFlush::Sync,
+            FlushCompress::Full => MZFlush::Full,
+            FlushCompress::Finish => MZFlush::Finish,
+        }
+    }
+}
+
+impl From<FlushDecompress> for MZFlush {
+    fn from(flush: FlushDecompress) -> Self {
+        match flush {
+            FlushDecompress::None => MZFlush::None,
+            FlushDecompress::Sync => MZFlush::Sync,
+            FlushDecompress::Finish => MZFlush::Finish,
+        }
+    }
+}
+
+fn status(result: &StreamResult, message: &'static str) -> Result<Status> {
+    match result.status {
+        Ok(MZStatus::Ok) => Ok(Status::Ok),
+        Ok(MZStatus::StreamEnd) => Ok(Status::StreamEnd),
+        Err(MZError::Buf) => Ok(Status::BufError),
+        Ok(MZStatus::NeedDict) | Err(_) => Err(Error::new(ErrorKind::InvalidData, message)),
+    }
+}
+
+pub(crate) struct Compress {
+    inner: Box<CompressorOxide>,
+    total_in: u64,
+    total_out: u64,
+}
+
+impl fmt::Debug for Compress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Compress")
+            .field("total_in", &self.total_in)
+            .field("total_out", &self.total_out)
+            .finish()
+    }
+}
+
+impl Compress {
+    pub(crate) fn new(level: Compression, zlib_header: bool) -> Self {
+        let window_bits = if zlib_header {
+            WINDOW_BITS
+        } else {
+            -WINDOW_BITS
+        };
+        let flags = create_comp_flags_from_zip_params(level.level() as i32, window_bits, 0);
+
+        Self {
+            inner: Box::new(CompressorOxide::new(flags)),
+            total_in: 0,
+            total_out: 0,
+        }
+    }
+
+    pub(crate) fn total_in(&self) -> u64 {
+        self.total_in
+    }
+
+    pub(crate) fn total_out(&self) -> u64 {
+        self.total_out
+    }
+
+    pub(crate) fn compress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+        flush: FlushCompress,
+    ) -> Result<Status> {
+        let result = deflate(&mut self.inner, input, output, flush.into());
+        self.total_in += result.bytes_consumed as u64;
+        self.total_out += result.bytes_written as u64;
+        status(&result, "deflate compression error")
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.inner.reset();
+        self.total_in = 0;
+        self.total_out = 0;
+    }
+}
+
+pub(crate) struct Decompress {
+    inner: Box<InflateState>,
+    total_in: u64,
+    total_out: u64,
+}
+
+impl fmt::Debug for Decompress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Decompress")
+            .field("total_in", &self.total_in)
+            .field("total_out", &self.total_out)
+            .finish()
+    }
+}
+
+fn data_format(zlib_header: bool) -> DataFormat {
+    if zlib_header {
+        DataFormat::Zlib
+    } else {
+        DataFormat::Raw
+    }
+}
+
+impl Decompress {
+    pub(crate) fn new(zlib_header: bool) -> Self {
+        Self {
+            inner: InflateState::new_boxed(data_format(zlib_header)),
+            total_in: 0,
+            total_out: 0,
+        }
+    }
+
+    pub(crate) fn total_in(&self) -> u64 {
+        self.total_in
+    }
+
+    pub(crate) fn total_out(&self) -> u64 {
+        self.total_out
+    }
+
+    pub(crate) fn reset(&mut self, zlib_header: bool) {
+        self.inner.reset(data_format(zlib_header));
+        self.total_in = 0;
+        self.total_out = 0;
+    }
+
+    pub(crate) fn decompress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+        flush: FlushDecompress,
+    ) -> Result<Status> {
+        let result = inflate(&mut self.inner, input, output, flush.into());
+        self.total_in += result.bytes_consumed as u64;
+        self.total_out += result.bytes_written as u64;
+        status(&result, "deflate decompression error")
+    }
+}
@@ -1,4 +1,14 @@
 mod decoder;
 mod encoder;
+#[cfg(feature = "miniz_oxide")]
+mod miniz;
 
 pub(crate) use self::{decoder::FlateDecoder, encoder::FlateEncoder};
+
+// Deflate can only refer back this far, so only the end of a longer preset dictionary is used.
+const WINDOW_SIZE: usize = 32 * 1024;
+
+/// The part of `dictionary` that will fit in the window.
+fn window(dictionary: &[u8]) -> &[u8] {
+    &dictionary[dictionary.len().saturating_sub(WINDOW_SIZE)..]
+}
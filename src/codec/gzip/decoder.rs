@@ -1,11 +1,12 @@
 use crate::{
-    codec::{
-        gzip::header::{self, Header},
-        Decode,
-    },
+    codec::{gzip::header, Decode},
+    error,
+    gzip::GzipHeader,
+    unshared::Unshared,
     util::PartialBuffer,
+    Algorithm,
 };
-use std::io::{Error, ErrorKind, Result};
+use std::io::Result;
 
 use flate2::Crc;
 
@@ -17,37 +18,29 @@ enum State {
     Done,
 }
 
+type HeaderCallback = Box<dyn FnMut(&GzipHeader) + Send>;
+
 #[derive(Debug)]
 pub struct GzipDecoder {
+    algorithm: Algorithm,
     inner: crate::codec::FlateDecoder,
     crc: Crc,
     state: State,
-    header: Header,
+    header: Option<GzipHeader>,
+    on_header: Option<Unshared<HeaderCallback>>,
 }
 
-fn check_footer(crc: &Crc, input: &[u8]) -> Result<()> {
+fn check_footer(algorithm: Algorithm, crc: &Crc, input: &[u8]) -> Result<()> {
     if input.len() < 8 {
-        return Err(Error::new(
-            ErrorKind::InvalidData,
-            "Invalid gzip footer length",
-        ));
+        return Err(error::corrupt(algorithm, "Invalid gzip footer length"));
     }
 
     let crc_sum = crc.sum().to_le_bytes();
     let bytes_read = crc.amount().to_le_bytes();
 
-    if crc_sum != input[0..4] {
-        return Err(Error::new(
-            ErrorKind::InvalidData,
-            "CRC computed does not match",
-        ));
-    }
-
-    if bytes_read != input[4..8] {
-        return Err(Error::new(
-            ErrorKind::InvalidData,
-            "amount of bytes read does not match",
-        ));
+    // Both the CRC and the amount of bytes read check the decoded data
+    if crc_sum != input[0..4] || bytes_read != input[4..8] {
+        return Err(error::checksum_mismatch(algorithm));
     }
 
     Ok(())
@@ -55,14 +48,29 @@ fn check_footer(crc: &Crc, input: &[u8]) -> Result<()> {
 
 impl GzipDecoder {
     pub(crate) fn new() -> Self {
+        Self::with_algorithm(Algorithm::Gzip)
+    }
+
+    /// For formats built on gzip, so that errors name them instead.
+    pub(crate) fn with_algorithm(algorithm: Algorithm) -> Self {
         Self {
-            inner: crate::codec::FlateDecoder::new(false),
+            algorithm,
+            inner: crate::codec::FlateDecoder::new(false, algorithm),
             crc: Crc::new(),
             state: State::Header(header::Parser::default()),
-            header: Header::default(),
+            header: None,
+            on_header: None,
         }
     }
 
+    pub(crate) fn header(&self) -> Option<&GzipHeader> {
+        self.header.as_ref()
+    }
+
+    pub(crate) fn on_header(&mut self, callback: impl FnMut(&GzipHeader) + Send + 'static) {
+        self.on_header = Some(Unshared::new(Box::new(callback)));
+    }
+
     fn process<I: AsRef<[u8]>, O: AsRef<[u8]> + AsMut<[u8]>>(
         &mut self,
         input: &mut PartialBuffer<I>,
@@ -72,8 +80,15 @@ impl GzipDecoder {
         loop {
             match &mut self.state {
                 State::Header(parser) => {
-                    if let Some(header) = parser.input(input)? {
-                        self.header = header;
+                    let algorithm = self.algorithm;
+                    let header = parser
+                        .input(input)
+                        .map_err(|e| error::from_library(algorithm, e))?;
+                    if let Some(header) = header {
+                        if let Some(on_header) = &mut self.on_header {
+                            (on_header.get_mut())(&header);
+                        }
+                        self.header = Some(header);
                         self.state = State::Decoding;
                     }
                 }
@@ -91,7 +106,7 @@ impl GzipDecoder {
                     footer.copy_unwritten_from(input);
 
                     if footer.unwritten().is_empty() {
-                        check_footer(&self.crc, footer.written())?;
+                        check_footer(self.algorithm, &self.crc, footer.written())?;
                         self.state = State::Done
                     }
                 }
@@ -111,11 +126,14 @@ impl GzipDecoder {
 }
 
 impl Decode for GzipDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(self.algorithm)
+    }
+
     fn reinit(&mut self) -> Result<()> {
         self.inner.reinit()?;
         self.crc = Crc::new();
         self.state = State::Header(header::Parser::default());
-        self.header = Header::default();
         Ok(())
     }
 
@@ -161,10 +179,7 @@ impl Decode for GzipDecoder {
         if let State::Done = self.state {
             Ok(true)
         } else {
-            Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "unexpected end of file",
-            ))
+            Err(error::truncated(self.algorithm))
         }
     }
 }
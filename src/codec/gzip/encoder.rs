@@ -1,4 +1,4 @@
-use crate::{codec::Encode, util::PartialBuffer};
+use crate::{codec::Encode, gzip::GzipHeader, util::PartialBuffer};
 use std::io::Result;
 
 use flate2::{Compression, Crc};
@@ -16,9 +16,11 @@ pub struct GzipEncoder {
     inner: crate::codec::FlateEncoder,
     crc: Crc,
     state: State,
+    // Written again at the start of each member.
+    header: Vec<u8>,
 }
 
-fn header(level: Compression) -> Vec<u8> {
+fn header(level: Compression, header: &GzipHeader) -> Vec<u8> {
     let level_byte = if level.level() >= Compression::best().level() {
         0x02
     } else if level.level() <= Compression::fast().level() {
@@ -27,15 +29,52 @@ fn header(level: Compression) -> Vec<u8> {
         0x00
     };
 
-    vec![0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, level_byte, 0xff]
+    let flags = u8::from(header.text)
+        | u8::from(header.header_crc) << 1
+        | u8::from(header.extra.is_some()) << 2
+        | u8::from(header.filename.is_some()) << 3
+        | u8::from(header.comment.is_some()) << 4;
+
+    let mut output = vec![0x1f, 0x8b, 0x08, flags];
+    output.extend_from_slice(&header.mtime.to_le_bytes());
+    output.extend_from_slice(&[level_byte, header.operating_system]);
+
+    if let Some(extra) = &header.extra {
+        output.extend_from_slice(&(extra.len() as u16).to_le_bytes());
+        output.extend_from_slice(extra);
+    }
+
+    if let Some(filename) = &header.filename {
+        output.extend_from_slice(filename);
+        output.push(0);
+    }
+
+    if let Some(comment) = &header.comment {
+        output.extend_from_slice(comment);
+        output.push(0);
+    }
+
+    if header.header_crc {
+        let mut crc = Crc::new();
+        crc.update(&output);
+        output.extend_from_slice(&(crc.sum() as u16).to_le_bytes());
+    }
+
+    output
 }
 
 impl GzipEncoder {
     pub(crate) fn new(level: Compression) -> Self {
+        Self::with_header(level, &GzipHeader::default())
+    }
+
+    pub(crate) fn with_header(level: Compression, header: &GzipHeader) -> Self {
+        let header = self::header(level, header);
         Self {
             inner: crate::codec::FlateEncoder::new(level, false),
             crc: Crc::new(),
-            state: State::Header(header(level).into()),
+            state: State::Header(header.clone().into()),
+            header,
         }
     }
 
@@ -50,6 +89,17 @@ impl GzipEncoder {
 }
 
 impl Encode for GzipEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.inner.reinit()?;
+        self.crc.reset();
+        self.state = State::Header(self.header.clone().into());
+        Ok(())
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -83,6 +133,14 @@ impl Encode for GzipEncoder {
     fn flush(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.flush_with_mode(crate::FlushMode::Sync, output)
+    }
+
+    fn flush_with_mode(
+        &mut self,
+        mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
         loop {
             let done = match &mut self.state {
@@ -95,7 +153,7 @@ impl Encode for GzipEncoder {
                     false
                 }
 
-                State::Encoding => self.inner.flush(output)?,
+                State::Encoding => self.inner.flush_with_mode(mode, output)?,
 
                 State::Footer(footer) => {
                     output.copy_unwritten_from(&mut *footer);
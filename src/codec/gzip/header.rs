@@ -1,6 +1,8 @@
-use crate::util::PartialBuffer;
+use crate::{error, gzip::GzipHeader, util::PartialBuffer, Algorithm};
 use std::io::{Error, ErrorKind, Result};
 
+use flate2::Crc;
+
 #[derive(Debug, Default)]
 struct Flags {
     ascii: bool,
@@ -10,11 +12,6 @@ struct Flags {
     comment: bool,
 }
 
-#[derive(Debug, Default)]
-pub(super) struct Header {
-    flags: Flags,
-}
-
 #[derive(Debug)]
 enum State {
     Fixed(PartialBuffer<[u8; 10]>),
@@ -32,13 +29,27 @@ impl Default for State {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub(super) struct Parser {
     state: State,
-    header: Header,
+    flags: Flags,
+    header: GzipHeader,
+    // Covers all the header bytes so far, to check against the optional `FHCRC` field.
+    crc: Crc,
 }
 
-impl Header {
+impl Default for Parser {
+    fn default() -> Self {
+        Self {
+            state: State::default(),
+            flags: Flags::default(),
+            header: GzipHeader::default(),
+            crc: Crc::new(),
+        }
+    }
+}
+
+impl Flags {
     fn parse(input: &[u8; 10]) -> Result<Self> {
         if input[0..3] != [0x1f, 0x8b, 0x08] {
             return Err(Error::new(ErrorKind::InvalidData, "Invalid gzip header"));
@@ -46,15 +57,26 @@ impl Header {
 
         let flag = input[3];
 
-        let flags = Flags {
+        Ok(Flags {
             ascii: (flag & 0b0000_0001) != 0,
             crc: (flag & 0b0000_0010) != 0,
             extra: (flag & 0b0000_0100) != 0,
             filename: (flag & 0b0000_1000) != 0,
             comment: (flag & 0b0001_0000) != 0,
-        };
+        })
+    }
+}
 
-        Ok(Header { flags })
+impl GzipHeader {
+    fn parse_fixed(flags: &Flags, input: &[u8; 10]) -> Self {
+        GzipHeader {
+            text: flags.ascii,
+            header_crc: flags.crc,
+            mtime: u32::from_le_bytes([input[4], input[5], input[6], input[7]]),
+            extra_flags: input[8],
+            operating_system: input[9],
+            ..GzipHeader::default()
+        }
     }
 }
 
@@ -62,14 +84,17 @@ impl Parser {
     pub(super) fn input(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
-    ) -> Result<Option<Header>> {
+    ) -> Result<Option<GzipHeader>> {
         loop {
             match &mut self.state {
                 State::Fixed(data) => {
                     data.copy_unwritten_from(input);
 
                     if data.unwritten().is_empty() {
-                        self.header = Header::parse(&data.take().into_inner())?;
+                        let data = data.take().into_inner();
+                        self.crc.update(&data);
+                        self.flags = Flags::parse(&data)?;
+                        self.header = GzipHeader::parse_fixed(&self.flags, &data);
                         self.state = State::ExtraLen(<_>::default());
                     } else {
                         return Ok(None);
@@ -77,7 +102,7 @@ impl Parser {
                 }
 
                 State::ExtraLen(data) => {
-                    if !self.header.flags.extra {
+                    if !self.flags.extra {
                         self.state = State::Filename(<_>::default());
                         continue;
                     }
@@ -85,7 +110,9 @@ impl Parser {
                     data.copy_unwritten_from(input);
 
                     if data.unwritten().is_empty() {
-                        let len = u16::from_be_bytes(data.take().into_inner());
+                        let data = data.take().into_inner();
+                        self.crc.update(&data);
+                        let len = u16::from_le_bytes(data);
                         self.state = State::Extra(vec![0; usize::from(len)].into());
                     } else {
                         return Ok(None);
@@ -96,6 +123,9 @@ impl Parser {
                     data.copy_unwritten_from(input);
 
                     if data.unwritten().is_empty() {
+                        let data = data.take().into_inner();
+                        self.crc.update(&data);
+                        self.header.extra = Some(data);
                         self.state = State::Filename(<_>::default());
                     } else {
                         return Ok(None);
@@ -103,7 +133,7 @@ impl Parser {
                 }
 
                 State::Filename(data) => {
-                    if !self.header.flags.filename {
+                    if !self.flags.filename {
                         self.state = State::Comment(<_>::default());
                         continue;
                     }
@@ -111,6 +141,9 @@ impl Parser {
                     if let Some(len) = memchr::memchr(0, input.unwritten()) {
                         data.extend_from_slice(&input.unwritten()[..len]);
                         input.advance(len + 1);
+                        self.crc.update(data);
+                        self.crc.update(&[0]);
+                        self.header.filename = Some(std::mem::take(data));
                         self.state = State::Comment(<_>::default());
                     } else {
                         data.extend_from_slice(input.unwritten());
@@ -120,7 +153,7 @@ impl Parser {
                 }
 
                 State::Comment(data) => {
-                    if !self.header.flags.comment {
+                    if !self.flags.comment {
                         self.state = State::Crc(<_>::default());
                         continue;
                     }
@@ -128,6 +161,9 @@ impl Parser {
                     if let Some(len) = memchr::memchr(0, input.unwritten()) {
                         data.extend_from_slice(&input.unwritten()[..len]);
                         input.advance(len + 1);
+                        self.crc.update(data);
+                        self.crc.update(&[0]);
+                        self.header.comment = Some(std::mem::take(data));
                         self.state = State::Crc(<_>::default());
                     } else {
                         data.extend_from_slice(input.unwritten());
@@ -137,7 +173,7 @@ impl Parser {
                 }
 
                 State::Crc(data) => {
-                    if !self.header.flags.crc {
+                    if !self.flags.crc {
                         self.state = State::Done;
                         return Ok(Some(std::mem::take(&mut self.header)));
                     }
@@ -145,6 +181,10 @@ impl Parser {
                     data.copy_unwritten_from(input);
 
                     if data.unwritten().is_empty() {
+                        if data.written() != (self.crc.sum() as u16).to_le_bytes() {
+                            return Err(error::checksum_mismatch(Algorithm::Gzip));
+                        }
+
                         self.state = State::Done;
                         return Ok(Some(std::mem::take(&mut self.header)));
                     } else {
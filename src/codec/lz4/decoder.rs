@@ -0,0 +1,151 @@
+use crate::{codec::Decode, error, unshared::Unshared, util::PartialBuffer, Algorithm};
+use std::{
+    fmt,
+    io::{Error, Result},
+    ptr,
+};
+
+use lz4::liblz4::{
+    check_error, LZ4FDecompressionContext, LZ4F_createDecompressionContext, LZ4F_decompress,
+    LZ4F_freeDecompressionContext, LZ4F_resetDecompressionContext, LZ4F_VERSION,
+};
+
+/// Classifies an error from liblz4 by its name, such as `ERROR_contentChecksum_invalid`.
+fn from_lz4(error: Error) -> Error {
+    let name = error.to_string();
+    if name.contains("Checksum_invalid") {
+        error::checksum_mismatch(Algorithm::Lz4)
+    } else if name.contains("allocation_failed") {
+        error::memory_limit(Algorithm::Lz4)
+    } else {
+        error::corrupt(Algorithm::Lz4, name)
+    }
+}
+
+struct DecoderContext {
+    ctx: LZ4FDecompressionContext,
+}
+
+impl DecoderContext {
+    fn new() -> Result<Self> {
+        let mut ctx = LZ4FDecompressionContext(ptr::null_mut());
+        check_error(unsafe { LZ4F_createDecompressionContext(&mut ctx, LZ4F_VERSION) })?;
+        Ok(Self { ctx })
+    }
+}
+
+impl Drop for DecoderContext {
+    fn drop(&mut self) {
+        unsafe { LZ4F_freeDecompressionContext(self.ctx) };
+    }
+}
+
+pub struct Lz4Decoder {
+    ctx: Unshared<DecoderContext>,
+    // The context resets itself at the end of a frame, so we need to track this separately.
+    frame_ended: bool,
+}
+
+impl fmt::Debug for Lz4Decoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lz4Decoder")
+            .field("decompress", &"<no debug>")
+            .field("frame_ended", &self.frame_ended)
+            .finish()
+    }
+}
+
+impl Lz4Decoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            ctx: Unshared::new(DecoderContext::new().unwrap()),
+            frame_ended: false,
+        }
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        let mut input_len = input.unwritten().len();
+        let mut output_len = output.unwritten().len();
+
+        let remaining = check_error(unsafe {
+            LZ4F_decompress(
+                self.ctx.get_mut().ctx,
+                output.unwritten_mut().as_mut_ptr(),
+                &mut output_len,
+                input.unwritten().as_ptr(),
+                &mut input_len,
+                ptr::null(),
+            )
+        })
+        .map_err(from_lz4)?;
+
+        input.advance(input_len);
+        output.advance(output_len);
+
+        if remaining == 0 {
+            self.frame_ended = true;
+        }
+
+        Ok(())
+    }
+}
+
+impl Decode for Lz4Decoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Lz4)
+    }
+
+    fn reinit(&mut self) -> Result<()> {
+        unsafe { LZ4F_resetDecompressionContext(self.ctx.get_mut().ctx) };
+        self.frame_ended = false;
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.decode(input, output)?;
+        Ok(self.frame_ended)
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            let old_len = output.written().len();
+            self.decode(&mut PartialBuffer::new(&[][..]), output)?;
+            if output.written().len() == old_len {
+                break;
+            }
+        }
+
+        Ok(!output.unwritten().is_empty())
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if self.frame_ended {
+            return Ok(true);
+        }
+
+        let old_len = output.written().len();
+        self.decode(&mut PartialBuffer::new(&[][..]), output)?;
+
+        if self.frame_ended {
+            Ok(true)
+        } else if output.written().len() != old_len {
+            Ok(false)
+        } else {
+            Err(error::truncated(Algorithm::Lz4))
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{codec::Encode, unshared::Unshared, util::PartialBuffer, FlushMode};
+use std::{cmp::min, fmt, io::Result, mem, ptr};
+
+use lz4::liblz4::{
+    check_error, BlockChecksum, BlockMode, BlockSize, ContentChecksum, FrameType,
+    LZ4FCompressionContext, LZ4FFrameInfo, LZ4FPreferences, LZ4F_compressBegin, LZ4F_compressBound,
+    LZ4F_compressEnd, LZ4F_compressUpdate, LZ4F_createCompressionContext, LZ4F_flush,
+    LZ4F_freeCompressionContext, LZ4F_VERSION,
+};
+
+// Maximum size of a frame header, `LZ4F_HEADER_SIZE_MAX` in `lz4frame.h`.
+const HEADER_SIZE_MAX: usize = 19;
+
+struct EncoderContext {
+    ctx: LZ4FCompressionContext,
+}
+
+impl EncoderContext {
+    fn new() -> Result<Self> {
+        let mut ctx = LZ4FCompressionContext(ptr::null_mut());
+        check_error(unsafe { LZ4F_createCompressionContext(&mut ctx, LZ4F_VERSION) })?;
+        Ok(Self { ctx })
+    }
+}
+
+impl Drop for EncoderContext {
+    fn drop(&mut self) {
+        unsafe { LZ4F_freeCompressionContext(self.ctx) };
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Header,
+    Encoding,
+    Done,
+}
+
+pub struct Lz4Encoder {
+    ctx: Unshared<EncoderContext>,
+    preferences: LZ4FPreferences,
+    state: State,
+    // The most input we pass to `LZ4F_compressUpdate` at once, so that its output is bounded by
+    // `bound`.
+    block_size: usize,
+    bound: usize,
+    // `LZ4F_*` calls need enough output space for their worst case result, when the caller gives
+    // us less than that we compress into here and copy out over multiple calls.
+    buffer: PartialBuffer<Vec<u8>>,
+}
+
+impl fmt::Debug for Lz4Encoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lz4Encoder")
+            .field("compress", &"<no debug>")
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl Lz4Encoder {
+    pub(crate) fn new(level: u32) -> Self {
+        let preferences = LZ4FPreferences {
+            frame_info: LZ4FFrameInfo {
+                block_size_id: BlockSize::Default,
+                block_mode: BlockMode::Linked,
+                content_checksum_flag: ContentChecksum::ChecksumEnabled,
+                frame_type: FrameType::Frame,
+                content_size: 0,
+                dict_id: 0,
+                block_checksum_flag: BlockChecksum::NoBlockChecksum,
+            },
+            compression_level: level,
+            auto_flush: 0,
+            favor_dec_speed: 0,
+            reserved: [0; 3],
+        };
+
+        let block_size = preferences.frame_info.block_size_id.get_size();
+        let bound = unsafe { LZ4F_compressBound(block_size, &preferences) };
+
+        Self {
+            ctx: Unshared::new(EncoderContext::new().unwrap()),
+            preferences,
+            state: State::Header,
+            block_size,
+            bound: bound.max(HEADER_SIZE_MAX),
+            buffer: PartialBuffer::new(Vec::new()),
+        }
+    }
+
+    /// Runs `f` directly against `output` if it has room for the worst case result, otherwise
+    /// against the internal buffer which is then drained into `output` as far as possible.
+    fn write(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        f: impl FnOnce(LZ4FCompressionContext, &LZ4FPreferences, &mut [u8]) -> usize,
+    ) -> Result<()> {
+        let ctx = self.ctx.get_mut().ctx;
+
+        if output.unwritten().len() >= self.bound {
+            let len = check_error(f(ctx, &self.preferences, output.unwritten_mut()))?;
+            output.advance(len);
+        } else {
+            let mut buffer = mem::take(&mut self.buffer).into_inner();
+            buffer.resize(self.bound, 0);
+            let len = check_error(f(ctx, &self.preferences, &mut buffer))?;
+            buffer.truncate(len);
+            self.buffer = PartialBuffer::new(buffer);
+            output.copy_unwritten_from(&mut self.buffer);
+        }
+
+        Ok(())
+    }
+
+    fn write_header(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        self.write(output, |ctx, preferences, dst| unsafe {
+            LZ4F_compressBegin(ctx, dst.as_mut_ptr(), dst.len(), preferences)
+        })
+    }
+}
+
+impl Encode for Lz4Encoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.state = State::Header;
+        Ok(())
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        loop {
+            output.copy_unwritten_from(&mut self.buffer);
+            if !self.buffer.unwritten().is_empty() {
+                return Ok(());
+            }
+
+            match self.state {
+                State::Header => {
+                    self.write_header(output)?;
+                    self.state = State::Encoding;
+                }
+
+                State::Encoding => {
+                    if input.unwritten().is_empty() {
+                        return Ok(());
+                    }
+
+                    let src = &input.unwritten()[..min(input.unwritten().len(), self.block_size)];
+                    self.write(output, |ctx, _, dst| unsafe {
+                        LZ4F_compressUpdate(
+                            ctx,
+                            dst.as_mut_ptr(),
+                            dst.len(),
+                            src.as_ptr(),
+                            src.len(),
+                            ptr::null(),
+                        )
+                    })?;
+                    input.advance(src.len());
+                }
+
+                State::Done => panic!("encode after complete"),
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            output.copy_unwritten_from(&mut self.buffer);
+            if !self.buffer.unwritten().is_empty() {
+                return Ok(false);
+            }
+
+            match self.state {
+                State::Header => {
+                    self.write_header(output)?;
+                    self.state = State::Encoding;
+                }
+
+                State::Encoding => {
+                    self.write(output, |ctx, _, dst| unsafe {
+                        LZ4F_flush(ctx, dst.as_mut_ptr(), dst.len(), ptr::null())
+                    })?;
+                    return Ok(self.buffer.unwritten().is_empty());
+                }
+
+                State::Done => return Ok(true),
+            }
+        }
+    }
+
+    /// A full or block flush ends the frame, then starts a new one.
+    fn flush_with_mode(
+        &mut self,
+        mode: FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        match mode {
+            // Nothing has been written since the last frame ended
+            FlushMode::Full | FlushMode::Block if matches!(self.state, State::Header) => Ok(true),
+            FlushMode::Full | FlushMode::Block => {
+                if !self.finish(output)? {
+                    return Ok(false);
+                }
+                self.reinit()?;
+                Ok(true)
+            }
+            _ => self.flush(output),
+        }
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            output.copy_unwritten_from(&mut self.buffer);
+            if !self.buffer.unwritten().is_empty() {
+                return Ok(false);
+            }
+
+            match self.state {
+                State::Header => {
+                    self.write_header(output)?;
+                    self.state = State::Encoding;
+                }
+
+                State::Encoding => {
+                    self.write(output, |ctx, _, dst| unsafe {
+                        LZ4F_compressEnd(ctx, dst.as_mut_ptr(), dst.len(), ptr::null())
+                    })?;
+                    self.state = State::Done;
+                }
+
+                State::Done => return Ok(true),
+            }
+        }
+    }
+}
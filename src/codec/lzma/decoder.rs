@@ -1,4 +1,4 @@
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{codec::Decode, util::PartialBuffer, Algorithm};
 
 use std::io::Result;
 
@@ -10,12 +10,16 @@ pub struct LzmaDecoder {
 impl LzmaDecoder {
     pub fn new() -> Self {
         Self {
-            inner: crate::codec::Xz2Decoder::new(),
+            inner: crate::codec::Xz2Decoder::new(Algorithm::Lzma),
         }
     }
 }
 
 impl Decode for LzmaDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Lzma)
+    }
+
     fn reinit(&mut self) -> Result<()> {
         self.inner.reinit()
     }
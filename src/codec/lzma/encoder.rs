@@ -16,6 +16,14 @@ impl LzmaEncoder {
 }
 
 impl Encode for LzmaEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.inner.reinit()
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
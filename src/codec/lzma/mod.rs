@@ -1,4 +1,7 @@
 mod decoder;
+#[cfg(feature = "lzma")]
 mod encoder;
 
-pub(crate) use self::{decoder::LzmaDecoder, encoder::LzmaEncoder};
+pub(crate) use self::decoder::LzmaDecoder;
+#[cfg(feature = "lzma")]
+pub(crate) use self::encoder::LzmaEncoder;
@@ -1,82 +1,194 @@
-use crate::util::PartialBuffer;
+//! The traits implemented by each compression format, these can be implemented for other formats
+//! and used with the generic `Encoder` and `Decoder` adapters in each IO module, e.g.
+//! [`futures::bufread::Encoder`](crate::futures::bufread::Encoder), to get the same IO handling as
+//! the built-in formats.
+//!
+//! Both traits work in terms of [`PartialBuffer`]s, the `input` buffer is advanced past the data
+//! consumed and the `output` buffer past the data produced. Each call should make as much
+//! progress as it can, but may return before either is exhausted, the adapters will call it again
+//! as long as there is more input and room for output.
+
+pub use crate::util::PartialBuffer;
 use std::io::Result;
 
+mod any;
+mod auto;
+#[cfg(feature = "bgzf")]
+mod bgzf;
 #[cfg(feature = "brotli")]
 mod brotli;
 #[cfg(feature = "bzip2")]
 mod bzip2;
+mod chain;
 #[cfg(feature = "deflate")]
 mod deflate;
 #[cfg(feature = "flate2")]
 mod flate;
 #[cfg(feature = "gzip")]
 mod gzip;
-#[cfg(feature = "lzma")]
+#[cfg(feature = "lz4")]
+mod lz4;
+#[cfg(any(feature = "lzma", feature = "lzma-rs"))]
 mod lzma;
-#[cfg(feature = "xz")]
+#[cfg(feature = "snappy")]
+mod snappy;
+#[cfg(any(feature = "xz", feature = "lzma-rs"))]
 mod xz;
-#[cfg(feature = "xz2")]
+#[cfg(any(feature = "xz2", feature = "lzma-rs"))]
 mod xz2;
 #[cfg(feature = "zlib")]
 mod zlib;
-#[cfg(feature = "zstd")]
+#[cfg(any(feature = "zstd", feature = "ruzstd"))]
 mod zstd;
 
+pub(crate) use self::any::{AnyDecoder, AnyEncoder};
+pub(crate) use self::auto::AutoDecoder;
+#[cfg(feature = "bgzf")]
+pub(crate) use self::bgzf::{BgzfDecoder, BgzfEncoder};
 #[cfg(feature = "brotli")]
 pub(crate) use self::brotli::{BrotliDecoder, BrotliEncoder};
 #[cfg(feature = "bzip2")]
 pub(crate) use self::bzip2::{BzDecoder, BzEncoder};
+pub(crate) use self::chain::ChainDecoder;
 #[cfg(feature = "deflate")]
 pub(crate) use self::deflate::{DeflateDecoder, DeflateEncoder};
 #[cfg(feature = "flate2")]
 pub(crate) use self::flate::{FlateDecoder, FlateEncoder};
 #[cfg(feature = "gzip")]
 pub(crate) use self::gzip::{GzipDecoder, GzipEncoder};
+#[cfg(feature = "lz4")]
+pub(crate) use self::lz4::{Lz4Decoder, Lz4Encoder};
+#[cfg(any(feature = "lzma", feature = "lzma-rs"))]
+pub(crate) use self::lzma::LzmaDecoder;
 #[cfg(feature = "lzma")]
-pub(crate) use self::lzma::{LzmaDecoder, LzmaEncoder};
+pub(crate) use self::lzma::LzmaEncoder;
+#[cfg(feature = "snappy")]
+pub(crate) use self::snappy::{SnappyDecoder, SnappyEncoder};
+#[cfg(any(feature = "xz", feature = "lzma-rs"))]
+pub(crate) use self::xz::XzDecoder;
 #[cfg(feature = "xz")]
-pub(crate) use self::xz::{XzDecoder, XzEncoder};
+pub(crate) use self::xz::XzEncoder;
+#[cfg(any(feature = "xz2", feature = "lzma-rs"))]
+pub(crate) use self::xz2::Xz2Decoder;
 #[cfg(feature = "xz2")]
-pub(crate) use self::xz2::{Xz2Decoder, Xz2Encoder, Xz2FileFormat};
+pub(crate) use self::xz2::{Xz2Encoder, Xz2FileFormat};
 #[cfg(feature = "zlib")]
-pub(crate) use self::zlib::{ZlibDecoder, ZlibEncoder};
+pub(crate) use self::zlib::{adler32, ZlibDecoder, ZlibEncoder};
+#[cfg(any(feature = "zstd", feature = "ruzstd"))]
+pub(crate) use self::zstd::ZstdDecoder;
 #[cfg(feature = "zstd")]
-pub(crate) use self::zstd::{ZstdDecoder, ZstdEncoder};
+pub(crate) use self::zstd::{
+    Step as ZstdSeekableStep, ZstdEncoder, ZstdSeekableDecoder, ZstdSeekableEncoder,
+};
+
+/// The error from ending a member/frame with an encoder which cannot start a new one.
+pub(crate) fn reinit_unsupported() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "this encoder cannot start a new member/frame",
+    )
+}
 
+/// A compressor, or anything else transforming uncompressed data into some encoded format.
 pub trait Encode {
+    /// Reinitializes this encoder ready to encode a new member/frame of data.
+    ///
+    /// This is called after [`finish`](Self::finish) completes when ending a member/frame partway
+    /// through the stream, the default fails as not every format can be split like this.
+    fn reinit(&mut self) -> Result<()> {
+        Err(reinit_unsupported())
+    }
+
+    /// Returns whether [`reinit`](Self::reinit) can start a new member/frame.
+    ///
+    /// This is checked before ending a member/frame partway through the stream, so that an
+    /// encoder which can't start another one fails without writing out the end of the current
+    /// one. Encoders which implement [`reinit`](Self::reinit) should return `true`.
+    fn can_reinit(&self) -> bool {
+        false
+    }
+
+    /// Encodes data from `input` into `output`.
+    ///
+    /// It is fine for this to buffer some of the input internally, rather than writing anything
+    /// to `output`, until it has enough to encode.
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<()>;
 
-    /// Returns whether the internal buffers are flushed
+    /// Writes any internally buffered data to `output`, such that everything encoded so far can
+    /// be decoded.
+    ///
+    /// Returns whether the internal buffers are flushed, if not this will be called again with
+    /// more room in `output`.
     fn flush(&mut self, output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>)
         -> Result<bool>;
 
-    /// Returns whether the internal buffers are flushed and the end of the stream is written
+    /// Like [`flush`](Self::flush), but doing the kind of flush given by `mode`, the default
+    /// treats [`FlushMode::Partial`](crate::FlushMode::Partial) the same as
+    /// [`FlushMode::Sync`](crate::FlushMode::Sync), which is what [`flush`](Self::flush)
+    /// should do, and fails for the others.
+    fn flush_with_mode(
+        &mut self,
+        mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        match mode {
+            crate::FlushMode::Partial | crate::FlushMode::Sync => self.flush(output),
+            _ => Err(crate::flush::unsupported(mode)),
+        }
+    }
+
+    /// Writes any internally buffered data and whatever marks the end of the stream to `output`,
+    /// nothing will be encoded after this.
+    ///
+    /// Returns whether the internal buffers are flushed and the end of the stream is written, if
+    /// not this will be called again with more room in `output`.
     fn finish(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool>;
 }
 
+/// A decompressor, or anything else transforming some encoded format back into the original data.
 pub trait Decode {
+    /// The algorithm this decodes, if it is one of the [`Algorithm`](crate::Algorithm)s, so
+    /// that errors can name it.
+    fn algorithm(&self) -> Option<crate::Algorithm> {
+        None
+    }
+
     /// Reinitializes this decoder ready to decode a new member/frame of data.
+    ///
+    /// This is called after [`finish`](Self::finish) completes when decoding multiple members,
+    /// any input after the end of the last member is passed to [`decode`](Self::decode) again.
     fn reinit(&mut self) -> Result<()>;
 
-    /// Returns whether the end of the stream has been read
+    /// Decodes data from `input` into `output`.
+    ///
+    /// Returns whether the end of the stream, or current member/frame, has been read. After this
+    /// returns `true` it must not consume any more input until [`reinit`](Self::reinit) is
+    /// called, so that the data following it is left in the underlying stream.
     fn decode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool>;
 
-    /// Returns whether the internal buffers are flushed
+    /// Writes whatever can be decoded from the input so far to `output`.
+    ///
+    /// Returns whether the internal buffers are flushed, if not this will be called again with
+    /// more room in `output`.
     fn flush(&mut self, output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>)
         -> Result<bool>;
 
-    /// Returns whether the internal buffers are flushed
+    /// Writes whatever remains to be decoded to `output` once the input has ended, this should
+    /// return an error if the input ended partway through the stream.
+    ///
+    /// Returns whether the internal buffers are flushed and the end of the stream has been
+    /// reached, if not this will be called again with more room in `output`.
     fn finish(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
@@ -0,0 +1,237 @@
+use crate::{
+    codec::{
+        snappy::{
+            masked_crc32c, COMPRESSED_DATA, MAX_BLOCK_SIZE, PADDING, STREAM_IDENTIFIER,
+            STREAM_IDENTIFIER_BODY, UNCOMPRESSED_DATA,
+        },
+        Decode,
+    },
+    error,
+    util::PartialBuffer,
+    Algorithm,
+};
+use std::{cmp::min, convert::TryInto, fmt, io::Result};
+
+use snap::raw::{decompress_len, max_compress_len, Decoder};
+
+#[derive(Debug)]
+enum State {
+    ChunkHeader(PartialBuffer<[u8; 4]>),
+    ChunkBody(u8, PartialBuffer<Vec<u8>>),
+    Skip(usize),
+    Output(PartialBuffer<Vec<u8>>),
+}
+
+pub struct SnappyDecoder {
+    decoder: Decoder,
+    state: State,
+    seen_identifier: bool,
+}
+
+impl fmt::Debug for SnappyDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnappyDecoder")
+            .field("state", &self.state)
+            .field("seen_identifier", &self.seen_identifier)
+            .finish()
+    }
+}
+
+fn check_crc(expected: &[u8], data: &[u8]) -> Result<()> {
+    if expected != masked_crc32c(data).to_le_bytes() {
+        return Err(error::checksum_mismatch(Algorithm::Snappy));
+    }
+
+    Ok(())
+}
+
+impl SnappyDecoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            decoder: Decoder::new(),
+            state: State::ChunkHeader(<_>::default()),
+            seen_identifier: false,
+        }
+    }
+
+    fn parse_header(&self, header: [u8; 4]) -> Result<State> {
+        let kind = header[0];
+        let len = u32::from_le_bytes([header[1], header[2], header[3], 0]) as usize;
+
+        if kind != STREAM_IDENTIFIER && !self.seen_identifier {
+            return Err(error::corrupt(
+                Algorithm::Snappy,
+                "snappy stream did not start with a stream identifier",
+            ));
+        }
+
+        let max_len = match kind {
+            STREAM_IDENTIFIER => STREAM_IDENTIFIER_BODY.len(),
+            COMPRESSED_DATA => 4 + max_compress_len(MAX_BLOCK_SIZE),
+            UNCOMPRESSED_DATA => 4 + MAX_BLOCK_SIZE,
+            PADDING | 0x80..=0xfd => return Ok(State::Skip(len)),
+            _ => {
+                return Err(error::corrupt(
+                    Algorithm::Snappy,
+                    "reserved unskippable snappy chunk type",
+                ))
+            }
+        };
+
+        let min_len = if kind == STREAM_IDENTIFIER {
+            max_len
+        } else {
+            4
+        };
+
+        if len < min_len || len > max_len {
+            return Err(error::corrupt(
+                Algorithm::Snappy,
+                "invalid snappy chunk length",
+            ));
+        }
+
+        Ok(State::ChunkBody(kind, vec![0; len].into()))
+    }
+
+    fn parse_body(&mut self, kind: u8, mut body: Vec<u8>) -> Result<State> {
+        match kind {
+            STREAM_IDENTIFIER => {
+                if body != STREAM_IDENTIFIER_BODY {
+                    return Err(error::corrupt(
+                        Algorithm::Snappy,
+                        "invalid snappy stream identifier",
+                    ));
+                }
+                self.seen_identifier = true;
+                Ok(State::ChunkHeader(<_>::default()))
+            }
+
+            COMPRESSED_DATA => {
+                let (crc, data) = body.split_at(4);
+                let len = decompress_len(data)
+                    .map_err(|e| error::corrupt(Algorithm::Snappy, e.to_string()))?;
+                if len > MAX_BLOCK_SIZE {
+                    return Err(error::corrupt(
+                        Algorithm::Snappy,
+                        "snappy chunk decompressed to more than 65536 bytes",
+                    ));
+                }
+
+                let mut output = vec![0; len];
+                self.decoder
+                    .decompress(data, &mut output)
+                    .map_err(|e| error::corrupt(Algorithm::Snappy, e.to_string()))?;
+                check_crc(crc, &output)?;
+                Ok(State::Output(output.into()))
+            }
+
+            UNCOMPRESSED_DATA => {
+                let crc: [u8; 4] = body[..4].try_into().unwrap();
+                body.drain(..4);
+                check_crc(&crc, &body)?;
+                Ok(State::Output(body.into()))
+            }
+
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Decode for SnappyDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Snappy)
+    }
+
+    fn reinit(&mut self) -> Result<()> {
+        self.state = State::ChunkHeader(<_>::default());
+        self.seen_identifier = false;
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            match &mut self.state {
+                State::ChunkHeader(header) => {
+                    header.copy_unwritten_from(input);
+
+                    if !header.unwritten().is_empty() {
+                        return Ok(false);
+                    }
+
+                    let header = header.take().into_inner();
+                    self.state = self.parse_header(header)?;
+                }
+
+                State::ChunkBody(kind, body) => {
+                    body.copy_unwritten_from(input);
+
+                    if !body.unwritten().is_empty() {
+                        return Ok(false);
+                    }
+
+                    let (kind, body) = (*kind, body.take().into_inner());
+                    self.state = self.parse_body(kind, body)?;
+                }
+
+                State::Skip(remaining) => {
+                    let len = min(*remaining, input.unwritten().len());
+                    input.advance(len);
+                    *remaining -= len;
+
+                    if *remaining != 0 {
+                        return Ok(false);
+                    }
+
+                    self.state = State::ChunkHeader(<_>::default());
+                }
+
+                State::Output(data) => {
+                    output.copy_unwritten_from(data);
+
+                    if !data.unwritten().is_empty() {
+                        return Ok(false);
+                    }
+
+                    self.state = State::ChunkHeader(<_>::default());
+                }
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if let State::Output(data) = &mut self.state {
+            output.copy_unwritten_from(data);
+
+            if !data.unwritten().is_empty() {
+                return Ok(false);
+            }
+
+            self.state = State::ChunkHeader(<_>::default());
+        }
+
+        Ok(true)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if !self.flush(output)? {
+            return Ok(false);
+        }
+
+        // The framing format has no end marker, so the stream may end at any chunk boundary.
+        match &self.state {
+            State::ChunkHeader(header) if header.written().is_empty() => Ok(true),
+            _ => Err(error::truncated(Algorithm::Snappy)),
+        }
+    }
+}
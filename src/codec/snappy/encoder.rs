@@ -0,0 +1,151 @@
+use crate::{
+    codec::{
+        snappy::{
+            masked_crc32c, COMPRESSED_DATA, MAX_BLOCK_SIZE, STREAM_IDENTIFIER,
+            STREAM_IDENTIFIER_BODY, UNCOMPRESSED_DATA,
+        },
+        Encode,
+    },
+    util::PartialBuffer,
+};
+use std::{cmp::min, fmt, io::Result, mem};
+
+use snap::raw::{max_compress_len, Encoder};
+
+pub struct SnappyEncoder {
+    encoder: Encoder,
+    // Uncompressed data waiting to fill a chunk.
+    block: Vec<u8>,
+    // Framed chunk(s) waiting to be written out, starts with the stream identifier.
+    buffer: PartialBuffer<Vec<u8>>,
+}
+
+impl fmt::Debug for SnappyEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnappyEncoder")
+            .field(
+                "block",
+                &format_args!("{}/{}", self.block.len(), MAX_BLOCK_SIZE),
+            )
+            .field("buffer", &self.buffer)
+            .finish()
+    }
+}
+
+impl SnappyEncoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            encoder: Encoder::new(),
+            block: Vec::with_capacity(MAX_BLOCK_SIZE),
+            buffer: Self::stream_identifier().into(),
+        }
+    }
+
+    /// The stream identifier chunk which starts every stream.
+    fn stream_identifier() -> Vec<u8> {
+        let mut header = vec![STREAM_IDENTIFIER];
+        header.extend_from_slice(&(STREAM_IDENTIFIER_BODY.len() as u32).to_le_bytes()[..3]);
+        header.extend_from_slice(STREAM_IDENTIFIER_BODY);
+        header
+    }
+
+    /// Frames the current block as a chunk into `buffer`, only compressing it if that saves at
+    /// least 12.5% of its size.
+    fn write_chunk(&mut self) -> Result<()> {
+        let mut chunk = mem::take(&mut self.buffer).into_inner();
+        chunk.clear();
+        chunk.resize(8 + max_compress_len(self.block.len()), 0);
+
+        let len = self.encoder.compress(&self.block, &mut chunk[8..])?;
+
+        let (kind, len) = if len < self.block.len() - self.block.len() / 8 {
+            chunk.truncate(8 + len);
+            (COMPRESSED_DATA, len)
+        } else {
+            chunk.truncate(8);
+            chunk.extend_from_slice(&self.block);
+            (UNCOMPRESSED_DATA, self.block.len())
+        };
+
+        chunk[0] = kind;
+        chunk[1..4].copy_from_slice(&(len as u32 + 4).to_le_bytes()[..3]);
+        chunk[4..8].copy_from_slice(&masked_crc32c(&self.block).to_le_bytes());
+
+        self.block.clear();
+        self.buffer = chunk.into();
+
+        Ok(())
+    }
+}
+
+impl Encode for SnappyEncoder {
+    /// Starts a new stream with its own stream identifier, which the framing format allows to
+    /// appear again partway through.
+    fn reinit(&mut self) -> Result<()> {
+        self.buffer = Self::stream_identifier().into();
+        Ok(())
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        loop {
+            output.copy_unwritten_from(&mut self.buffer);
+
+            if !self.buffer.unwritten().is_empty() || input.unwritten().is_empty() {
+                return Ok(());
+            }
+
+            let len = min(MAX_BLOCK_SIZE - self.block.len(), input.unwritten().len());
+            self.block.extend_from_slice(&input.unwritten()[..len]);
+            input.advance(len);
+
+            if self.block.len() == MAX_BLOCK_SIZE {
+                self.write_chunk()?;
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            output.copy_unwritten_from(&mut self.buffer);
+
+            if !self.buffer.unwritten().is_empty() {
+                return Ok(false);
+            }
+
+            if self.block.is_empty() {
+                return Ok(true);
+            }
+
+            self.write_chunk()?;
+        }
+    }
+
+    fn flush_with_mode(
+        &mut self,
+        _mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // Every flush ends the current chunk, and chunks are compressed independently of each
+        // other, so this is already a full flush.
+        self.flush(output)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // The framing format has no trailer, so finishing is just flushing the last chunk.
+        self.flush(output)
+    }
+}
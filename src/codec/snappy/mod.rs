@@ -0,0 +1,20 @@
+mod decoder;
+mod encoder;
+
+pub(crate) use self::{decoder::SnappyDecoder, encoder::SnappyEncoder};
+
+const STREAM_IDENTIFIER: u8 = 0xff;
+const COMPRESSED_DATA: u8 = 0x00;
+const UNCOMPRESSED_DATA: u8 = 0x01;
+const PADDING: u8 = 0xfe;
+
+const STREAM_IDENTIFIER_BODY: &[u8] = b"sNaPpY";
+
+/// The maximum amount of uncompressed data allowed in a single chunk.
+const MAX_BLOCK_SIZE: usize = 65_536;
+
+/// The CRC-32C of `data`, masked as described in the framing format specification.
+fn masked_crc32c(data: &[u8]) -> u32 {
+    let crc = crc32c::crc32c(data);
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
@@ -1,6 +1,6 @@
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{codec::Decode, error, util::PartialBuffer, Algorithm};
 
-use std::io::{Error, ErrorKind, Result};
+use std::io::Result;
 
 #[derive(Debug)]
 pub struct XzDecoder {
@@ -11,13 +11,17 @@ pub struct XzDecoder {
 impl XzDecoder {
     pub fn new() -> Self {
         Self {
-            inner: crate::codec::Xz2Decoder::new(),
+            inner: crate::codec::Xz2Decoder::new(Algorithm::Xz),
             skip_padding: None,
         }
     }
 }
 
 impl Decode for XzDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Xz)
+    }
+
     fn reinit(&mut self) -> Result<()> {
         self.skip_padding = Some(4);
         self.inner.reinit()
@@ -42,8 +46,8 @@ impl Decode for XzDecoder {
             // If this is non-padding then it cannot start with null bytes, so it must be invalid
             // padding
             if *count != 4 {
-                return Err(Error::new(
-                    ErrorKind::InvalidData,
+                return Err(error::corrupt(
+                    Algorithm::Xz,
                     "stream padding was not a multiple of 4 bytes",
                 ));
             }
@@ -16,6 +16,14 @@ impl XzEncoder {
 }
 
 impl Encode for XzEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.inner.reinit()
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -31,6 +39,14 @@ impl Encode for XzEncoder {
         self.inner.flush(output)
     }
 
+    fn flush_with_mode(
+        &mut self,
+        mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.inner.flush_with_mode(mode, output)
+    }
+
     fn finish(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
@@ -1,4 +1,7 @@
 mod decoder;
+#[cfg(feature = "xz")]
 mod encoder;
 
-pub(crate) use self::{decoder::XzDecoder, encoder::XzEncoder};
+pub(crate) use self::decoder::XzDecoder;
+#[cfg(feature = "xz")]
+pub(crate) use self::encoder::XzEncoder;
@@ -1,10 +1,11 @@
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{codec::Decode, error, util::PartialBuffer, Algorithm};
 
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::io::Result;
 use xz2::stream::{Action, Status, Stream};
 
 pub struct Xz2Decoder {
+    algorithm: Algorithm,
     stream: Stream,
 }
 
@@ -15,8 +16,9 @@ impl Debug for Xz2Decoder {
 }
 
 impl Xz2Decoder {
-    pub fn new() -> Self {
+    pub fn new(algorithm: Algorithm) -> Self {
         Self {
+            algorithm,
             stream: Stream::new_auto_decoder(u64::max_value(), 0).unwrap(),
         }
     }
@@ -24,7 +26,7 @@ impl Xz2Decoder {
 
 impl Decode for Xz2Decoder {
     fn reinit(&mut self) -> Result<()> {
-        *self = Self::new();
+        *self = Self::new(self.algorithm);
         Ok(())
     }
 
@@ -38,7 +40,8 @@ impl Decode for Xz2Decoder {
 
         let status = self
             .stream
-            .process(input.unwritten(), output.unwritten_mut(), Action::Run)?;
+            .process(input.unwritten(), output.unwritten_mut(), Action::Run)
+            .map_err(|e| super::error(self.algorithm, e))?;
 
         input.advance(self.stream.total_in() as usize - previous_in);
         output.advance(self.stream.total_out() as usize - previous_out);
@@ -47,10 +50,7 @@ impl Decode for Xz2Decoder {
             Status::Ok => Ok(false),
             Status::StreamEnd => Ok(true),
             Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "More memory needed",
-            )),
+            Status::MemNeeded => Err(error::memory_limit(self.algorithm)),
         }
     }
 
@@ -70,7 +70,8 @@ impl Decode for Xz2Decoder {
 
         let status = self
             .stream
-            .process(&[], output.unwritten_mut(), Action::Finish)?;
+            .process(&[], output.unwritten_mut(), Action::Finish)
+            .map_err(|e| super::error(self.algorithm, e))?;
 
         output.advance(self.stream.total_out() as usize - previous_out);
 
@@ -78,10 +79,7 @@ impl Decode for Xz2Decoder {
             Status::Ok => Ok(false),
             Status::StreamEnd => Ok(true),
             Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "More memory needed",
-            )),
+            Status::MemNeeded => Err(error::memory_limit(self.algorithm)),
         }
     }
 }
@@ -1,12 +1,16 @@
 use crate::codec::Xz2FileFormat;
-use crate::{codec::Encode, util::PartialBuffer};
+use crate::{codec::Encode, error, util::PartialBuffer, Algorithm, FlushMode};
 
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::io::Result;
 use xz2::stream::{Action, Check, LzmaOptions, Status, Stream};
 
 pub struct Xz2Encoder {
+    algorithm: Algorithm,
     stream: Stream,
+    // To create the stream again for each new member.
+    format: Xz2FileFormat,
+    level: u32,
 }
 
 impl Debug for Xz2Encoder {
@@ -17,18 +21,42 @@ impl Debug for Xz2Encoder {
 
 impl Xz2Encoder {
     pub fn new(format: Xz2FileFormat, level: u32) -> Self {
-        let stream = match format {
-            Xz2FileFormat::Xz => Stream::new_easy_encoder(level, Check::Crc64).unwrap(),
-            Xz2FileFormat::Lzma => {
-                Stream::new_lzma_encoder(&LzmaOptions::new_preset(level).unwrap()).unwrap()
-            }
-        };
+        let (algorithm, stream) = Self::stream(format, level);
+
+        Self {
+            algorithm,
+            stream,
+            format,
+            level,
+        }
+    }
 
-        Self { stream }
+    fn stream(format: Xz2FileFormat, level: u32) -> (Algorithm, Stream) {
+        match format {
+            #[cfg(feature = "xz")]
+            Xz2FileFormat::Xz => (
+                Algorithm::Xz,
+                Stream::new_easy_encoder(level, Check::Crc64).unwrap(),
+            ),
+            #[cfg(feature = "lzma")]
+            Xz2FileFormat::Lzma => (
+                Algorithm::Lzma,
+                Stream::new_lzma_encoder(&LzmaOptions::new_preset(level).unwrap()).unwrap(),
+            ),
+        }
     }
 }
 
 impl Encode for Xz2Encoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.stream = Self::stream(self.format, self.level).1;
+        Ok(())
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -39,7 +67,8 @@ impl Encode for Xz2Encoder {
 
         let status = self
             .stream
-            .process(input.unwritten(), output.unwritten_mut(), Action::Run)?;
+            .process(input.unwritten(), output.unwritten_mut(), Action::Run)
+            .map_err(|e| super::error(self.algorithm, e))?;
 
         input.advance(self.stream.total_in() as usize - previous_in);
         output.advance(self.stream.total_out() as usize - previous_out);
@@ -47,10 +76,7 @@ impl Encode for Xz2Encoder {
         match status {
             Status::Ok | Status::StreamEnd => Ok(()),
             Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "out of memory",
-            )),
+            Status::MemNeeded => Err(error::memory_limit(self.algorithm)),
         }
     }
 
@@ -58,11 +84,26 @@ impl Encode for Xz2Encoder {
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
+        self.flush_with_mode(FlushMode::Sync, output)
+    }
+
+    fn flush_with_mode(
+        &mut self,
+        mode: FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        // A full flush ends the current block, which is all a block flush needs.
+        let action = match mode {
+            FlushMode::Full | FlushMode::Block => Action::FullFlush,
+            _ => Action::SyncFlush,
+        };
+
         let previous_out = self.stream.total_out() as usize;
 
         let status = self
             .stream
-            .process(&[], output.unwritten_mut(), Action::SyncFlush)?;
+            .process(&[], output.unwritten_mut(), action)
+            .map_err(|e| super::error(self.algorithm, e))?;
 
         output.advance(self.stream.total_out() as usize - previous_out);
 
@@ -70,10 +111,7 @@ impl Encode for Xz2Encoder {
             Status::Ok => Ok(false),
             Status::StreamEnd => Ok(true),
             Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "out of memory",
-            )),
+            Status::MemNeeded => Err(error::memory_limit(self.algorithm)),
         }
     }
 
@@ -85,7 +123,8 @@ impl Encode for Xz2Encoder {
 
         let status = self
             .stream
-            .process(&[], output.unwritten_mut(), Action::Finish)?;
+            .process(&[], output.unwritten_mut(), Action::Finish)
+            .map_err(|e| super::error(self.algorithm, e))?;
 
         output.advance(self.stream.total_out() as usize - previous_out);
 
@@ -93,10 +132,7 @@ impl Encode for Xz2Encoder {
             Status::Ok => Ok(false),
             Status::StreamEnd => Ok(true),
             Status::GetCheck => panic!("Unexpected lzma integrity check"),
-            Status::MemNeeded => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "out of memory",
-            )),
+            Status::MemNeeded => Err(error::memory_limit(self.algorithm)),
         }
     }
 }
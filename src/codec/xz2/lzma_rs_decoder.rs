@@ -0,0 +1,405 @@
+use crate::{codec::Decode, error, util::PartialBuffer, Algorithm};
+
+use std::{
+    cmp::min,
+    fmt,
+    io::{Error, Result, Write},
+    mem,
+};
+
+use lzma_rs::decompress::Stream;
+
+// The first byte of the xz magic number, which is never a valid first byte of an lzma header.
+const XZ_MAGIC_START: u8 = 0xfd;
+
+const XZ_HEADER_MAGIC: [u8; 6] = [0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const XZ_FOOTER_MAGIC: [u8; 2] = [b'Y', b'Z'];
+
+// How much input is given to the lzma decoder at once, to bound how much output it produces
+// before that is written out.
+const LZMA_CHUNK_SIZE: usize = 256;
+
+// The lzma header and the first bytes of range coded data, which `lzma-rs` reads before it
+// starts decoding.
+const LZMA_START_LEN: u64 = 13 + 5;
+
+// `lzma-rs` can't decode xz streams incrementally, so each stream is buffered and decoded in one
+// go once all of it has been read, these bound how large a stream can be.
+const MAX_XZ_INPUT: usize = 32 << 20;
+const MAX_XZ_OUTPUT: u64 = 256 << 20;
+
+enum State {
+    Detect,
+    Lzma {
+        stream: Stream<Vec<u8>>,
+        // How many bytes `stream` has taken.
+        consumed: u64,
+    },
+    Xz {
+        input: Vec<u8>,
+        framer: XzFramer,
+    },
+    Output(PartialBuffer<Vec<u8>>),
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            State::Detect => f.write_str("Detect"),
+            State::Lzma { consumed, .. } => f.debug_tuple("Lzma").field(consumed).finish(),
+            State::Xz { input, framer } => f
+                .debug_tuple("Xz")
+                .field(&input.len())
+                .field(framer)
+                .finish(),
+            State::Output(output) => f.debug_tuple("Output").field(output).finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Part {
+    StreamHeader,
+    BlockHeader,
+    Chunk,
+    BlockEnd,
+    Index {
+        start: usize,
+        // How many more variable length integers are in the index, once the count has been read.
+        fields: Option<u64>,
+    },
+    Footer,
+}
+
+/// Finds the end of an xz stream, without decompressing it, by walking through the headers of the
+/// stream, its blocks and the lzma2 chunks within them, then its index.
+#[derive(Debug)]
+struct XzFramer {
+    part: Part,
+    // The offset in the stream of the next part.
+    pos: usize,
+    block_start: usize,
+    check_size: usize,
+    // The total size of the chunks once decompressed.
+    unpacked: u64,
+}
+
+impl XzFramer {
+    fn new() -> Self {
+        Self {
+            part: Part::StreamHeader,
+            pos: 0,
+            block_start: 0,
+            check_size: 0,
+            unpacked: 0,
+        }
+    }
+
+    /// Walks through as much of `stream` as is available, returning the length of the xz stream
+    /// at its start once all of it is available.
+    fn walk(&mut self, algorithm: Algorithm, stream: &[u8]) -> Result<Option<usize>> {
+        loop {
+            let rest = match stream.get(self.pos..) {
+                Some(rest) => rest,
+                None => return Ok(None),
+            };
+
+            match &mut self.part {
+                Part::StreamHeader => {
+                    if rest.len() < 12 {
+                        return Ok(None);
+                    }
+                    if rest[..6] != XZ_HEADER_MAGIC {
+                        return Err(error::corrupt(algorithm, "invalid xz stream header"));
+                    }
+                    self.check_size = match rest[7] & 0x0f {
+                        0 => 0,
+                        id => 4 << ((id - 1) / 3),
+                    };
+                    self.pos += 12;
+                    self.part = Part::BlockHeader;
+                }
+
+                Part::BlockHeader => match rest.first() {
+                    None => return Ok(None),
+                    Some(0) => {
+                        self.part = Part::Index {
+                            start: self.pos,
+                            fields: None,
+                        };
+                        self.pos += 1;
+                    }
+                    Some(&size) => {
+                        self.block_start = self.pos;
+                        self.pos += (usize::from(size) + 1) * 4;
+                        self.part = Part::Chunk;
+                    }
+                },
+
+                Part::Chunk => {
+                    let (header, packed, unpacked) = match rest {
+                        [] => return Ok(None),
+                        [0, ..] => {
+                            self.pos += 1;
+                            self.part = Part::BlockEnd;
+                            continue;
+                        }
+                        [1..=2, size @ ..] => match size {
+                            [a, b, ..] => {
+                                let size = usize::from(u16::from_be_bytes([*a, *b])) + 1;
+                                (3, size, size)
+                            }
+                            _ => return Ok(None),
+                        },
+                        [control @ 0x80..=0xff, sizes @ ..] => match sizes {
+                            [a, b, c, d, ..] => {
+                                let unpacked = usize::from(control & 0x1f) << 16
+                                    | usize::from(u16::from_be_bytes([*a, *b]));
+                                let packed = usize::from(u16::from_be_bytes([*c, *d]));
+                                let header = if *control >= 0xc0 { 6 } else { 5 };
+                                (header, packed + 1, unpacked + 1)
+                            }
+                            _ => return Ok(None),
+                        },
+                        _ => return Err(error::corrupt(algorithm, "invalid lzma2 chunk")),
+                    };
+                    self.unpacked += unpacked as u64;
+                    if self.unpacked > MAX_XZ_OUTPUT {
+                        return Err(error::memory_limit(algorithm));
+                    }
+                    self.pos += header + packed;
+                }
+
+                Part::BlockEnd => {
+                    self.pos += padding(self.pos - self.block_start) + self.check_size;
+                    self.part = Part::BlockHeader;
+                }
+
+                Part::Index {
+                    start,
+                    fields: Some(0),
+                } => {
+                    self.pos += padding(self.pos - *start) + 4;
+                    self.part = Part::Footer;
+                }
+
+                Part::Index { fields, .. } => {
+                    let mut value = 0;
+                    let mut len = 0;
+                    loop {
+                        let byte = match rest.get(len) {
+                            Some(&byte) => byte,
+                            None => return Ok(None),
+                        };
+                        if len == 9 {
+                            return Err(error::corrupt(algorithm, "invalid xz index"));
+                        }
+                        value |= u64::from(byte & 0x7f) << (7 * len);
+                        len += 1;
+                        if byte & 0x80 == 0 {
+                            break;
+                        }
+                    }
+                    self.pos += len;
+                    *fields = Some(match *fields {
+                        // Each record is an unpadded size and an uncompressed size
+                        None => value.saturating_mul(2),
+                        Some(fields) => fields - 1,
+                    });
+                }
+
+                Part::Footer => {
+                    if rest.len() < 12 {
+                        return Ok(None);
+                    }
+                    if rest[10..12] != XZ_FOOTER_MAGIC {
+                        return Err(error::corrupt(algorithm, "invalid xz stream footer"));
+                    }
+                    return Ok(Some(self.pos + 12));
+                }
+            }
+        }
+    }
+}
+
+/// How many bytes of padding follow `len` bytes to align them to 4 bytes.
+fn padding(len: usize) -> usize {
+    (4 - len % 4) % 4
+}
+
+fn from_lzma_rs(algorithm: Algorithm, error: lzma_rs::error::Error) -> Error {
+    match error {
+        lzma_rs::error::Error::IoError(error) | lzma_rs::error::Error::HeaderTooShort(error) => {
+            error::from_library(algorithm, error)
+        }
+        error => error::corrupt(algorithm, error.to_string()),
+    }
+}
+
+/// An xz and lzma decoder implemented by `lzma-rs`, detecting which format it is decoding like
+/// liblzma's auto decoder.
+#[derive(Debug)]
+pub struct Xz2Decoder {
+    algorithm: Algorithm,
+    state: State,
+}
+
+impl Xz2Decoder {
+    pub fn new(algorithm: Algorithm) -> Self {
+        Self {
+            algorithm,
+            state: State::Detect,
+        }
+    }
+
+    /// Writes out any data the lzma decoder has produced so far, returning whether it is all
+    /// written.
+    fn drain(&mut self, output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>) -> bool {
+        match &mut self.state {
+            State::Lzma { stream, .. } => {
+                let pending = stream.get_output_mut().unwrap();
+                let len = min(pending.len(), output.unwritten().len());
+                output.unwritten_mut()[..len].copy_from_slice(&pending[..len]);
+                output.advance(len);
+                pending.drain(..len);
+                pending.is_empty()
+            }
+
+            State::Output(pending) => {
+                output.copy_unwritten_from(pending);
+                pending.unwritten().is_empty()
+            }
+
+            State::Detect | State::Xz { .. } => true,
+        }
+    }
+
+    /// Finishes decoding the stream, leaving anything it decoded to still be written out.
+    fn end(&mut self) -> Result<()> {
+        let decoded = match mem::replace(&mut self.state, State::Detect) {
+            State::Detect => return Err(error::truncated(self.algorithm)),
+
+            State::Lzma { stream, .. } => stream
+                .finish()
+                .map_err(|e| from_lzma_rs(self.algorithm, e))?,
+
+            State::Xz { input, mut framer } => {
+                if framer.walk(self.algorithm, &input)? != Some(input.len()) {
+                    return Err(error::truncated(self.algorithm));
+                }
+                let mut decoded = Vec::new();
+                lzma_rs::xz_decompress(&mut &input[..], &mut decoded)
+                    .map_err(|e| from_lzma_rs(self.algorithm, e))?;
+                decoded
+            }
+
+            State::Output(pending) => {
+                self.state = State::Output(pending);
+                return Ok(());
+            }
+        };
+
+        self.state = State::Output(decoded.into());
+        Ok(())
+    }
+}
+
+impl Decode for Xz2Decoder {
+    fn reinit(&mut self) -> Result<()> {
+        *self = Self::new(self.algorithm);
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let algorithm = self.algorithm;
+
+        loop {
+            if !self.drain(output) {
+                return Ok(false);
+            }
+
+            match &mut self.state {
+                State::Detect => match input.unwritten().first() {
+                    Some(&XZ_MAGIC_START) => {
+                        self.state = State::Xz {
+                            input: Vec::new(),
+                            framer: XzFramer::new(),
+                        }
+                    }
+                    Some(_) => {
+                        self.state = State::Lzma {
+                            stream: Stream::new(Vec::new()),
+                            consumed: 0,
+                        }
+                    }
+                    None => return Ok(false),
+                },
+
+                State::Lzma { stream, consumed } => {
+                    if input.unwritten().is_empty() {
+                        return Ok(false);
+                    }
+
+                    let len = min(LZMA_CHUNK_SIZE, input.unwritten().len());
+                    let written = stream
+                        .write(&input.unwritten()[..len])
+                        .map_err(|e| error::from_library(algorithm, e))?;
+                    input.advance(written);
+
+                    // Once past the header `lzma-rs` only stops taking input at the end of the
+                    // stream
+                    let ended = written < len && *consumed >= LZMA_START_LEN;
+                    *consumed += written as u64;
+                    if ended {
+                        self.end()?;
+                        return Ok(true);
+                    }
+                }
+
+                State::Xz {
+                    input: buffer,
+                    framer,
+                } => {
+                    let buffered = buffer.len();
+                    buffer.extend_from_slice(input.unwritten());
+                    match framer.walk(algorithm, buffer)? {
+                        Some(end) => {
+                            buffer.truncate(end);
+                            input.advance(end - buffered);
+                            self.end()?;
+                            return Ok(true);
+                        }
+                        None => {
+                            input.advance(input.unwritten().len());
+                            if buffer.len() > MAX_XZ_INPUT {
+                                return Err(error::memory_limit(algorithm));
+                            }
+                            return Ok(false);
+                        }
+                    }
+                }
+
+                State::Output(_) => return Ok(false),
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        Ok(self.drain(output))
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.end()?;
+        Ok(self.drain(output))
+    }
+}
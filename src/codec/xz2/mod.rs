@@ -1,9 +1,34 @@
+#[cfg(feature = "xz2")]
 mod decoder;
+#[cfg(feature = "xz2")]
 mod encoder;
+#[cfg(not(feature = "xz2"))]
+mod lzma_rs_decoder;
 
+#[cfg(feature = "xz2")]
+#[derive(Clone, Copy)]
 pub enum Xz2FileFormat {
+    #[cfg(feature = "xz")]
     Xz,
+    #[cfg(feature = "lzma")]
     Lzma,
 }
 
+#[cfg(feature = "xz2")]
+fn error(algorithm: crate::Algorithm, error: xz2::stream::Error) -> std::io::Error {
+    use xz2::stream::Error;
+
+    match error {
+        Error::Data | Error::Format => crate::error::corrupt(algorithm, error.to_string()),
+        Error::Mem | Error::MemLimit => crate::error::memory_limit(algorithm),
+        Error::Options | Error::NoCheck | Error::UnsupportedCheck => {
+            crate::error::unsupported(algorithm, error.to_string())
+        }
+        Error::Program => error.into(),
+    }
+}
+
+#[cfg(not(feature = "xz2"))]
+pub(crate) use self::lzma_rs_decoder::Xz2Decoder;
+#[cfg(feature = "xz2")]
 pub(crate) use self::{decoder::Xz2Decoder, encoder::Xz2Encoder};
@@ -1,22 +1,135 @@
-use crate::util::PartialBuffer;
-use std::io::Result;
+use crate::{
+    codec::{
+        zlib::{adler32, Adler32, FDICT},
+        Decode,
+    },
+    error,
+    unshared::Unshared,
+    util::PartialBuffer,
+    Algorithm,
+};
+use std::{fmt, io::Result};
+
+#[derive(Debug)]
+enum State {
+    Header(PartialBuffer<[u8; 2]>),
+    DictionaryId(PartialBuffer<[u8; 4]>),
+    Decoding,
+    Footer(PartialBuffer<[u8; 4]>),
+    Done,
+}
+
+type DictionaryCallback = Box<dyn FnMut(u32) -> Option<Vec<u8>> + Send>;
+
+enum Dictionary {
+    Fixed { id: u32, dictionary: Vec<u8> },
+    Callback(Unshared<DictionaryCallback>),
+}
+
+impl fmt::Debug for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dictionary::Fixed { id, .. } => f.debug_struct("Fixed").field("id", id).finish(),
+            Dictionary::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct ZlibDecoder {
     inner: crate::codec::FlateDecoder,
+    adler: Adler32,
+    state: State,
+    dictionary: Option<Dictionary>,
+}
+
+/// Checks the header is valid, returning whether it is followed by a dictionary id.
+fn check_header(header: [u8; 2]) -> Result<bool> {
+    if header[0] & 0x0f != 8 || header[0] >> 4 > 7 {
+        return Err(error::corrupt(
+            Algorithm::Zlib,
+            "invalid zlib compression method",
+        ));
+    }
+
+    // The header as a big-endian number must be a multiple of 31
+    let check = u16::from_be_bytes(header) % 31;
+    if check != 0 {
+        return Err(error::corrupt(
+            Algorithm::Zlib,
+            "invalid zlib header check bits",
+        ));
+    }
+
+    Ok(header[1] & FDICT != 0)
 }
 
 impl ZlibDecoder {
     pub(crate) fn new() -> Self {
         Self {
-            inner: crate::codec::FlateDecoder::new(true),
+            inner: crate::codec::FlateDecoder::new(false, Algorithm::Zlib),
+            adler: Adler32::new(),
+            state: State::Header(<_>::default()),
+            dictionary: None,
+        }
+    }
+
+    pub(crate) fn with_dictionary(dictionary: &[u8]) -> Self {
+        Self {
+            dictionary: Some(Dictionary::Fixed {
+                id: adler32(dictionary),
+                dictionary: dictionary.to_vec(),
+            }),
+            ..Self::new()
+        }
+    }
+
+    pub(crate) fn with_dictionary_callback(
+        callback: impl FnMut(u32) -> Option<Vec<u8>> + Send + 'static,
+    ) -> Self {
+        Self {
+            dictionary: Some(Dictionary::Callback(Unshared::new(Box::new(callback)))),
+            ..Self::new()
+        }
+    }
+
+    fn set_dictionary(&mut self, id: u32) -> Result<()> {
+        let unknown = || {
+            error::unsupported(
+                Algorithm::Zlib,
+                format!("preset dictionary with id {:#010x}", id),
+            )
+        };
+
+        match &mut self.dictionary {
+            Some(Dictionary::Fixed {
+                id: expected,
+                dictionary,
+            }) if *expected == id => self.inner.set_dictionary(dictionary),
+
+            Some(Dictionary::Fixed { .. }) => Err(unknown()),
+
+            Some(Dictionary::Callback(callback)) => match (callback.get_mut())(id) {
+                Some(dictionary) if adler32(&dictionary) == id => {
+                    self.inner.set_dictionary(&dictionary)
+                }
+                Some(_) | None => Err(unknown()),
+            },
+
+            None => Err(unknown()),
         }
     }
 }
 
-impl crate::codec::Decode for ZlibDecoder {
+impl Decode for ZlibDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Zlib)
+    }
+
     fn reinit(&mut self) -> Result<()> {
         self.inner.reinit()?;
+        self.adler = Adler32::new();
+        self.state = State::Header(<_>::default());
         Ok(())
     }
 
@@ -25,20 +138,98 @@ impl crate::codec::Decode for ZlibDecoder {
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.inner.decode(input, output)
+        loop {
+            match &mut self.state {
+                State::Header(header) => {
+                    header.copy_unwritten_from(input);
+
+                    if header.unwritten().is_empty() {
+                        self.state = if check_header(header.take().into_inner())? {
+                            State::DictionaryId(<_>::default())
+                        } else {
+                            State::Decoding
+                        };
+                    }
+                }
+
+                State::DictionaryId(id) => {
+                    id.copy_unwritten_from(input);
+
+                    if id.unwritten().is_empty() {
+                        let id = u32::from_be_bytes(id.take().into_inner());
+                        self.set_dictionary(id)?;
+                        self.state = State::Decoding;
+                    }
+                }
+
+                State::Decoding => {
+                    let prior = output.written().len();
+                    let done = self.inner.decode(input, output)?;
+                    self.adler.update(&output.written()[prior..]);
+                    if done {
+                        self.state = State::Footer(<_>::default());
+                    }
+                }
+
+                State::Footer(footer) => {
+                    footer.copy_unwritten_from(input);
+
+                    if footer.unwritten().is_empty() {
+                        if footer.written() != self.adler.sum().to_be_bytes() {
+                            return Err(error::checksum_mismatch(Algorithm::Zlib));
+                        }
+                        self.state = State::Done;
+                    }
+                }
+
+                State::Done => {}
+            };
+
+            if let State::Done = self.state {
+                return Ok(true);
+            }
+
+            if input.unwritten().is_empty() || output.unwritten().is_empty() {
+                return Ok(false);
+            }
+        }
     }
 
     fn flush(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.inner.flush(output)
+        loop {
+            match self.state {
+                State::Header(_) | State::DictionaryId(_) | State::Footer(_) | State::Done => {
+                    return Ok(true)
+                }
+
+                State::Decoding => {
+                    let prior = output.written().len();
+                    let done = self.inner.flush(output)?;
+                    self.adler.update(&output.written()[prior..]);
+                    if done {
+                        return Ok(true);
+                    }
+                }
+            };
+
+            if output.unwritten().is_empty() {
+                return Ok(false);
+            }
+        }
     }
 
     fn finish(
         &mut self,
-        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.inner.finish(output)
+        // Because of the footer we have to have already flushed all the data out before we get here
+        if let State::Done = self.state {
+            Ok(true)
+        } else {
+            Err(error::truncated(Algorithm::Zlib))
+        }
     }
 }
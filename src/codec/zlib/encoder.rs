@@ -1,41 +1,206 @@
-use crate::{codec::Encode, util::PartialBuffer};
+use crate::{
+    codec::{
+        zlib::{adler32, Adler32, CMF, FDICT},
+        Encode,
+    },
+    util::PartialBuffer,
+};
 use std::io::Result;
 
 use flate2::Compression;
 
+#[derive(Debug)]
+enum State {
+    Header(PartialBuffer<Vec<u8>>),
+    Encoding,
+    Footer(PartialBuffer<Vec<u8>>),
+    Done,
+}
+
 #[derive(Debug)]
 pub struct ZlibEncoder {
     inner: crate::codec::FlateEncoder,
+    adler: Adler32,
+    state: State,
+    // Written again at the start of each member.
+    header: Vec<u8>,
+}
+
+fn header(level: Compression, dictionary: Option<&[u8]>) -> Vec<u8> {
+    // The same mapping from level to `FLEVEL` that zlib uses.
+    let level_flags = match level.level() {
+        0..=1 => 0,
+        2..=5 => 1,
+        6 => 2,
+        _ => 3,
+    };
+
+    let mut flags = level_flags << 6;
+    if dictionary.is_some() {
+        flags |= FDICT;
+    }
+    flags += 31 - ((u16::from(CMF) << 8 | u16::from(flags)) % 31) as u8;
+
+    let mut output = vec![CMF, flags];
+    if let Some(dictionary) = dictionary {
+        output.extend_from_slice(&adler32(dictionary).to_be_bytes());
+    }
+
+    output
 }
 
 impl ZlibEncoder {
     pub(crate) fn new(level: Compression) -> Self {
+        let header = self::header(level, None);
         Self {
-            inner: crate::codec::FlateEncoder::new(level, true),
+            inner: crate::codec::FlateEncoder::new(level, false),
+            adler: Adler32::new(),
+            state: State::Header(header.clone().into()),
+            header,
         }
     }
+
+    pub(crate) fn with_dictionary(level: Compression, dictionary: &[u8]) -> Result<Self> {
+        let header = self::header(level, Some(dictionary));
+        Ok(Self {
+            inner: crate::codec::FlateEncoder::with_dictionary(level, dictionary)?,
+            adler: Adler32::new(),
+            state: State::Header(header.clone().into()),
+            header,
+        })
+    }
 }
 
 impl Encode for ZlibEncoder {
+    fn reinit(&mut self) -> Result<()> {
+        self.inner.reinit()?;
+        self.adler = Adler32::new();
+        self.state = State::Header(self.header.clone().into());
+        Ok(())
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<()> {
-        self.inner.encode(input, output)
+        loop {
+            match &mut self.state {
+                State::Header(header) => {
+                    output.copy_unwritten_from(&mut *header);
+
+                    if header.unwritten().is_empty() {
+                        self.state = State::Encoding;
+                    }
+                }
+
+                State::Encoding => {
+                    let prior_written = input.written().len();
+                    self.inner.encode(input, output)?;
+                    self.adler.update(&input.written()[prior_written..]);
+                }
+
+                State::Footer(_) | State::Done => panic!("encode after complete"),
+            };
+
+            if input.unwritten().is_empty() || output.unwritten().is_empty() {
+                return Ok(());
+            }
+        }
     }
 
     fn flush(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.inner.flush(output)
+        self.flush_with_mode(crate::FlushMode::Sync, output)
+    }
+
+    fn flush_with_mode(
+        &mut self,
+        mode: crate::FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            let done = match &mut self.state {
+                State::Header(header) => {
+                    output.copy_unwritten_from(&mut *header);
+
+                    if header.unwritten().is_empty() {
+                        self.state = State::Encoding;
+                    }
+                    false
+                }
+
+                State::Encoding => self.inner.flush_with_mode(mode, output)?,
+
+                State::Footer(footer) => {
+                    output.copy_unwritten_from(&mut *footer);
+
+                    if footer.unwritten().is_empty() {
+                        self.state = State::Done;
+                        true
+                    } else {
+                        false
+                    }
+                }
+
+                State::Done => true,
+            };
+
+            if done {
+                return Ok(true);
+            }
+
+            if output.unwritten().is_empty() {
+                return Ok(false);
+            }
+        }
     }
 
     fn finish(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        self.inner.finish(output)
+        loop {
+            match &mut self.state {
+                State::Header(header) => {
+                    output.copy_unwritten_from(&mut *header);
+
+                    if header.unwritten().is_empty() {
+                        self.state = State::Encoding;
+                    }
+                }
+
+                State::Encoding => {
+                    if self.inner.finish(output)? {
+                        let footer = self.adler.sum().to_be_bytes().to_vec();
+                        self.state = State::Footer(footer.into());
+                    }
+                }
+
+                State::Footer(footer) => {
+                    output.copy_unwritten_from(&mut *footer);
+
+                    if footer.unwritten().is_empty() {
+                        self.state = State::Done;
+                    }
+                }
+
+                State::Done => {}
+            };
+
+            if let State::Done = self.state {
+                return Ok(true);
+            }
+
+            if output.unwritten().is_empty() {
+                return Ok(false);
+            }
+        }
     }
 }
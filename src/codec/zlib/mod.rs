@@ -2,3 +2,49 @@ mod decoder;
 mod encoder;
 
 pub(crate) use self::{decoder::ZlibDecoder, encoder::ZlibEncoder};
+
+// Compression method 8 (deflate) with a 32K window.
+const CMF: u8 = 0x78;
+
+// Set in the flags byte when the header is followed by the id of a preset dictionary.
+const FDICT: u8 = 0x20;
+
+// The largest prime smaller than 2^16.
+const MOD_ADLER: u32 = 65521;
+
+// The most bytes that can be summed before `b` could overflow, as calculated by zlib.
+const NMAX: usize = 5552;
+
+/// A running Adler-32 checksum, used for both the trailer and the preset dictionary id.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    pub(crate) fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(NMAX) {
+            for &byte in chunk {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+            self.a %= MOD_ADLER;
+            self.b %= MOD_ADLER;
+        }
+    }
+
+    pub(crate) fn sum(&self) -> u32 {
+        self.b << 16 | self.a
+    }
+}
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    let mut adler = Adler32::new();
+    adler.update(data);
+    adler.sum()
+}
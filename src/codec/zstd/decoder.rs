@@ -1,24 +1,100 @@
-use std::io::Result;
+use std::io::{Error, Result};
 
-use crate::{codec::Decode, unshared::Unshared, util::PartialBuffer};
-use libzstd::stream::raw::{Decoder, Operation};
+use crate::{
+    codec::Decode,
+    error,
+    unshared::Unshared,
+    util::PartialBuffer,
+    zstd::{DecoderDictionary, DecoderParams},
+    Algorithm,
+};
+use zstd_safe::{DCtx, DParameter, InBuffer, OutBuffer};
+
+// Values of `ZSTD_ErrorCode` from `zstd_errors.h`, which libzstd keeps stable.
+const VERSION_UNSUPPORTED: usize = 12;
+const FRAME_PARAMETER_UNSUPPORTED: usize = 14;
+const FRAME_PARAMETER_WINDOW_TOO_LARGE: usize = 16;
+const CHECKSUM_WRONG: usize = 22;
+const DICTIONARY_WRONG: usize = 32;
+const MEMORY_ALLOCATION: usize = 64;
+
+/// Classifies an error code returned by libzstd, which is the negated `ZSTD_ErrorCode`.
+fn from_zstd(code: usize) -> Error {
+    let name = zstd_safe::get_error_name(code);
+    match 0usize.wrapping_sub(code) {
+        CHECKSUM_WRONG => error::checksum_mismatch(Algorithm::Zstd),
+        FRAME_PARAMETER_WINDOW_TOO_LARGE | MEMORY_ALLOCATION => {
+            error::memory_limit(Algorithm::Zstd)
+        }
+        VERSION_UNSUPPORTED | FRAME_PARAMETER_UNSUPPORTED | DICTIONARY_WRONG => {
+            error::unsupported(Algorithm::Zstd, name)
+        }
+        _ => error::corrupt(Algorithm::Zstd, name),
+    }
+}
 
 #[derive(Debug)]
 pub struct ZstdDecoder {
-    decoder: Unshared<Decoder<'static>>,
+    decoder: Unshared<DCtx<'static>>,
+    // Referenced by `decoder` when using a prepared dictionary, so must be dropped after it.
+    dictionary: Option<DecoderDictionary>,
+    // Whether part of a frame has been decoded, so that the end of the input is within it.
+    in_frame: bool,
 }
 
 impl ZstdDecoder {
-    pub(crate) fn new() -> Self {
+    fn from_context(decoder: DCtx<'static>, dictionary: Option<DecoderDictionary>) -> Self {
         Self {
-            decoder: Unshared::new(Decoder::new().unwrap()),
+            decoder: Unshared::new(decoder),
+            dictionary,
+            in_frame: false,
+        }
+    }
+
+    pub(crate) fn new() -> Self {
+        let mut decoder = DCtx::create();
+        decoder.init();
+        Self::from_context(decoder, None)
+    }
+
+    pub(crate) fn with_params(params: &DecoderParams) -> Result<Self> {
+        let mut decoder = DCtx::create();
+        decoder.init();
+
+        if let Some(window_log_max) = params.window_log_max {
+            decoder
+                .set_parameter(DParameter::WindowLogMax(window_log_max))
+                .map_err(from_zstd)?;
         }
+
+        Ok(Self::from_context(decoder, None))
+    }
+
+    pub(crate) fn with_dict(dictionary: &[u8]) -> Result<Self> {
+        let mut decoder = DCtx::create();
+        decoder.init();
+        decoder.load_dictionary(dictionary).map_err(from_zstd)?;
+        Ok(Self::from_context(decoder, None))
+    }
+
+    pub(crate) fn with_prepared_dict(dictionary: &DecoderDictionary) -> Result<Self> {
+        let mut decoder = DCtx::create();
+        decoder.init();
+        decoder
+            .ref_ddict(dictionary.inner.as_ddict())
+            .map_err(from_zstd)?;
+        Ok(Self::from_context(decoder, Some(dictionary.clone())))
     }
 }
 
 impl Decode for ZstdDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Zstd)
+    }
+
     fn reinit(&mut self) -> Result<()> {
-        self.decoder.get_mut().reinit()?;
+        self.decoder.get_mut().reset().map_err(from_zstd)?;
+        self.in_frame = false;
         Ok(())
     }
 
@@ -27,34 +103,35 @@ impl Decode for ZstdDecoder {
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        let status = self
+        let mut in_buf = InBuffer::around(input.unwritten());
+        let mut out_buf = OutBuffer::around(output.unwritten_mut());
+        let remaining = self
             .decoder
             .get_mut()
-            .run_on_buffers(input.unwritten(), output.unwritten_mut())?;
-        input.advance(status.bytes_read);
-        output.advance(status.bytes_written);
-        Ok(status.remaining == 0)
+            .decompress_stream(&mut out_buf, &mut in_buf)
+            .map_err(from_zstd)?;
+        let (read, written) = (in_buf.pos(), out_buf.pos());
+        input.advance(read);
+        output.advance(written);
+        self.in_frame = remaining != 0;
+        Ok(remaining == 0)
     }
 
     fn flush(
         &mut self,
-        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        let mut out_buf = zstd_safe::OutBuffer::around(output.unwritten_mut());
-        let bytes_left = self.decoder.get_mut().flush(&mut out_buf)?;
-        let len = out_buf.as_slice().len();
-        output.advance(len);
-        Ok(bytes_left == 0)
+        Ok(true)
     }
 
     fn finish(
         &mut self,
-        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
     ) -> Result<bool> {
-        let mut out_buf = zstd_safe::OutBuffer::around(output.unwritten_mut());
-        let bytes_left = self.decoder.get_mut().finish(&mut out_buf, true)?;
-        let len = out_buf.as_slice().len();
-        output.advance(len);
-        Ok(bytes_left == 0)
+        if self.in_frame {
+            Err(error::truncated(Algorithm::Zstd))
+        } else {
+            Ok(true)
+        }
     }
 }
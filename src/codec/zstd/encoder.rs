@@ -1,21 +1,83 @@
-use crate::{codec::Encode, unshared::Unshared, util::PartialBuffer};
-use libzstd::stream::raw::{Encoder, Operation};
+use crate::{
+    codec::Encode,
+    unshared::Unshared,
+    util::PartialBuffer,
+    zstd::{EncoderDictionary, EncoderParams},
+    FlushMode,
+};
+use libzstd::stream::raw::{CParameter, Encoder, Operation};
 use std::io::Result;
 
 #[derive(Debug)]
 pub struct ZstdEncoder {
     encoder: Unshared<Encoder<'static>>,
+    // Referenced by `encoder` when using a prepared dictionary, so must be dropped after it.
+    dictionary: Option<EncoderDictionary>,
+    // Whether any input has been encoded into the current frame, so that a full flush doesn't
+    // write out empty frames.
+    in_frame: bool,
 }
 
 impl ZstdEncoder {
     pub(crate) fn new(level: i32) -> Self {
         Self {
             encoder: Unshared::new(Encoder::new(level).unwrap()),
+            dictionary: None,
+            in_frame: false,
         }
     }
+
+    pub(crate) fn with_params(params: &EncoderParams) -> Result<Self> {
+        let mut encoder = Encoder::new(params.level.unwrap_or(0))?;
+
+        if let Some(window_log) = params.window_log {
+            encoder.set_parameter(CParameter::WindowLog(window_log))?;
+        }
+        if let Some(enabled) = params.long_distance_matching {
+            encoder.set_parameter(CParameter::EnableLongDistanceMatching(enabled))?;
+        }
+        if let Some(enabled) = params.checksum {
+            encoder.set_parameter(CParameter::ChecksumFlag(enabled))?;
+        }
+        if let Some(size) = params.pledged_src_size {
+            encoder.set_pledged_src_size(size)?;
+        }
+
+        Ok(Self {
+            encoder: Unshared::new(encoder),
+            dictionary: None,
+            in_frame: false,
+        })
+    }
+
+    pub(crate) fn with_dict(level: i32, dictionary: &[u8]) -> Result<Self> {
+        Ok(Self {
+            encoder: Unshared::new(Encoder::with_dictionary(level, dictionary)?),
+            dictionary: None,
+            in_frame: false,
+        })
+    }
+
+    pub(crate) fn with_prepared_dict(dictionary: &EncoderDictionary) -> Result<Self> {
+        Ok(Self {
+            encoder: Unshared::new(Encoder::with_prepared_dictionary(&dictionary.inner)?),
+            dictionary: Some(dictionary.clone()),
+            in_frame: false,
+        })
+    }
 }
 
 impl Encode for ZstdEncoder {
+    /// Starts a new frame after the previous one has been finished, keeping all parameters.
+    fn reinit(&mut self) -> Result<()> {
+        self.in_frame = false;
+        self.encoder.get_mut().reinit()
+    }
+
+    fn can_reinit(&self) -> bool {
+        true
+    }
+
     fn encode(
         &mut self,
         input: &mut PartialBuffer<impl AsRef<[u8]>>,
@@ -27,6 +89,7 @@ impl Encode for ZstdEncoder {
             .run_on_buffers(input.unwritten(), output.unwritten_mut())?;
         input.advance(status.bytes_read);
         output.advance(status.bytes_written);
+        self.in_frame |= status.bytes_read > 0;
         Ok(())
     }
 
@@ -41,6 +104,27 @@ impl Encode for ZstdEncoder {
         Ok(bytes_left == 0)
     }
 
+    /// A full or block flush ends the frame, then starts a new one.
+    fn flush_with_mode(
+        &mut self,
+        mode: FlushMode,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        match mode {
+            FlushMode::Full | FlushMode::Block => {
+                if !self.in_frame {
+                    return Ok(true);
+                }
+                if !self.finish(output)? {
+                    return Ok(false);
+                }
+                self.reinit()?;
+                Ok(true)
+            }
+            _ => self.flush(output),
+        }
+    }
+
     fn finish(
         &mut self,
         output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
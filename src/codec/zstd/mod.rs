@@ -1,4 +1,17 @@
+#[cfg(feature = "zstd")]
 mod decoder;
+#[cfg(feature = "zstd")]
 mod encoder;
+#[cfg(not(feature = "zstd"))]
+mod ruzstd_decoder;
+#[cfg(feature = "zstd")]
+mod seekable;
 
-pub(crate) use self::{decoder::ZstdDecoder, encoder::ZstdEncoder};
+#[cfg(not(feature = "zstd"))]
+pub(crate) use self::ruzstd_decoder::ZstdDecoder;
+#[cfg(feature = "zstd")]
+pub(crate) use self::{
+    decoder::ZstdDecoder,
+    encoder::ZstdEncoder,
+    seekable::{Step, ZstdSeekableDecoder, ZstdSeekableEncoder},
+};
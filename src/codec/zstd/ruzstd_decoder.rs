@@ -0,0 +1,292 @@
+use crate::{codec::Decode, error, util::PartialBuffer, Algorithm};
+use std::{
+    cmp::min,
+    convert::TryInto,
+    fmt,
+    io::{Error, Read, Result},
+};
+
+use ruzstd::FrameDecoder;
+
+// Skippable frames use any magic number from 0x184d2a50 to 0x184d2a5f.
+const SKIPPABLE_FRAME_MAGIC: u32 = 0x184d_2a50;
+const SKIPPABLE_FRAME_MAGIC_MASK: u32 = 0xffff_fff0;
+
+// An RLE block is a single byte repeated, so has 1 byte of content whatever its size.
+const RLE_BLOCK: u32 = 1;
+const RESERVED_BLOCK: u32 = 3;
+
+const CHECKSUM_SIZE: usize = 4;
+
+#[derive(Debug)]
+enum Header {
+    Frame(usize),
+    Skippable(u64),
+}
+
+/// Works out the size of the frame header at the start of `input`.
+///
+/// This needs 8 bytes, the smallest frame header is 6 bytes but it is always followed by at least
+/// a 3 byte block header.
+fn parse_header(input: &[u8; 8]) -> Header {
+    let magic = u32::from_le_bytes([input[0], input[1], input[2], input[3]]);
+    if magic & SKIPPABLE_FRAME_MAGIC_MASK == SKIPPABLE_FRAME_MAGIC {
+        let len = u32::from_le_bytes([input[4], input[5], input[6], input[7]]);
+        return Header::Skippable(u64::from(len));
+    }
+
+    let descriptor = input[4];
+    let single_segment = descriptor & 0x20 != 0;
+    let dictionary_id_size = [0, 1, 2, 4][usize::from(descriptor & 0x03)];
+    let content_size_size = match descriptor >> 6 {
+        0 if single_segment => 1,
+        0 => 0,
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    };
+    let window_descriptor_size = if single_segment { 0 } else { 1 };
+
+    Header::Frame(5 + window_descriptor_size + dictionary_id_size + content_size_size)
+}
+
+fn invalid_data(error: impl std::error::Error) -> Error {
+    error::corrupt(Algorithm::Zstd, error.to_string())
+}
+
+#[derive(Debug)]
+enum State {
+    Header,
+    Skip(u64),
+    BlockHeader,
+    Block { last: bool, len: usize },
+    Checksum,
+    Draining,
+}
+
+/// A zstd decoder implemented by `ruzstd`.
+///
+/// `ruzstd` can only decode whole blocks, so we parse the block headers to buffer exactly one
+/// block at a time, never reading past the end of a frame. It also keeps the last window of
+/// decompressed data until the end of each frame, so flushing can't output that.
+pub struct ZstdDecoder {
+    decoder: FrameDecoder,
+    state: State,
+    checksum: bool,
+    // The frame header, block or checksum currently being read.
+    buffer: Vec<u8>,
+}
+
+impl fmt::Debug for ZstdDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZstdDecoder")
+            .field("decoder", &"<no debug>")
+            .field("state", &self.state)
+            .field("checksum", &self.checksum)
+            .field("buffer", &self.buffer.len())
+            .finish()
+    }
+}
+
+impl ZstdDecoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            decoder: FrameDecoder::new(),
+            state: State::Header,
+            checksum: false,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Copies input into `buffer` until it contains `len` bytes, returning whether it does.
+    fn fill(&mut self, input: &mut PartialBuffer<impl AsRef<[u8]>>, len: usize) -> bool {
+        let needed = min(
+            len.saturating_sub(self.buffer.len()),
+            input.unwritten().len(),
+        );
+        self.buffer.extend_from_slice(&input.unwritten()[..needed]);
+        input.advance(needed);
+        self.buffer.len() >= len
+    }
+
+    /// Decodes the complete block or checksum in `buffer`.
+    fn decode_buffer(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        let (read, written) = self
+            .decoder
+            .decode_from_to(&self.buffer, output.unwritten_mut())
+            .map_err(invalid_data)?;
+        output.advance(written);
+
+        if read != self.buffer.len() {
+            return Err(error::corrupt(
+                Algorithm::Zstd,
+                "zstd block did not decode completely",
+            ));
+        }
+
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Outputs decoded data, returning whether there is none left to output for now.
+    fn drain(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let len = self
+            .decoder
+            .read(output.unwritten_mut())
+            .map_err(|e| error::from_library(Algorithm::Zstd, e))?;
+        output.advance(len);
+        Ok(self.decoder.can_collect() == 0)
+    }
+
+    /// Outputs the end of the frame, returning whether it is all output.
+    fn drain_frame(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if !self.drain(output)? {
+            return Ok(false);
+        }
+
+        if let Some(checksum) = self.decoder.get_checksum_from_data() {
+            if Some(checksum) != self.decoder.get_calculated_checksum() {
+                return Err(error::checksum_mismatch(Algorithm::Zstd));
+            }
+        }
+
+        self.state = State::Header;
+        Ok(true)
+    }
+}
+
+impl Decode for ZstdDecoder {
+    fn algorithm(&self) -> Option<Algorithm> {
+        Some(Algorithm::Zstd)
+    }
+
+    fn reinit(&mut self) -> Result<()> {
+        self.state = State::Header;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            match self.state {
+                State::Header => {
+                    if !self.fill(input, 8) {
+                        return Ok(false);
+                    }
+
+                    match parse_header(self.buffer[..8].try_into().unwrap()) {
+                        Header::Skippable(len) => {
+                            self.buffer.clear();
+                            self.state = State::Skip(len);
+                        }
+
+                        Header::Frame(len) => {
+                            if !self.fill(input, len) {
+                                return Ok(false);
+                            }
+                            self.decoder
+                                .reset(&self.buffer[..len])
+                                .map_err(invalid_data)?;
+                            self.checksum = self.buffer[4] & 0x04 != 0;
+                            // Any extra bytes are the start of the first block header
+                            self.buffer.drain(..len);
+                            self.state = State::BlockHeader;
+                        }
+                    }
+                }
+
+                State::Skip(ref mut remaining) => {
+                    let len = min(*remaining, input.unwritten().len() as u64);
+                    input.advance(len as usize);
+                    *remaining -= len;
+
+                    if *remaining != 0 {
+                        return Ok(false);
+                    }
+
+                    // Like libzstd, treat a skippable frame as a complete frame of its own
+                    self.state = State::Header;
+                    return Ok(true);
+                }
+
+                State::BlockHeader => {
+                    // Output everything decoded so far before decoding more, to bound how much
+                    // is kept in memory
+                    if !self.drain(output)? || !self.fill(input, 3) {
+                        return Ok(false);
+                    }
+
+                    let header =
+                        u32::from_le_bytes([self.buffer[0], self.buffer[1], self.buffer[2], 0]);
+                    let len = match (header >> 1) & 0x03 {
+                        RLE_BLOCK => 1,
+                        RESERVED_BLOCK => {
+                            return Err(error::corrupt(Algorithm::Zstd, "reserved zstd block type"))
+                        }
+                        _ => (header >> 3) as usize,
+                    };
+
+                    self.state = State::Block {
+                        last: header & 0x01 != 0,
+                        len: 3 + len,
+                    };
+                }
+
+                State::Block { last, len } => {
+                    if !self.fill(input, len) {
+                        return Ok(false);
+                    }
+
+                    self.decode_buffer(output)?;
+                    self.state = match (last, self.checksum) {
+                        (false, _) => State::BlockHeader,
+                        (true, true) => State::Checksum,
+                        (true, false) => State::Draining,
+                    };
+                }
+
+                State::Checksum => {
+                    if !self.fill(input, CHECKSUM_SIZE) {
+                        return Ok(false);
+                    }
+
+                    self.decode_buffer(output)?;
+                    self.state = State::Draining;
+                }
+
+                State::Draining => return self.drain_frame(output),
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.drain(output)
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        match self.state {
+            State::Header if self.buffer.is_empty() => Ok(true),
+            State::Draining => self.drain_frame(output),
+            _ => Err(error::truncated(Algorithm::Zstd)),
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use crate::{
+    codec::{Decode, Encode, ZstdDecoder, ZstdEncoder},
+    error,
+    util::PartialBuffer,
+    zstd::SeekTable,
+    Algorithm,
+};
+use std::{
+    cmp::min,
+    io::{Result, SeekFrom},
+    mem,
+    ops::Range,
+};
+
+// The largest amount of uncompressed data the format allows in a single frame.
+const MAX_FRAME_SIZE: u32 = 0x4000_0000;
+
+const DEFAULT_FRAME_SIZE: u32 = 0x10_0000;
+
+#[derive(Debug)]
+enum State {
+    Encoding,
+    FinishingFrame,
+    SeekTable(PartialBuffer<Vec<u8>>),
+    Done,
+}
+
+/// Splits the data into independent frames of at most `frame_size` uncompressed bytes, recording
+/// each in a seek table that is written in a skippable frame at the end.
+#[derive(Debug)]
+pub struct ZstdSeekableEncoder {
+    encoder: ZstdEncoder,
+    frame_size: usize,
+    // How much uncompressed and compressed data the current frame contains so far.
+    frame_in: usize,
+    frame_out: usize,
+    table: SeekTable,
+    state: State,
+}
+
+impl ZstdSeekableEncoder {
+    pub(crate) fn new(level: i32) -> Self {
+        Self::with_frame_size(level, DEFAULT_FRAME_SIZE)
+    }
+
+    pub(crate) fn with_frame_size(level: i32, frame_size: u32) -> Self {
+        assert!(
+            frame_size > 0 && frame_size <= MAX_FRAME_SIZE,
+            "zstd seekable frame size must be between 1 byte and 1 GiB"
+        );
+
+        Self {
+            encoder: ZstdEncoder::new(level),
+            frame_size: frame_size as usize,
+            frame_in: 0,
+            frame_out: 0,
+            table: SeekTable::default(),
+            state: State::Encoding,
+        }
+    }
+
+    /// Finishes the current frame and records it in the seek table, returning whether it is
+    /// complete.
+    fn finish_frame(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        let old_len = output.written().len();
+        let done = self.encoder.finish(output)?;
+        self.frame_out += output.written().len() - old_len;
+
+        if done {
+            self.table.push(self.frame_out as u32, self.frame_in as u32);
+            self.frame_in = 0;
+            self.frame_out = 0;
+            self.encoder.reinit()?;
+        }
+
+        Ok(done)
+    }
+}
+
+impl Encode for ZstdSeekableEncoder {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        loop {
+            match self.state {
+                State::Encoding => {
+                    let len = min(self.frame_size - self.frame_in, input.unwritten().len());
+                    let mut chunk = PartialBuffer::new(&input.unwritten()[..len]);
+                    let old_len = output.written().len();
+                    self.encoder.encode(&mut chunk, output)?;
+                    let read = chunk.written().len();
+                    input.advance(read);
+                    self.frame_in += read;
+                    self.frame_out += output.written().len() - old_len;
+
+                    if self.frame_in == self.frame_size {
+                        self.state = State::FinishingFrame;
+                    } else if input.unwritten().is_empty() || output.unwritten().is_empty() {
+                        return Ok(());
+                    }
+                }
+
+                State::FinishingFrame => {
+                    if !self.finish_frame(output)? {
+                        return Ok(());
+                    }
+                    self.state = State::Encoding;
+                }
+
+                State::SeekTable(_) | State::Done => panic!("encode after complete"),
+            }
+        }
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            match &mut self.state {
+                State::Encoding => {
+                    let old_len = output.written().len();
+                    let done = self.encoder.flush(output)?;
+                    self.frame_out += output.written().len() - old_len;
+                    return Ok(done);
+                }
+
+                State::FinishingFrame => {
+                    if !self.finish_frame(output)? {
+                        return Ok(false);
+                    }
+                    self.state = State::Encoding;
+                }
+
+                State::SeekTable(table) => {
+                    output.copy_unwritten_from(table);
+                    return Ok(table.unwritten().is_empty());
+                }
+
+                State::Done => return Ok(true),
+            }
+        }
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        loop {
+            match &mut self.state {
+                State::Encoding => {
+                    self.state = if self.frame_in > 0 || self.frame_out > 0 {
+                        State::FinishingFrame
+                    } else {
+                        State::SeekTable(self.table.to_bytes().into())
+                    };
+                }
+
+                State::FinishingFrame => {
+                    if !self.finish_frame(output)? {
+                        return Ok(false);
+                    }
+                    self.state = State::SeekTable(self.table.to_bytes().into());
+                }
+
+                State::SeekTable(table) => {
+                    output.copy_unwritten_from(table);
+                    if !table.unwritten().is_empty() {
+                        return Ok(false);
+                    }
+                    self.state = State::Done;
+                }
+
+                State::Done => return Ok(true),
+            }
+        }
+    }
+}
+
+// How much compressed data is read from the underlying reader at once.
+const READ_SIZE: usize = 8 * 1024;
+
+/// What the IO driver of a [`ZstdSeekableDecoder`] needs to do next.
+#[derive(Debug)]
+pub(crate) enum Step<'a> {
+    /// Seek the reader to this position, then pass the resulting position to `seeked`.
+    Seek(SeekFrom),
+    /// Read into this buffer, then pass the amount read to `read`.
+    Read(&'a mut [u8]),
+    /// Call `decode` with the caller's output buffer.
+    Decode,
+    /// The whole range has been output.
+    Done,
+}
+
+#[derive(Debug)]
+enum DecodeState {
+    SeekFooter,
+    ReadFooter {
+        len: u64,
+        footer: PartialBuffer<[u8; SeekTable::FOOTER_SIZE]>,
+    },
+    SeekTable {
+        len: u64,
+        size: usize,
+    },
+    ReadTable {
+        len: u64,
+        table: PartialBuffer<Vec<u8>>,
+    },
+    StartRange,
+    SeekRange,
+    ReadFrames,
+    Decoding,
+    Done,
+}
+
+/// A state machine for decoding a range of a seekable stream, independent of the IO traits used
+/// to seek and read it.
+#[derive(Debug)]
+pub(crate) struct ZstdSeekableDecoder {
+    decoder: ZstdDecoder,
+    table: Option<SeekTable>,
+    range: Range<u64>,
+    state: DecodeState,
+    // Compressed data read from the covering frames, but not yet decoded.
+    buffer: PartialBuffer<Vec<u8>>,
+    compressed_remaining: u64,
+    // Uncompressed data to discard from the start of the first frame.
+    skip: u64,
+}
+
+impl ZstdSeekableDecoder {
+    pub(crate) fn new(range: Range<u64>) -> Self {
+        Self {
+            decoder: ZstdDecoder::new(),
+            table: None,
+            range,
+            state: DecodeState::SeekFooter,
+            buffer: PartialBuffer::new(Vec::new()),
+            compressed_remaining: 0,
+            skip: 0,
+        }
+    }
+
+    pub(crate) fn seek_table(&self) -> Option<&SeekTable> {
+        self.table.as_ref()
+    }
+
+    pub(crate) fn set_range(&mut self, range: Range<u64>) -> Result<()> {
+        self.range = range;
+        if self.table.is_some() {
+            self.decoder.reinit()?;
+            self.buffer = PartialBuffer::new(Vec::new());
+            self.state = DecodeState::StartRange;
+        }
+        Ok(())
+    }
+
+    /// Works out the range of frames to decode once the seek table is known.
+    fn start_range(&mut self) {
+        let table = self.table.as_ref().unwrap();
+        let end = min(self.range.end, table.decompressed_size());
+
+        if self.range.start >= end {
+            self.state = DecodeState::Done;
+            return;
+        }
+
+        let first = &table.frames()[table.frame_index(self.range.start).unwrap()];
+        let last = &table.frames()[table.frame_index(end - 1).unwrap()];
+
+        self.skip = self.range.start - first.decompressed_offset();
+        self.compressed_remaining = last.compressed_offset() + u64::from(last.compressed_size())
+            - first.compressed_offset();
+        self.range.end = end;
+        self.state = DecodeState::SeekRange;
+    }
+
+    pub(crate) fn step(&mut self) -> Step<'_> {
+        if let DecodeState::StartRange = self.state {
+            self.start_range();
+        }
+
+        if let DecodeState::Decoding = self.state {
+            if self.range.start == self.range.end {
+                self.state = DecodeState::Done;
+            } else if self.buffer.unwritten().is_empty() && self.compressed_remaining > 0 {
+                let mut buffer = mem::take(&mut self.buffer).into_inner();
+                let len = min(READ_SIZE as u64, self.compressed_remaining) as usize;
+                buffer.resize(len, 0);
+                self.buffer = PartialBuffer::new(buffer);
+                self.state = DecodeState::ReadFrames;
+            }
+        }
+
+        match &mut self.state {
+            DecodeState::SeekFooter => Step::Seek(SeekFrom::End(-(SeekTable::FOOTER_SIZE as i64))),
+
+            DecodeState::ReadFooter { footer, .. } => Step::Read(footer.unwritten_mut()),
+
+            DecodeState::SeekTable { size, .. } => Step::Seek(SeekFrom::End(-(*size as i64))),
+
+            DecodeState::ReadTable { table, .. } => Step::Read(table.unwritten_mut()),
+
+            DecodeState::StartRange => unreachable!(),
+
+            DecodeState::SeekRange => {
+                let table = self.table.as_ref().unwrap();
+                let index = table.frame_index(self.range.start).unwrap();
+                Step::Seek(SeekFrom::Start(table.frames()[index].compressed_offset()))
+            }
+
+            DecodeState::ReadFrames => Step::Read(self.buffer.unwritten_mut()),
+
+            DecodeState::Decoding => Step::Decode,
+
+            DecodeState::Done => Step::Done,
+        }
+    }
+
+    pub(crate) fn seeked(&mut self, position: u64) -> Result<()> {
+        self.state = match self.state {
+            DecodeState::SeekFooter => DecodeState::ReadFooter {
+                len: position + SeekTable::FOOTER_SIZE as u64,
+                footer: Default::default(),
+            },
+
+            DecodeState::SeekTable { len, size } => DecodeState::ReadTable {
+                len,
+                table: PartialBuffer::new(vec![0; size]),
+            },
+
+            DecodeState::SeekRange => DecodeState::Decoding,
+
+            _ => unreachable!(),
+        };
+
+        Ok(())
+    }
+
+    pub(crate) fn read(&mut self, len: usize) -> Result<()> {
+        if len == 0 {
+            return Err(error::truncated(Algorithm::Zstd));
+        }
+
+        match &mut self.state {
+            DecodeState::ReadFooter { len: total, footer } => {
+                footer.advance(len);
+                if footer.unwritten().is_empty() {
+                    let size = SeekTable::parse_footer(footer.get_mut())?;
+                    if size as u64 > *total {
+                        return Err(error::corrupt(
+                            Algorithm::Zstd,
+                            "zstd seek table is larger than the stream",
+                        ));
+                    }
+                    self.state = DecodeState::SeekTable { len: *total, size };
+                }
+            }
+
+            DecodeState::ReadTable { len: total, table } => {
+                table.advance(len);
+                if table.unwritten().is_empty() {
+                    let (total, table) = (*total, table.take().into_inner());
+                    let parsed = SeekTable::parse(&table)?;
+                    if parsed.compressed_size() + table.len() as u64 != total {
+                        return Err(error::corrupt(
+                            Algorithm::Zstd,
+                            "zstd seek table does not match the stream",
+                        ));
+                    }
+                    self.table = Some(parsed);
+                    self.state = DecodeState::StartRange;
+                }
+            }
+
+            DecodeState::ReadFrames => {
+                let mut buffer = mem::take(&mut self.buffer).into_inner();
+                buffer.truncate(len);
+                self.buffer = PartialBuffer::new(buffer);
+                self.compressed_remaining -= len as u64;
+                self.state = DecodeState::Decoding;
+            }
+
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    /// Decodes into `output`, returning how much was written. This may be 0 while skipping data
+    /// before the start of the range, in which case `step` should be called again.
+    pub(crate) fn decode(&mut self, output: &mut [u8]) -> Result<usize> {
+        let mut scratch = [0; 1024];
+        let mut output = if self.skip > 0 {
+            let len = min(self.skip, scratch.len() as u64) as usize;
+            PartialBuffer::new(&mut scratch[..len])
+        } else {
+            let len = min(self.range.end - self.range.start, output.len() as u64) as usize;
+            PartialBuffer::new(&mut output[..len])
+        };
+
+        let old_input_len = self.buffer.written().len();
+        if self.decoder.decode(&mut self.buffer, &mut output)? {
+            self.decoder.reinit()?;
+        }
+
+        let len = output.written().len();
+        if len == 0
+            && self.buffer.written().len() == old_input_len
+            && self.buffer.unwritten().is_empty()
+        {
+            return Err(error::truncated(Algorithm::Zstd));
+        }
+
+        if self.skip > 0 {
+            self.skip -= len as u64;
+            Ok(0)
+        } else {
+            self.range.start += len as u64;
+            Ok(len)
+        }
+    }
+}
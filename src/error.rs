@@ -0,0 +1,184 @@
+use crate::Algorithm;
+use std::{
+    fmt,
+    io::{self, ErrorKind},
+};
+
+/// Why an encoder or decoder failed, for errors caused by the compressed data itself rather than
+/// by the underlying reader or writer.
+///
+/// This is carried inside the [`io::Error`]s returned by the encoders and decoders, and can be
+/// found with [`Error::from_io`]. The [`kind`](io::Error::kind) of the `io::Error` is
+/// [`UnexpectedEof`](ErrorKind::UnexpectedEof) for [`Truncated`](Error::Truncated),
+/// [`Other`](ErrorKind::Other) for [`MemoryLimit`](Error::MemoryLimit), and
+/// [`InvalidData`](ErrorKind::InvalidData) for everything else.
+///
+/// Exceeding a decoder's `max_output_size` or `max_ratio` is reported with
+/// [`LimitExceeded`](crate::LimitExceeded) instead.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The data is not valid for the algorithm.
+    Corrupt {
+        /// The algorithm that failed.
+        algorithm: Algorithm,
+        /// A description of what was wrong, from the library doing the decoding where possible.
+        message: String,
+    },
+    /// The data decoded, but did not match the checksum or length stored with it.
+    ChecksumMismatch {
+        /// The algorithm that failed.
+        algorithm: Algorithm,
+    },
+    /// The data ended before the end of the stream.
+    Truncated {
+        /// The algorithm that failed.
+        algorithm: Algorithm,
+    },
+    /// The data is valid, but uses a feature this crate or the library doing the decoding does
+    /// not support, or that needs something which was not given, such as a preset dictionary.
+    Unsupported {
+        /// The algorithm that failed.
+        algorithm: Algorithm,
+        /// The feature that is not supported.
+        feature: String,
+    },
+    /// More memory was needed than was available or allowed.
+    MemoryLimit {
+        /// The algorithm that failed.
+        algorithm: Algorithm,
+    },
+    /// There was unexpected data after the end of the stream, see
+    /// [`TrailingData::Strict`](crate::TrailingData::Strict).
+    TrailingData {
+        /// The algorithm that failed.
+        algorithm: Algorithm,
+    },
+    /// The format of the data could not be detected from its first bytes, or there were none,
+    /// when decoding it with an `AutoDecoder` without a fallback.
+    UnrecognizedFormat,
+}
+
+impl Error {
+    /// The algorithm that failed, [`Identity`](Algorithm::Identity) if there is none because the
+    /// format was not recognized.
+    pub fn algorithm(&self) -> Algorithm {
+        match *self {
+            Self::Corrupt { algorithm, .. }
+            | Self::ChecksumMismatch { algorithm }
+            | Self::Truncated { algorithm }
+            | Self::Unsupported { algorithm, .. }
+            | Self::MemoryLimit { algorithm }
+            | Self::TrailingData { algorithm } => algorithm,
+            Self::UnrecognizedFormat => Algorithm::Identity,
+        }
+    }
+
+    /// Returns the `Error` carried by `error`, if it has one.
+    pub fn from_io(error: &io::Error) -> Option<&Self> {
+        error.get_ref().and_then(|error| error.downcast_ref())
+    }
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Truncated { .. } => ErrorKind::UnexpectedEof,
+            Self::MemoryLimit { .. } => ErrorKind::Other,
+            _ => ErrorKind::InvalidData,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Corrupt { algorithm, message } => {
+                write!(f, "invalid {} data: {}", algorithm, message)
+            }
+            Self::ChecksumMismatch { algorithm } => {
+                write!(f, "{} checksum mismatch", algorithm)
+            }
+            Self::Truncated { algorithm } => {
+                write!(f, "{} stream ended unexpectedly", algorithm)
+            }
+            Self::Unsupported { algorithm, feature } => {
+                write!(f, "unsupported {} feature: {}", algorithm, feature)
+            }
+            Self::MemoryLimit { algorithm } => {
+                write!(f, "{} needed more memory than is available", algorithm)
+            }
+            Self::TrailingData { algorithm } => {
+                write!(
+                    f,
+                    "unexpected data after the end of the {} stream",
+                    algorithm
+                )
+            }
+            Self::UnrecognizedFormat => f.write_str("unrecognized compression format"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        io::Error::new(error.kind(), error)
+    }
+}
+
+pub(crate) fn corrupt(algorithm: Algorithm, message: impl Into<String>) -> io::Error {
+    Error::Corrupt {
+        algorithm,
+        message: message.into(),
+    }
+    .into()
+}
+
+pub(crate) fn checksum_mismatch(algorithm: Algorithm) -> io::Error {
+    Error::ChecksumMismatch { algorithm }.into()
+}
+
+pub(crate) fn truncated(algorithm: Algorithm) -> io::Error {
+    Error::Truncated { algorithm }.into()
+}
+
+pub(crate) fn unsupported(algorithm: Algorithm, feature: impl Into<String>) -> io::Error {
+    Error::Unsupported {
+        algorithm,
+        feature: feature.into(),
+    }
+    .into()
+}
+
+pub(crate) fn memory_limit(algorithm: Algorithm) -> io::Error {
+    Error::MemoryLimit { algorithm }.into()
+}
+
+pub(crate) fn unrecognized_format() -> io::Error {
+    Error::UnrecognizedFormat.into()
+}
+
+/// Takes the algorithm from [`Decode::algorithm`](crate::codec::Decode::algorithm), which custom
+/// codecs may not give.
+pub(crate) fn trailing_data(algorithm: Option<Algorithm>) -> io::Error {
+    match algorithm {
+        Some(algorithm) => Error::TrailingData { algorithm }.into(),
+        None => io::Error::new(
+            ErrorKind::InvalidData,
+            "unexpected data after the end of the stream",
+        ),
+    }
+}
+
+/// Converts an error from the library doing the decoding, which has no more detail than its kind
+/// and message, leaving it alone if it is already one of ours.
+pub(crate) fn from_library(algorithm: Algorithm, error: impl Into<io::Error>) -> io::Error {
+    let error = error.into();
+    if Error::from_io(&error).is_some() {
+        return error;
+    }
+    match error.kind() {
+        ErrorKind::UnexpectedEof => truncated(algorithm),
+        _ => corrupt(algorithm, error.to_string()),
+    }
+}
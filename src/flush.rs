@@ -0,0 +1,60 @@
+/// How much an encoder does when it is flushed, see `flush_mode` on each encoder.
+///
+/// Not every format supports every mode, flushing with one which isn't supported is an error of
+/// kind [`Unsupported`](std::io::ErrorKind::Unsupported).
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushMode {
+    /// Write out everything encoded so far, but only as much as the format needs for it to be
+    /// decoded, which for deflate based formats may not end on a byte boundary. Formats without a
+    /// cheaper flush do a [`Sync`](Self::Sync) flush.
+    Partial,
+    /// Write out everything encoded so far so that it can be decoded, without resetting any
+    /// state, so compression carries on as well as before.
+    #[default]
+    Sync,
+    /// Like [`Sync`](Self::Sync), but also reset the state of the encoder, such as its
+    /// dictionary, so that a reader can resynchronize and decode the data following it without
+    /// anything before it.
+    Full,
+    /// Like [`Sync`](Self::Sync), but also end the current block, or for zstd and lz4 the
+    /// current frame.
+    Block,
+}
+
+/// The error for flushing an encoder with a mode it doesn't support.
+pub(crate) fn unsupported(mode: FlushMode) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("{:?} flush is not supported by this encoder", mode),
+    )
+}
+
+/// When a write encoder flushes itself, without waiting for `flush` to be called, see
+/// `auto_flush` on each write encoder.
+///
+/// The flush is done with the encoder's `flush_mode`, and flushes the underlying writer too.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AutoFlush {
+    /// Only flush when asked to.
+    #[default]
+    Never,
+    /// Flush after every write.
+    EveryWrite,
+    /// Flush after a write once at least this many bytes of uncompressed input have been
+    /// written since the last flush.
+    Bytes(u64),
+}
+
+impl AutoFlush {
+    /// Whether to flush after a write, with `unflushed` bytes written since the last flush.
+    pub(crate) fn is_due(self, unflushed: u64) -> bool {
+        unflushed > 0
+            && match self {
+                Self::Never => false,
+                Self::EveryWrite => true,
+                Self::Bytes(limit) => unflushed >= limit,
+            }
+    }
+}
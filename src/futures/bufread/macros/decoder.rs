@@ -1,5 +1,5 @@
 macro_rules! decoder {
-    ($(#[$attr:meta])* $name:ident) => {
+    ($(#[$attr:meta])* $name:ident $({ $($constructor:tt)* })*) => {
         pin_project_lite::pin_project! {
             $(#[$attr])*
             #[derive(Debug)]
@@ -13,13 +13,12 @@ macro_rules! decoder {
         }
 
         impl<R: futures_io::AsyncBufRead> $name<R> {
-            /// Creates a new decoder which will read compressed data from the given stream and
-            /// emit a uncompressed stream.
-            pub fn new(read: R) -> $name<R> {
-                $name {
-                    inner: crate::futures::bufread::Decoder::new(read, crate::codec::$name::new()),
-                }
-            }
+            $(
+                /// Creates a new decoder which will read compressed data from the given stream and
+                /// emit a uncompressed stream.
+                ///
+                $($constructor)*
+            )*
 
             /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
             /// when reaching the end of a compressed member/frame and expect either EOF or another
@@ -28,6 +27,25 @@ macro_rules! decoder {
                 self.inner.multiple_members(enabled);
             }
 
+            /// Configure what is done with any data following the end of the stream when not decoding
+            /// multiple members, by default it is [ignored](crate::TrailingData::Ignore) and left unread.
+            pub fn trailing_data(&mut self, mode: crate::TrailingData) {
+                self.inner.trailing_data(mode);
+            }
+
+            /// Limits the total size of the decompressed output, over all members, it is an error with a
+            /// [`LimitExceeded`](crate::LimitExceeded) inside to produce more than `limit` bytes.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits how many bytes of decompressed output can be produced per byte of
+            /// compressed input consumed, over all members, it is an error with a
+            /// [`LimitExceeded`](crate::LimitExceeded) inside to exceed it.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
+            }
+
             /// Acquires a reference to the underlying reader that this decoder is wrapping.
             pub fn get_ref(&self) -> &R {
                 self.inner.get_ref()
@@ -58,6 +76,23 @@ macro_rules! decoder {
             pub fn into_inner(self) -> R {
                 self.inner.into_inner()
             }
+
+            /// Returns the number of bytes of compressed input consumed so far, over all members.
+            pub fn total_in(&self) -> u64 {
+                self.inner.total_in()
+            }
+
+            /// Returns the number of bytes of uncompressed output produced so far, over all members.
+            pub fn total_out(&self) -> u64 {
+                self.inner.total_out()
+            }
+
+            /// Returns the number of bytes read after the end of the stream so far, which are only read
+            /// when [`trailing_data`](Self::trailing_data) is not
+            /// [`Ignore`](crate::TrailingData::Ignore).
+            pub fn trailing_bytes(&self) -> u64 {
+                self.inner.trailing_bytes()
+            }
         }
 
         impl<R: futures_io::AsyncBufRead> futures_io::AsyncRead for $name<R> {
@@ -5,6 +5,8 @@
 mod macros;
 mod generic;
 
-pub(crate) use generic::{Decoder, Encoder};
+pub use self::generic::{Decoder, Encoder};
 
 algos!(futures::bufread<R>);
+auto_decoder!(futures::bufread<R>);
+chain_decoder!(futures::bufread<R>);
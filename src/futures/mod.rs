@@ -1,4 +1,8 @@
 //! Implementations for IO traits exported by `futures`.
 
 pub mod bufread;
+pub mod read;
+#[cfg(any(feature = "bgzf", feature = "zstd"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "bgzf", feature = "zstd"))))]
+pub mod seekable;
 pub mod write;
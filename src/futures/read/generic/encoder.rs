@@ -0,0 +1,126 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Result;
+
+use super::super::BufReader;
+use crate::{codec::Encode, futures::bufread};
+use futures_io::AsyncRead;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A generic encoder, for any [`Encode`](crate::codec::Encode) implementation.
+    ///
+    /// This structure implements an [`AsyncRead`](futures_io::AsyncRead) interface and will read
+    /// uncompressed data from an underlying stream, through an internal buffer, and emit a stream
+    /// of compressed data.
+    #[derive(Debug)]
+    pub struct Encoder<R, E: Encode> {
+        #[pin]
+        inner: bufread::Encoder<BufReader<R>, E>,
+    }
+}
+
+impl<R: AsyncRead, E: Encode> Encoder<R, E> {
+    /// Creates a new encoder which will read uncompressed data from the given stream and emit a
+    /// compressed stream, using `encoder` to compress it.
+    pub fn new(reader: R, encoder: E) -> Self {
+        Self {
+            inner: bufread::Encoder::new(BufReader::new(reader), encoder),
+        }
+    }
+
+    /// Like [`new`](Self::new), but with an input buffer of `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0.
+    pub fn with_capacity(capacity: usize, reader: R, encoder: E) -> Self {
+        Self {
+            inner: bufread::Encoder::new(BufReader::with_capacity(capacity, reader), encoder),
+        }
+    }
+
+    /// Changes the capacity of the input buffer, keeping any data which is currently buffered.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0.
+    pub fn set_buffer_capacity(&mut self, capacity: usize) {
+        self.inner.get_mut().set_capacity(capacity);
+    }
+
+    /// Acquires a reference to the underlying reader that this encoder is wrapping.
+    pub fn get_ref(&self) -> &R {
+        self.inner.get_ref().get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying reader that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this encoder.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.inner.get_mut().get_mut()
+    }
+
+    /// Acquires a pinned mutable reference to the underlying reader that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this encoder.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner.get_pin_mut().get_pin_mut()
+    }
+
+    /// Consumes this encoder returning the underlying reader.
+    ///
+    /// Note that this may discard internal state of this encoder, including any buffered input,
+    /// so care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner().into_inner()
+    }
+
+    /// Ends the current member/frame after the input consumed so far, as counted by
+    /// [`total_in`](Self::total_in), so that it can be decoded on its own. The next reads emit
+    /// the end of it, then encode any more input in a new member/frame.
+    ///
+    /// If the encoder cannot start a new member/frame this fails without ending it.
+    pub fn finish_member(&mut self) -> Result<()> {
+        self.inner.finish_member()
+    }
+}
+
+impl<R, E: Encode> Encoder<R, E> {
+    /// Acquires a reference to the encoder that this is using.
+    pub fn get_encoder_ref(&self) -> &E {
+        self.inner.get_encoder_ref()
+    }
+
+    /// Acquires a mutable reference to the encoder that this is using.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the encoder which may
+    /// otherwise confuse this adapter.
+    pub fn get_encoder_mut(&mut self) -> &mut E {
+        self.inner.get_encoder_mut()
+    }
+
+    /// Returns the number of bytes of uncompressed input consumed so far.
+    pub fn total_in(&self) -> u64 {
+        self.inner.total_in()
+    }
+
+    /// Returns the number of bytes of compressed output produced so far.
+    pub fn total_out(&self) -> u64 {
+        self.inner.total_out()
+    }
+}
+
+impl<R: AsyncRead, E: Encode> AsyncRead for Encoder<R, E> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        self.project().inner.poll_read(cx, buf)
+    }
+}
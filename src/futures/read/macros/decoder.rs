@@ -0,0 +1,133 @@
+macro_rules! decoder {
+    ($(#[$attr:meta])* $name:ident $({ $($constructor:tt)* })*) => {
+        pin_project_lite::pin_project! {
+            $(#[$attr])*
+            #[derive(Debug)]
+            ///
+            /// This structure implements an [`AsyncRead`](futures_io::AsyncRead) interface and will
+            /// read compressed data from an underlying stream, through an internal buffer, and emit a
+            /// stream of uncompressed data.
+            pub struct $name<R> {
+                #[pin]
+                inner: crate::futures::read::Decoder<R, crate::codec::$name>,
+            }
+        }
+
+        impl<R: futures_io::AsyncRead> $name<R> {
+            $(
+                /// Creates a new decoder which will read compressed data from the given stream and
+                /// emit a uncompressed stream.
+                ///
+                $($constructor)*
+            )*
+
+            /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
+            /// when reaching the end of a compressed member/frame and expect either EOF or another
+            /// compressed member/frame to follow it in the stream.
+            pub fn multiple_members(&mut self, enabled: bool) {
+                self.inner.multiple_members(enabled);
+            }
+
+            /// Configure what is done with any data following the end of the stream when not decoding
+            /// multiple members, by default it is [ignored](crate::TrailingData::Ignore) and left unread.
+            pub fn trailing_data(&mut self, mode: crate::TrailingData) {
+                self.inner.trailing_data(mode);
+            }
+
+            /// Sets the capacity of the internal input buffer, which is 8 KiB by default, for use
+            /// right after creating this decoder, e.g. `.with_buffer_capacity(64 * 1024)`.
+            ///
+            /// Any data already buffered is kept.
+            ///
+            /// # Panics
+            ///
+            /// If `capacity` is 0.
+            pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+                self.inner.set_buffer_capacity(capacity);
+                self
+            }
+
+            /// Limits the total size of the decompressed output, over all members, it is an error with a
+            /// [`LimitExceeded`](crate::LimitExceeded) inside to produce more than `limit` bytes.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits how many bytes of decompressed output can be produced per byte of
+            /// compressed input consumed, over all members, it is an error with a
+            /// [`LimitExceeded`](crate::LimitExceeded) inside to exceed it.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
+            }
+
+            /// Acquires a reference to the underlying reader that this decoder is wrapping.
+            pub fn get_ref(&self) -> &R {
+                self.inner.get_ref()
+            }
+
+            /// Acquires a mutable reference to the underlying reader that this decoder is
+            /// wrapping.
+            ///
+            /// Note that care must be taken to avoid tampering with the state of the reader which
+            /// may otherwise confuse this decoder.
+            pub fn get_mut(&mut self) -> &mut R {
+                self.inner.get_mut()
+            }
+
+            /// Acquires a pinned mutable reference to the underlying reader that this decoder is
+            /// wrapping.
+            ///
+            /// Note that care must be taken to avoid tampering with the state of the reader which
+            /// may otherwise confuse this decoder.
+            pub fn get_pin_mut(self: std::pin::Pin<&mut Self>) -> std::pin::Pin<&mut R> {
+                self.project().inner.get_pin_mut()
+            }
+
+            /// Consumes this decoder returning the underlying reader.
+            ///
+            /// Note that this may discard internal state of this decoder, so care should be taken
+            /// to avoid losing resources when this is called.
+            pub fn into_inner(self) -> R {
+                self.inner.into_inner()
+            }
+
+            /// Returns the number of bytes of compressed input consumed so far, over all members.
+            pub fn total_in(&self) -> u64 {
+                self.inner.total_in()
+            }
+
+            /// Returns the number of bytes of uncompressed output produced so far, over all members.
+            pub fn total_out(&self) -> u64 {
+                self.inner.total_out()
+            }
+
+            /// Returns the number of bytes read after the end of the stream so far, which are only read
+            /// when [`trailing_data`](Self::trailing_data) is not
+            /// [`Ignore`](crate::TrailingData::Ignore).
+            pub fn trailing_bytes(&self) -> u64 {
+                self.inner.trailing_bytes()
+            }
+        }
+
+        impl<R: futures_io::AsyncRead> futures_io::AsyncRead for $name<R> {
+            fn poll_read(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+                buf: &mut [u8],
+            ) -> std::task::Poll<std::io::Result<usize>> {
+                self.project().inner.poll_read(cx, buf)
+            }
+        }
+
+        const _: () = {
+            fn _assert() {
+                use crate::util::{_assert_send, _assert_sync};
+                use core::pin::Pin;
+                use futures_io::AsyncRead;
+
+                _assert_send::<$name<Pin<Box<dyn AsyncRead + Send>>>>();
+                _assert_sync::<$name<Pin<Box<dyn AsyncRead + Sync>>>>();
+            }
+        };
+    }
+}
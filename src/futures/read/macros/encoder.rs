@@ -0,0 +1,109 @@
+macro_rules! encoder {
+    ($(#[$attr:meta])* $name:ident<$inner:ident> $({ $($constructor:tt)* })*) => {
+        pin_project_lite::pin_project! {
+            $(#[$attr])*
+            #[derive(Debug)]
+            ///
+            /// This structure implements an [`AsyncRead`](futures_io::AsyncRead) interface and will
+            /// read uncompressed data from an underlying stream, through an internal buffer, and emit a
+            /// stream of compressed data.
+            pub struct $name<$inner> {
+                #[pin]
+                inner: crate::futures::read::Encoder<$inner, crate::codec::$name>,
+            }
+        }
+
+        impl<$inner: futures_io::AsyncRead> $name<$inner> {
+            $(
+                /// Creates a new encoder which will read uncompressed data from the given stream
+                /// and emit a compressed stream.
+                ///
+                $($constructor)*
+            )*
+
+            /// Sets the capacity of the internal input buffer, which is 8 KiB by default, for use
+            /// right after creating this encoder, e.g. `.with_buffer_capacity(64 * 1024)`.
+            ///
+            /// Any data already buffered is kept.
+            ///
+            /// # Panics
+            ///
+            /// If `capacity` is 0.
+            pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+                self.inner.set_buffer_capacity(capacity);
+                self
+            }
+
+            /// Acquires a reference to the underlying reader that this encoder is wrapping.
+            pub fn get_ref(&self) -> &$inner {
+                self.inner.get_ref()
+            }
+
+            /// Acquires a mutable reference to the underlying reader that this encoder is
+            /// wrapping.
+            ///
+            /// Note that care must be taken to avoid tampering with the state of the reader which
+            /// may otherwise confuse this encoder.
+            pub fn get_mut(&mut self) -> &mut $inner {
+                self.inner.get_mut()
+            }
+
+            /// Acquires a pinned mutable reference to the underlying reader that this encoder is
+            /// wrapping.
+            ///
+            /// Note that care must be taken to avoid tampering with the state of the reader which
+            /// may otherwise confuse this encoder.
+            pub fn get_pin_mut(self: std::pin::Pin<&mut Self>) -> std::pin::Pin<&mut $inner> {
+                self.project().inner.get_pin_mut()
+            }
+
+            /// Consumes this encoder returning the underlying reader.
+            ///
+            /// Note that this may discard internal state of this encoder, so care should be taken
+            /// to avoid losing resources when this is called.
+            pub fn into_inner(self) -> $inner {
+                self.inner.into_inner()
+            }
+
+            /// Ends the current member/frame after the input consumed so far, as counted by
+            /// [`total_in`](Self::total_in), so that it can be decoded on its own. The next reads
+            /// emit the end of it, then encode any more input in a new member/frame.
+            ///
+            /// If the encoder cannot start a new member/frame this fails without ending it.
+            pub fn finish_member(&mut self) -> std::io::Result<()> {
+                self.inner.finish_member()
+            }
+
+            /// Returns the number of bytes of uncompressed input consumed so far.
+            pub fn total_in(&self) -> u64 {
+                self.inner.total_in()
+            }
+
+            /// Returns the number of bytes of compressed output produced so far.
+            pub fn total_out(&self) -> u64 {
+                self.inner.total_out()
+            }
+        }
+
+        impl<$inner: futures_io::AsyncRead> futures_io::AsyncRead for $name<$inner> {
+            fn poll_read(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+                buf: &mut [u8],
+            ) -> std::task::Poll<std::io::Result<usize>> {
+                self.project().inner.poll_read(cx, buf)
+            }
+        }
+
+        const _: () = {
+            fn _assert() {
+                use crate::util::{_assert_send, _assert_sync};
+                use core::pin::Pin;
+                use futures_io::AsyncRead;
+
+                _assert_send::<$name<Pin<Box<dyn AsyncRead + Send>>>>();
+                _assert_sync::<$name<Pin<Box<dyn AsyncRead + Sync>>>>();
+            }
+        };
+    }
+}
@@ -0,0 +1,19 @@
+//! Types which operate over [`AsyncRead`](futures_io::AsyncRead) streams, both encoders and
+//! decoders for various formats.
+//!
+//! Unlike the types in [`bufread`](crate::futures::bufread) these manage their own input buffer,
+//! so the underlying reader does not need to be wrapped in a `BufReader`.
+
+#[macro_use]
+mod macros;
+mod generic;
+
+mod buf_reader;
+
+use self::buf_reader::BufReader;
+
+pub use self::generic::{Decoder, Encoder};
+
+algos!(futures::read<R>);
+auto_decoder!(futures::read<R>);
+chain_decoder!(futures::read<R>);
@@ -7,7 +7,7 @@ use futures_core::ready;
 use futures_io::{AsyncSeek, AsyncWrite, SeekFrom};
 use pin_project_lite::pin_project;
 use std::{
-    cmp::min,
+    cmp::{max, min},
     fmt, io,
     pin::Pin,
     task::{Context, Poll},
@@ -34,6 +34,7 @@ impl<W: AsyncWrite> BufWriter<W> {
 
     /// Creates a new `BufWriter` with the specified buffer capacity.
     pub fn with_capacity(cap: usize, inner: W) -> Self {
+        assert!(cap > 0, "buffer capacity must be non-zero");
         Self {
             inner,
             buf: vec![0; cap].into(),
@@ -42,6 +43,18 @@ impl<W: AsyncWrite> BufWriter<W> {
         }
     }
 
+    /// Changes the buffer capacity, keeping any data which is currently buffered, so the buffer
+    /// may end up larger than requested.
+    pub fn set_capacity(&mut self, cap: usize) {
+        assert!(cap > 0, "buffer capacity must be non-zero");
+        let buffered = &self.buf[self.written..self.buffered];
+        let mut buf = vec![0; max(cap, buffered.len())];
+        buf[..buffered.len()].copy_from_slice(buffered);
+        self.buf = buf.into();
+        self.buffered -= self.written;
+        self.written = 0;
+    }
+
     fn partial_flush_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         let mut this = self.project();
 
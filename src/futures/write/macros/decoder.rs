@@ -1,5 +1,5 @@
 macro_rules! decoder {
-    ($(#[$attr:meta])* $name:ident) => {
+    ($(#[$attr:meta])* $name:ident $(default($default:expr))? $({ $($constructor:tt)* })*) => {
         pin_project_lite::pin_project! {
             $(#[$attr])*
             #[derive(Debug)]
@@ -13,12 +13,69 @@ macro_rules! decoder {
         }
 
         impl<W: futures_io::AsyncWrite> $name<W> {
-            /// Creates a new decoder which will take in compressed data and write it uncompressedd
-            /// to the given stream.
-            pub fn new(read: W) -> $name<W> {
-                $name {
-                    inner: crate::futures::write::Decoder::new(read, crate::codec::$name::new()),
+            $(
+                /// Creates a new decoder which will take in compressed data and write it
+                /// uncompressed to the given stream.
+                ///
+                $($constructor)*
+            )*
+
+            $(
+                /// Creates a new decoder like [`new`](Self::new), but with an output buffer of
+                /// `capacity` bytes instead of the default 8 KiB.
+                ///
+                /// To set the capacity along with other settings, use
+                /// [`with_buffer_capacity`](Self::with_buffer_capacity) on the decoder from another
+                /// constructor instead.
+                ///
+                /// # Panics
+                ///
+                /// If `capacity` is 0.
+                pub fn with_capacity(capacity: usize, inner: W) -> Self {
+                    Self {
+                        inner: crate::futures::write::Decoder::with_capacity(
+                            capacity,
+                            inner,
+                            $default,
+                        ),
+                    }
                 }
+            )?
+
+            /// Sets the capacity of the internal output buffer, which is 8 KiB by default, for use
+            /// right after creating this decoder, e.g. `.with_buffer_capacity(64 * 1024)`.
+            ///
+            /// Any data already buffered is kept.
+            ///
+            /// # Panics
+            ///
+            /// If `capacity` is 0.
+            pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+                self.inner.set_buffer_capacity(capacity);
+                self
+            }
+
+            /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
+            /// when reaching the end of a compressed member/frame and expect either the end of the
+            /// input or another compressed member/frame to follow it.
+            ///
+            /// If disabled, writing anything after the end of the first member/frame is an error
+            /// with [`Error::TrailingData`](crate::Error) inside.
+            pub fn multiple_members(&mut self, enabled: bool) {
+                self.inner.multiple_members(enabled);
+            }
+
+            /// Limits the total size of the decompressed output, it is an error with a
+            /// [`LimitExceeded`](crate::LimitExceeded) inside to produce more than `limit` bytes.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits how many bytes of decompressed output can be produced per byte of
+            /// compressed input consumed, it is an error with a
+            /// [`LimitExceeded`](crate::LimitExceeded) inside to exceed it.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
             }
 
             /// Acquires a reference to the underlying reader that this decoder is wrapping.
@@ -51,6 +108,17 @@ macro_rules! decoder {
             pub fn into_inner(self) -> W {
                 self.inner.into_inner()
             }
+
+            /// Returns the number of bytes of compressed input consumed so far.
+            pub fn total_in(&self) -> u64 {
+                self.inner.total_in()
+            }
+
+            /// Returns the number of bytes of uncompressed output produced so far, including
+            /// any still in the output buffer.
+            pub fn total_out(&self) -> u64 {
+                self.inner.total_out()
+            }
         }
 
         impl<W: futures_io::AsyncWrite> futures_io::AsyncWrite for $name<W> {
@@ -1,5 +1,5 @@
 macro_rules! encoder {
-    ($(#[$attr:meta])* $name:ident<$inner:ident> $({ $($constructor:tt)* })*) => {
+    ($(#[$attr:meta])* $name:ident<$inner:ident> $(default($default:expr))? $({ $($constructor:tt)* })*) => {
         pin_project_lite::pin_project! {
             $(#[$attr])*
             #[derive(Debug)]
@@ -20,6 +20,60 @@ macro_rules! encoder {
                 $($constructor)*
             )*
 
+            $(
+                /// Creates a new encoder like [`new`](Self::new), but with an output buffer of
+                /// `capacity` bytes instead of the default 8 KiB.
+                ///
+                /// To set the capacity along with a different level, use
+                /// [`with_buffer_capacity`](Self::with_buffer_capacity) on the encoder from another
+                /// constructor, e.g.
+                /// `Self::with_quality(inner, level).with_buffer_capacity(capacity)`.
+                ///
+                /// # Panics
+                ///
+                /// If `capacity` is 0.
+                pub fn with_capacity(capacity: usize, inner: $inner) -> Self {
+                    Self {
+                        inner: crate::futures::write::Encoder::with_capacity(
+                            capacity,
+                            inner,
+                            $default,
+                        ),
+                    }
+                }
+            )?
+
+            /// Sets the capacity of the internal output buffer, which is 8 KiB by default, for use
+            /// right after creating this encoder, e.g. `.with_buffer_capacity(64 * 1024)`.
+            ///
+            /// Any data already buffered is kept.
+            ///
+            /// # Panics
+            ///
+            /// If `capacity` is 0.
+            pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+                self.inner.set_buffer_capacity(capacity);
+                self
+            }
+
+            /// Sets the kind of flush done when this encoder is flushed, which is
+            /// [`FlushMode::Sync`](crate::FlushMode::Sync) by default.
+            ///
+            /// Flushing with a mode this format doesn't support is an error.
+            pub fn flush_mode(&mut self, mode: crate::FlushMode) {
+                self.inner.flush_mode(mode);
+            }
+
+            /// Sets when this encoder flushes itself, which is
+            /// [`AutoFlush::Never`](crate::AutoFlush::Never) by default.
+            ///
+            /// An automatic flush which can't complete during the write that started it is left
+            /// pending, and is only finished by a later write or flush, so the caller must keep
+            /// driving one of those to get the data out.
+            pub fn auto_flush(&mut self, policy: crate::AutoFlush) {
+                self.inner.auto_flush(policy);
+            }
+
             /// Acquires a reference to the underlying writer that this encoder is wrapping.
             pub fn get_ref(&self) -> &$inner {
                 self.inner.get_ref()
@@ -46,10 +100,63 @@ macro_rules! encoder {
             /// Consumes this encoder returning the underlying writer.
             ///
             /// Note that this may discard internal state of this encoder, so care should be taken
-            /// to avoid losing resources when this is called.
+            /// to avoid losing resources when this is called, call [`finish`](Self::finish) first
+            /// to write out the end of the compressed stream.
             pub fn into_inner(self) -> $inner {
                 self.inner.into_inner()
             }
+
+            /// Writes out the end of the compressed stream and flushes it to the underlying
+            /// writer, like `poll_close` but leaving the underlying writer open so that more
+            /// can be written to it after [`into_inner`](Self::into_inner).
+            ///
+            /// Nothing more can be written to this encoder afterwards, though it can still be
+            /// flushed.
+            pub fn poll_finish(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<std::io::Result<()>> {
+                self.project().inner.poll_finish(cx)
+            }
+
+            /// Like [`poll_finish`](Self::poll_finish), as a future.
+            pub async fn finish(&mut self) -> std::io::Result<()>
+            where
+                $inner: Unpin,
+            {
+                self.inner.finish().await
+            }
+
+            /// Writes out the end of the current member/frame and flushes it to the underlying
+            /// writer, so that everything written so far can be decoded on its own. Anything
+            /// written afterwards is encoded in a new member/frame.
+            ///
+            /// If the encoder cannot start a new member/frame this fails without writing anything.
+            pub fn poll_end_member(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<std::io::Result<()>> {
+                self.project().inner.poll_end_member(cx)
+            }
+
+            /// Like [`poll_end_member`](Self::poll_end_member), as a future.
+            pub async fn finish_member(&mut self) -> std::io::Result<()>
+            where
+                $inner: Unpin,
+            {
+                self.inner.finish_member().await
+            }
+
+            /// Returns the number of bytes of uncompressed input consumed so far.
+            pub fn total_in(&self) -> u64 {
+                self.inner.total_in()
+            }
+
+            /// Returns the number of bytes of compressed output produced so far, including
+            /// any still in the output buffer.
+            pub fn total_out(&self) -> u64 {
+                self.inner.total_out()
+            }
         }
 
         impl<$inner: futures_io::AsyncWrite> futures_io::AsyncWrite for $name<$inner> {
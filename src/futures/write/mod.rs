@@ -8,10 +8,8 @@ mod generic;
 mod buf_write;
 mod buf_writer;
 
-use self::{
-    buf_write::AsyncBufWrite,
-    buf_writer::BufWriter,
-    generic::{Decoder, Encoder},
-};
+use self::{buf_write::AsyncBufWrite, buf_writer::BufWriter};
+
+pub use self::generic::{Decoder, Encoder};
 
 algos!(futures::write<W>);
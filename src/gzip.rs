@@ -0,0 +1,192 @@
+//! Types which are specific to the gzip format.
+
+/// The metadata stored in the header of a gzip member.
+///
+/// Headers read by a decoder can be inspected through its `header` method, and a header to write
+/// can be given to an encoder's `with_header` constructor after creating it with a
+/// [`GzipHeaderBuilder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GzipHeader {
+    pub(crate) text: bool,
+    pub(crate) header_crc: bool,
+    pub(crate) mtime: u32,
+    pub(crate) extra_flags: u8,
+    pub(crate) operating_system: u8,
+    pub(crate) extra: Option<Vec<u8>>,
+    pub(crate) filename: Option<Vec<u8>>,
+    pub(crate) comment: Option<Vec<u8>>,
+}
+
+impl Default for GzipHeader {
+    fn default() -> Self {
+        Self {
+            text: false,
+            header_crc: false,
+            mtime: 0,
+            extra_flags: 0,
+            operating_system: 255,
+            extra: None,
+            filename: None,
+            comment: None,
+        }
+    }
+}
+
+impl GzipHeader {
+    /// Whether the `FTEXT` flag is set, indicating the content is probably ASCII text.
+    pub fn is_text(&self) -> bool {
+        self.text
+    }
+
+    /// Whether the header is protected by a `FHCRC` checksum.
+    pub fn has_header_crc(&self) -> bool {
+        self.header_crc
+    }
+
+    /// The modification time of the original file as seconds since the Unix epoch, 0 means no
+    /// time was recorded.
+    pub fn mtime(&self) -> u32 {
+        self.mtime
+    }
+
+    /// The `XFL` byte, used by deflate to signal the compression level used.
+    pub fn extra_flags(&self) -> u8 {
+        self.extra_flags
+    }
+
+    /// The `OS` byte identifying the filesystem the member was created on, 255 means unknown.
+    pub fn operating_system(&self) -> u8 {
+        self.operating_system
+    }
+
+    /// The raw `FEXTRA` field, if present.
+    pub fn extra(&self) -> Option<&[u8]> {
+        self.extra.as_deref()
+    }
+
+    /// Iterates over the `(id, data)` subfields of the `FEXTRA` field, stopping at the first
+    /// subfield that does not fit in the field.
+    pub fn extra_subfields(&self) -> impl Iterator<Item = ([u8; 2], &[u8])> {
+        let mut extra = self.extra().unwrap_or_default();
+
+        std::iter::from_fn(move || {
+            if extra.len() < 4 {
+                return None;
+            }
+
+            let id = [extra[0], extra[1]];
+            let len = usize::from(u16::from_le_bytes([extra[2], extra[3]]));
+            let data = extra.get(4..4 + len)?;
+            extra = &extra[4 + len..];
+
+            Some((id, data))
+        })
+    }
+
+    /// The original file name, without the terminating zero byte, if present.
+    pub fn filename(&self) -> Option<&[u8]> {
+        self.filename.as_deref()
+    }
+
+    /// The file comment, without the terminating zero byte, if present.
+    pub fn comment(&self) -> Option<&[u8]> {
+        self.comment.as_deref()
+    }
+}
+
+/// A builder for the [`GzipHeader`] written by an encoder.
+///
+/// By default the header has no optional fields, a modification time of 0 and an unknown
+/// operating system, matching what encoders write when not given a header.
+#[derive(Clone, Debug, Default)]
+pub struct GzipHeaderBuilder {
+    header: GzipHeader,
+}
+
+impl GzipHeaderBuilder {
+    /// Creates a builder for the default header.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `FTEXT` flag, indicating the content is probably ASCII text.
+    pub fn text(mut self, text: bool) -> Self {
+        self.header.text = text;
+        self
+    }
+
+    /// Sets whether to protect the header with a `FHCRC` checksum.
+    pub fn header_crc(mut self, header_crc: bool) -> Self {
+        self.header.header_crc = header_crc;
+        self
+    }
+
+    /// Sets the modification time of the original file as seconds since the Unix epoch.
+    pub fn mtime(mut self, mtime: u32) -> Self {
+        self.header.mtime = mtime;
+        self
+    }
+
+    /// Sets the `OS` byte identifying the filesystem the data came from, e.g. 3 for Unix.
+    pub fn operating_system(mut self, operating_system: u8) -> Self {
+        self.header.operating_system = operating_system;
+        self
+    }
+
+    /// Appends a subfield with the given two byte `id` to the `FEXTRA` field, which can hold at
+    /// most 65535 bytes of subfields, see [`build`](Self::build).
+    pub fn extra_subfield(mut self, id: [u8; 2], data: &[u8]) -> Self {
+        let extra = self.header.extra.get_or_insert_with(Vec::new);
+        extra.extend_from_slice(&id);
+        extra.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        extra.extend_from_slice(data);
+        self
+    }
+
+    /// Sets the original file name, as restored by `gunzip -N`, which must not contain a zero
+    /// byte, see [`build`](Self::build).
+    pub fn filename(mut self, filename: impl Into<Vec<u8>>) -> Self {
+        self.header.filename = Some(filename.into());
+        self
+    }
+
+    /// Sets the file comment, which must not contain a zero byte, see [`build`](Self::build).
+    pub fn comment(mut self, comment: impl Into<Vec<u8>>) -> Self {
+        self.header.comment = Some(comment.into());
+        self
+    }
+
+    /// Returns the configured header.
+    ///
+    /// # Errors
+    ///
+    /// An [`InvalidInput`](std::io::ErrorKind::InvalidInput) error if the header can't be
+    /// written, because the `FEXTRA` field is longer than 65535 bytes or the file name or comment
+    /// contains a zero byte.
+    pub fn build(self) -> std::io::Result<GzipHeader> {
+        let invalid = |message| {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                message,
+            ))
+        };
+
+        if let Some(extra) = &self.header.extra {
+            if extra.len() > usize::from(u16::MAX) {
+                return invalid("gzip extra field too long");
+            }
+        }
+        if let Some(filename) = &self.header.filename {
+            if filename.contains(&0) {
+                return invalid("gzip filename contains a zero byte");
+            }
+        }
+        if let Some(comment) = &self.header.comment {
+            if comment.contains(&0) {
+                return invalid("gzip comment contains a zero byte");
+            }
+        }
+
+        Ok(self.header)
+    }
+}
@@ -0,0 +1,125 @@
+//! Helpers for the HTTP `Content-Encoding` and `Accept-Encoding` headers.
+//!
+//! Use [`parse_content_encoding`] with a `ChainDecoder` to decode a request or response body, and
+//! [`negotiate`], or the `AnyEncoder::negotiate` constructor, to pick how to encode a response.
+
+use crate::{Algorithm, ParseAlgorithmError};
+
+/// Parses the value of a `Content-Encoding` header into the algorithms it lists, in the order
+/// they were applied, e.g. `gzip, br` was compressed with gzip then brotli so must be decompressed
+/// with brotli then gzip.
+///
+/// Any `identity` codings are skipped, it is an error if any other coding is unknown or not
+/// enabled.
+pub fn parse_content_encoding(value: &str) -> Result<Vec<Algorithm>, ParseAlgorithmError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .filter_map(|token| match Algorithm::from_content_encoding(token) {
+            Some(Algorithm::Identity) => None,
+            Some(algorithm) => Some(Ok(algorithm)),
+            None => Some(Err(ParseAlgorithmError {
+                name: token.to_owned(),
+            })),
+        })
+        .collect()
+}
+
+/// Picks which of the server's `preferences` to encode a response with for a request with the
+/// given `Accept-Encoding` header value, returning `None` if none of them are acceptable and
+/// neither is `identity`, which should be answered with `406 Not Acceptable`.
+///
+/// The coding with the highest q-value wins, with ties going to whichever comes first in
+/// `preferences`. `identity` is acceptable unless it is excluded with `identity;q=0` or `*;q=0`,
+/// and is used when nothing else is or it has a higher q-value, even if it is not in
+/// `preferences`. Algorithms without a `Content-Encoding` token are ignored.
+///
+/// When the request has no `Accept-Encoding` header any coding is acceptable, but to be
+/// conservative it is best to not call this and send the response unencoded.
+pub fn negotiate(accept_encoding: &str, preferences: &[Algorithm]) -> Option<Algorithm> {
+    let mut best = None;
+    for &algorithm in preferences {
+        let q = match algorithm.content_encoding() {
+            Some(coding) => quality(accept_encoding, coding),
+            None => continue,
+        };
+        if q > best.map_or(0, |(_, best_q)| best_q) {
+            best = Some((algorithm, q));
+        }
+    }
+
+    let identity = quality(accept_encoding, "identity");
+    match best {
+        Some((algorithm, q)) if q >= identity => Some(algorithm),
+        _ if identity > 0 => Some(Algorithm::Identity),
+        _ => None,
+    }
+}
+
+/// The q-value in thousandths given to `coding` by an `Accept-Encoding` header value.
+///
+/// `identity` is given the lowest non-zero q-value if it is not mentioned, so that any coding the
+/// client explicitly accepts is preferred over it.
+fn quality(accept_encoding: &str, coding: &str) -> u16 {
+    let mut wildcard = None;
+    for (name, q) in accept_encoding.split(',').filter_map(parse_entry) {
+        let name = if name.eq_ignore_ascii_case("x-gzip") {
+            "gzip"
+        } else {
+            name
+        };
+
+        if name.eq_ignore_ascii_case(coding) {
+            return q;
+        }
+        if name == "*" {
+            wildcard = Some(q);
+        }
+    }
+
+    match wildcard {
+        Some(q) => q,
+        None if coding == "identity" => 1,
+        None => 0,
+    }
+}
+
+/// Parses a `coding;q=value` entry, returning `None` for empty or malformed entries so that they
+/// are ignored.
+fn parse_entry(entry: &str) -> Option<(&str, u16)> {
+    let mut parts = entry.split(';');
+    let name = parts.next()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut q = 1000;
+    for param in parts {
+        let (key, value) = param.split_once('=')?;
+        if key.trim().eq_ignore_ascii_case("q") {
+            q = parse_qvalue(value.trim())?;
+        }
+    }
+
+    Some((name, q))
+}
+
+/// Parses a q-value, a number from 0 to 1 with at most 3 decimal places, into thousandths.
+fn parse_qvalue(value: &str) -> Option<u16> {
+    let (whole, fraction) = match value.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (value, ""),
+    };
+
+    if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let fraction = format!("{:0<3}", fraction).parse::<u16>().ok()?;
+    match whole {
+        "0" => Some(fraction),
+        "1" if fraction == 0 => Some(1000),
+        _ => None,
+    }
+}
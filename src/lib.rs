@@ -31,11 +31,11 @@
 // that's unstable
 #![cfg_attr(
     feature = "futures-io",
-    doc = "[`futures-io`](crate::futures) | [`futures::io::AsyncBufRead`](futures_io::AsyncBufRead), [`futures::io::AsyncWrite`](futures_io::AsyncWrite)"
+    doc = "[`futures-io`](crate::futures) | [`futures::io::AsyncRead`](futures_io::AsyncRead), [`futures::io::AsyncBufRead`](futures_io::AsyncBufRead), [`futures::io::AsyncWrite`](futures_io::AsyncWrite)"
 )]
 #![cfg_attr(
     not(feature = "futures-io"),
-    doc = "`futures-io` (*inactive*) | `futures::io::AsyncBufRead`, `futures::io::AsyncWrite`"
+    doc = "`futures-io` (*inactive*) | `futures::io::AsyncRead`, `futures::io::AsyncBufRead`, `futures::io::AsyncWrite`"
 )]
 #![cfg_attr(
     feature = "futures-bufread",
@@ -55,29 +55,33 @@
 )]
 #![cfg_attr(
     feature = "tokio-02",
-    doc = "[`tokio-02`](crate::tokio_02) | [`tokio::io::AsyncBufRead`](::tokio_02::io::AsyncBufRead), [`tokio::io::AsyncWrite`](::tokio_02::io::AsyncWrite)"
+    doc = "[`tokio-02`](crate::tokio_02) | [`tokio::io::AsyncRead`](::tokio_02::io::AsyncRead), [`tokio::io::AsyncBufRead`](::tokio_02::io::AsyncBufRead), [`tokio::io::AsyncWrite`](::tokio_02::io::AsyncWrite)"
 )]
 #![cfg_attr(
     not(feature = "tokio-02"),
-    doc = "`tokio-02` (*inactive*) | `tokio::io::AsyncBufRead`, `tokio::io::AsyncWrite`"
+    doc = "`tokio-02` (*inactive*) | `tokio::io::AsyncRead`, `tokio::io::AsyncBufRead`, `tokio::io::AsyncWrite`"
 )]
 #![cfg_attr(
     feature = "tokio-03",
-    doc = "[`tokio-03`](crate::tokio_03) | [`tokio::io::AsyncBufRead`](::tokio_03::io::AsyncBufRead), [`tokio::io::AsyncWrite`](::tokio_03::io::AsyncWrite)"
+    doc = "[`tokio-03`](crate::tokio_03) | [`tokio::io::AsyncRead`](::tokio_03::io::AsyncRead), [`tokio::io::AsyncBufRead`](::tokio_03::io::AsyncBufRead), [`tokio::io::AsyncWrite`](::tokio_03::io::AsyncWrite)"
 )]
 #![cfg_attr(
     not(feature = "tokio-03"),
-    doc = "`tokio-03` (*inactive*) | `tokio::io::AsyncBufRead`, `tokio::io::AsyncWrite`"
+    doc = "`tokio-03` (*inactive*) | `tokio::io::AsyncRead`, `tokio::io::AsyncBufRead`, `tokio::io::AsyncWrite`"
 )]
 #![cfg_attr(
     feature = "tokio",
-    doc = "[`tokio`](crate::tokio) | [`tokio::io::AsyncBufRead`](::tokio::io::AsyncBufRead), [`tokio::io::AsyncWrite`](::tokio::io::AsyncWrite)"
+    doc = "[`tokio`](crate::tokio) | [`tokio::io::AsyncRead`](::tokio::io::AsyncRead), [`tokio::io::AsyncBufRead`](::tokio::io::AsyncBufRead), [`tokio::io::AsyncWrite`](::tokio::io::AsyncWrite)"
 )]
 #![cfg_attr(
     not(feature = "tokio"),
-    doc = "`tokio` (*inactive*) | `tokio::io::AsyncBufRead`, `tokio::io::AsyncWrite`"
+    doc = "`tokio` (*inactive*) | `tokio::io::AsyncRead`, `tokio::io::AsyncBufRead`, `tokio::io::AsyncWrite`"
 )]
 //!
+//! The `tokio-time` feature additionally lets the `tokio` write encoders flush themselves once
+//! they have been idle for a while, see `flush_when_idle` on each of them. It enables tokio's
+//! `time` feature, so the encoders must then be used within a runtime with the time driver.
+//!
 
 //! ## Compression algorithm
 //!
@@ -87,6 +91,14 @@
 
 //!  Feature | Types
 //! ---------|------
+#![cfg_attr(
+    feature = "bgzf",
+    doc = "`bgzf` | [`BgzfEncoder`](?search=BgzfEncoder), [`BgzfDecoder`](?search=BgzfDecoder)"
+)]
+#![cfg_attr(
+    not(feature = "bgzf"),
+    doc = "`bgzf` (*inactive*) | `BgzfEncoder`, `BgzfDecoder`"
+)]
 #![cfg_attr(
     feature = "brotli",
     doc = "`brotli` | [`BrotliEncoder`](?search=BrotliEncoder), [`BrotliDecoder`](?search=BrotliDecoder)"
@@ -119,6 +131,14 @@
     not(feature = "gzip"),
     doc = "`gzip` (*inactive*) | `GzipEncoder`, `GzipDecoder`"
 )]
+#![cfg_attr(
+    feature = "lz4",
+    doc = "`lz4` | [`Lz4Encoder`](?search=Lz4Encoder), [`Lz4Decoder`](?search=Lz4Decoder)"
+)]
+#![cfg_attr(
+    not(feature = "lz4"),
+    doc = "`lz4` (*inactive*) | `Lz4Encoder`, `Lz4Decoder`"
+)]
 #![cfg_attr(
     feature = "lzma",
     doc = "`lzma` | [`LzmaEncoder`](?search=LzmaEncoder), [`LzmaDecoder`](?search=LzmaDecoder)"
@@ -127,6 +147,14 @@
     not(feature = "lzma"),
     doc = "`lzma` (*inactive*) | `LzmaEncoder`, `LzmaDecoder`"
 )]
+#![cfg_attr(
+    feature = "snappy",
+    doc = "`snappy` | [`SnappyEncoder`](?search=SnappyEncoder), [`SnappyDecoder`](?search=SnappyDecoder)"
+)]
+#![cfg_attr(
+    not(feature = "snappy"),
+    doc = "`snappy` (*inactive*) | `SnappyEncoder`, `SnappyDecoder`"
+)]
 #![cfg_attr(
     feature = "xz",
     doc = "`xz` | [`XzEncoder`](?search=XzEncoder), [`XzDecoder`](?search=XzDecoder)"
@@ -153,6 +181,27 @@
 )]
 //!
 
+//! ### Pure-Rust backends
+//!
+//! Some algorithms can instead be backed by pure-Rust implementations, avoiding the need for a C
+//! toolchain when cross-compiling or targeting WebAssembly:
+//!
+//!  Feature | Does
+//! ---------|------
+//!  `miniz_oxide` | Uses `miniz_oxide` for the `deflate`, `gzip` and `zlib` encoders and decoders, instead of `flate2`'s backend
+//!  `ruzstd` | Provides `ZstdDecoder` without the `zstd` feature, there is no encoder or support for dictionaries
+//!  `lzma-rs` | Provides `XzDecoder` and `LzmaDecoder` without the `xz` and `lzma` features, there are no encoders
+//!
+//! When both a C-backed algorithm feature and its pure-Rust alternative are enabled the C-backed
+//! implementation is used.
+//!
+//! `lzma-rs` can't decode xz streams incrementally, so its `XzDecoder` buffers each stream and
+//! only produces output once all of it has been read. Streams over 32 MiB, or which decompress to
+//! over 256 MiB, are rejected with a [`MemoryLimit`](Error::MemoryLimit) error. Its lzma decoder
+//! does stream, but an lzma stream of unknown size, ended by an end marker, can't be followed by
+//! any other data.
+//!
+
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(
     missing_docs,
@@ -164,11 +213,18 @@
 
 #[macro_use]
 mod macros;
-mod codec;
+pub mod codec;
 
+#[cfg(feature = "bgzf")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bgzf")))]
+pub mod bgzf;
 #[cfg(feature = "futures-io")]
 #[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
 pub mod futures;
+#[cfg(feature = "gzip")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+pub mod gzip;
+pub mod http;
 #[cfg(feature = "stream")]
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 pub mod stream;
@@ -181,10 +237,29 @@ pub mod tokio_02;
 #[cfg(feature = "tokio-03")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio-03")))]
 pub mod tokio_03;
+#[cfg(feature = "zlib")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zlib")))]
+pub mod zlib;
+#[cfg(feature = "zstd")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zstd")))]
+pub mod zstd;
 
+mod algorithm;
+mod error;
+mod flush;
+mod limit;
+mod trailing;
 mod unshared;
 mod util;
 
+pub use self::{
+    algorithm::{Algorithm, ParseAlgorithmError},
+    error::Error,
+    flush::{AutoFlush, FlushMode},
+    limit::LimitExceeded,
+    trailing::TrailingData,
+};
+
 #[cfg(feature = "brotli")]
 use brotli::enc::backward_references::BrotliEncoderParams;
 
@@ -238,6 +313,16 @@ impl Level {
         }
     }
 
+    #[cfg(feature = "lz4")]
+    fn into_lz4(self) -> u32 {
+        match self {
+            Self::Fastest => 0,
+            Self::Best => 12,
+            Self::Precise(quality) => quality.min(12),
+            Self::Default => 0,
+        }
+    }
+
     #[cfg(feature = "zstd")]
     fn into_zstd(self) -> i32 {
         match self {
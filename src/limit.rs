@@ -0,0 +1,78 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, ErrorKind},
+};
+
+/// The error given when a decoder produces more output than it is allowed to, see
+/// `max_output_size` and `max_ratio` on each decoder.
+///
+/// This is carried inside an [`io::Error`] of kind [`InvalidData`](ErrorKind::InvalidData), and
+/// can be found with `error.get_ref().and_then(|e| e.downcast_ref::<LimitExceeded>())`. Other
+/// failures caused by the compressed data are reported with [`Error`](crate::Error).
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitExceeded {
+    /// More than `limit` bytes were decompressed.
+    OutputSize {
+        /// The maximum allowed.
+        limit: u64,
+    },
+    /// More than `limit` bytes were decompressed per byte of compressed input.
+    Ratio {
+        /// The maximum allowed.
+        limit: u64,
+    },
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutputSize { limit } => {
+                write!(
+                    f,
+                    "decompressed output exceeded the limit of {} bytes",
+                    limit
+                )
+            }
+            Self::Ratio { limit } => {
+                write!(f, "decompression ratio exceeded the limit of {}", limit)
+            }
+        }
+    }
+}
+
+impl Error for LimitExceeded {}
+
+/// The limits on how much output a decoder is allowed to produce, to protect against
+/// decompression bombs.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Limits {
+    pub(crate) max_output_size: Option<u64>,
+    pub(crate) max_ratio: Option<u64>,
+}
+
+impl Limits {
+    /// Checks the totals so far, over all members, against the limits.
+    pub(crate) fn check(&self, total_in: u64, total_out: u64) -> io::Result<()> {
+        if let Some(limit) = self.max_output_size {
+            if total_out > limit {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    LimitExceeded::OutputSize { limit },
+                ));
+            }
+        }
+
+        if let Some(limit) = self.max_ratio {
+            if total_out > total_in.saturating_mul(limit) {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    LimitExceeded::Ratio { limit },
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
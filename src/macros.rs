@@ -1,30 +1,172 @@
 macro_rules! algos {
-    (@algo $algo:ident [$algo_s:expr] $decoder:ident $encoder:ident<$inner:ident> $({ $($constructor:tt)* })*) => {
-        #[cfg(feature = $algo_s)]
-        decoder! {
+    (@algo $($mod:ident)::+; $algo:ident [$algo_s:expr $(, $decoder_s:expr)*] $decoder:ident $encoder:ident<$inner:ident> default($default:expr) $({ $($constructor:tt)* })* $(decoder { $($decoder_constructor:tt)* })*) => {
+        #[cfg(any(feature = $algo_s $(, feature = $decoder_s)*))]
+        algos!(@decoder $($mod)::+;
             /// A
             #[doc = $algo_s]
             /// decoder, or decompressor.
-            #[cfg_attr(docsrs, doc(cfg(feature = $algo_s)))]
-            $decoder
-        }
+            #[cfg_attr(docsrs, doc(cfg(any(feature = $algo_s $(, feature = $decoder_s)*))))]
+            $decoder default(crate::codec::$decoder::new()) {
+                pub fn new(inner: $inner) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::$decoder::new(),
+                        ),
+                    }
+                }
+            } $({ $($decoder_constructor)* })*
+        );
 
         #[cfg(feature = $algo_s)]
-        encoder! {
+        algos!(@encoder $($mod)::+;
             /// A
             #[doc = $algo_s]
             /// encoder, or compressor.
             #[cfg_attr(docsrs, doc(cfg(feature = $algo_s)))]
-            $encoder<$inner> {
+            $encoder<$inner> default($default) {
                 pub fn new(inner: $inner) -> Self {
                     Self::with_quality(inner, crate::Level::Default)
                 }
             } $({ $($constructor)* })*
-        }
+        );
+    };
+
+    // Only the write adaptors use the default codec, to build `with_capacity` from.
+    (@decoder $io:ident::write; $($tokens:tt)*) => {
+        decoder! { $($tokens)* }
+    };
+
+    (@decoder $($mod:ident)::+; $(#[$attr:meta])* $name:ident default($default:expr) $($tokens:tt)*) => {
+        decoder! { $(#[$attr])* $name $($tokens)* }
+    };
+
+    (@encoder $io:ident::write; $($tokens:tt)*) => {
+        encoder! { $($tokens)* }
+    };
+
+    (@encoder $($mod:ident)::+; $(#[$attr:meta])* $name:ident<$inner:ident> default($default:expr) $($tokens:tt)*) => {
+        encoder! { $(#[$attr])* $name<$inner> $($tokens)* }
     };
 
     ($($mod:ident)::+<$inner:ident>) => {
-        algos!(@algo brotli ["brotli"] BrotliDecoder BrotliEncoder<$inner> {
+        encoder! {
+            /// An encoder, or compressor, for an [`Algorithm`](crate::Algorithm) chosen at
+            /// runtime.
+            AnyEncoder<$inner> {
+                /// Fails if only a decoder is enabled for `algorithm`.
+                pub fn new(inner: $inner, algorithm: crate::Algorithm) -> std::io::Result<Self> {
+                    Self::with_quality(inner, algorithm, crate::Level::Default)
+                }
+            } {
+                /// Fails if only a decoder is enabled for `algorithm`.
+                pub fn with_quality(
+                    inner: $inner,
+                    algorithm: crate::Algorithm,
+                    level: crate::Level,
+                ) -> std::io::Result<Self> {
+                    Ok(Self {
+                        inner: crate::$($mod::)+generic::Encoder::new(
+                            inner,
+                            crate::codec::AnyEncoder::new(algorithm, level)?,
+                        ),
+                    })
+                }
+            } {
+                /// Picks the algorithm for a response to a request with the given
+                /// `Accept-Encoding` header value, using whichever of `preferences` with an
+                /// encoder enabled is picked by [`http::negotiate`](crate::http::negotiate).
+                ///
+                /// Returns `None` if nothing is acceptable, which should be answered with
+                /// `406 Not Acceptable`. Otherwise the `Content-Encoding` to send is
+                /// [`encoder.algorithm().content_encoding()`](crate::Algorithm::content_encoding).
+                pub fn negotiate(
+                    inner: $inner,
+                    accept_encoding: &str,
+                    preferences: &[crate::Algorithm],
+                ) -> Option<Self> {
+                    let preferences: Vec<_> = preferences
+                        .iter()
+                        .copied()
+                        .filter(|&algorithm| crate::codec::AnyEncoder::is_supported(algorithm))
+                        .collect();
+                    let algorithm = crate::http::negotiate(accept_encoding, &preferences)?;
+                    Some(Self::new(inner, algorithm).expect("algorithm has an encoder"))
+                }
+            }
+        }
+
+        impl<$inner> AnyEncoder<$inner> {
+            /// Returns the algorithm this encoder is using.
+            pub fn algorithm(&self) -> crate::Algorithm {
+                self.inner.get_encoder_ref().algorithm()
+            }
+        }
+
+        decoder! {
+            /// A decoder, or decompressor, for an [`Algorithm`](crate::Algorithm) chosen at
+            /// runtime.
+            AnyDecoder {
+                pub fn new(inner: $inner, algorithm: crate::Algorithm) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::AnyDecoder::new(algorithm),
+                        ),
+                    }
+                }
+            }
+        }
+
+        impl<$inner> AnyDecoder<$inner> {
+            /// Returns the algorithm this decoder is using.
+            pub fn algorithm(&self) -> crate::Algorithm {
+                self.inner.get_decoder_ref().algorithm()
+            }
+        }
+
+        algos!(@algo $($mod)::+; bgzf ["bgzf"] BgzfDecoder BgzfEncoder<$inner>
+            default(crate::codec::BgzfEncoder::new(crate::Level::Default.into_flate2())) {
+            pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::BgzfEncoder::new(level.into_flate2()),
+                    ),
+                }
+            }
+        } decoder {
+            /// Starts decoding from the given virtual offset, `inner` must already be positioned
+            /// at [`offset.compressed()`](crate::bgzf::VirtualOffset::compressed) in the BGZF
+            /// file, e.g. by seeking it there, and the first
+            /// [`offset.uncompressed()`](crate::bgzf::VirtualOffset::uncompressed) bytes of
+            /// uncompressed data will be skipped.
+            ///
+            /// With a seekable stream, `BgzfSeekableDecoder` from the `seekable` module does the
+            /// seek itself.
+            pub fn with_virtual_offset(inner: $inner, offset: crate::bgzf::VirtualOffset) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::BgzfDecoder::with_skip(offset.uncompressed()),
+                    ),
+                }
+            }
+        });
+
+        #[cfg(feature = "bgzf")]
+        impl<$inner> BgzfEncoder<$inner> {
+            /// Returns the virtual offset of the next byte of uncompressed data given to this
+            /// encoder, which can be recorded in an index to later start decoding from there.
+            pub fn virtual_offset(&self) -> crate::bgzf::VirtualOffset {
+                self.inner.get_encoder_ref().virtual_offset()
+            }
+        }
+
+        algos!(@algo $($mod)::+; brotli ["brotli"] BrotliDecoder BrotliEncoder<$inner>
+            default(crate::codec::BrotliEncoder::new(
+                crate::Level::Default.into_brotli(Default::default()),
+            )) {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 let params = brotli::enc::backward_references::BrotliEncoderParams::default();
                 Self {
@@ -36,7 +178,8 @@ macro_rules! algos {
             }
         });
 
-        algos!(@algo bzip2 ["bzip2"] BzDecoder BzEncoder<$inner> {
+        algos!(@algo $($mod)::+; bzip2 ["bzip2"] BzDecoder BzEncoder<$inner>
+            default(crate::codec::BzEncoder::new(crate::Level::Default.into_bzip2(), 0)) {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -47,7 +190,8 @@ macro_rules! algos {
             }
         });
 
-        algos!(@algo deflate ["deflate"] DeflateDecoder DeflateEncoder<$inner> {
+        algos!(@algo $($mod)::+; deflate ["deflate"] DeflateDecoder DeflateEncoder<$inner>
+            default(crate::codec::DeflateEncoder::new(crate::Level::Default.into_flate2())) {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -56,9 +200,38 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// Uses the given preset `dictionary`, which must also be given to the decoder.
+            pub fn with_dictionary(
+                inner: $inner,
+                level: crate::Level,
+                dictionary: &[u8],
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::DeflateEncoder::with_dictionary(
+                            level.into_flate2(),
+                            dictionary,
+                        )?,
+                    ),
+                })
+            }
+        } decoder {
+            /// Uses the given preset `dictionary`, which must be the same as was used by the
+            /// encoder.
+            pub fn with_dictionary(inner: $inner, dictionary: &[u8]) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::DeflateDecoder::with_dictionary(dictionary)?,
+                    ),
+                })
+            }
         });
 
-        algos!(@algo gzip ["gzip"] GzipDecoder GzipEncoder<$inner> {
+        algos!(@algo $($mod)::+; gzip ["gzip"] GzipDecoder GzipEncoder<$inner>
+            default(crate::codec::GzipEncoder::new(crate::Level::Default.into_flate2())) {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -67,9 +240,72 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// Writes the given `header`, built with a
+            /// [`GzipHeaderBuilder`](crate::gzip::GzipHeaderBuilder), instead of the default
+            /// minimal one.
+            pub fn with_header(
+                inner: $inner,
+                level: crate::Level,
+                header: &crate::gzip::GzipHeader,
+            ) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::GzipEncoder::with_header(level.into_flate2(), header),
+                    ),
+                }
+            }
         });
 
-        algos!(@algo zlib ["zlib"] ZlibDecoder ZlibEncoder<$inner> {
+        #[cfg(feature = "gzip")]
+        impl<$inner> GzipDecoder<$inner> {
+            /// Returns the header of the gzip member currently being decoded, once it has been
+            /// read.
+            ///
+            /// When decoding multiple members this is replaced as each member's header is read,
+            /// use [`on_member_header`](Self::on_member_header) to observe every one of them.
+            pub fn header(&self) -> Option<&crate::gzip::GzipHeader> {
+                self.inner.get_decoder_ref().header()
+            }
+
+            /// Registers a callback which is called with the header of each gzip member as soon as
+            /// it has been read.
+            pub fn on_member_header(
+                &mut self,
+                callback: impl FnMut(&crate::gzip::GzipHeader) + Send + 'static,
+            ) {
+                self.inner.get_decoder_mut().on_header(callback);
+            }
+        }
+
+        algos!(@algo $($mod)::+; lz4 ["lz4"] Lz4Decoder Lz4Encoder<$inner>
+            default(crate::codec::Lz4Encoder::new(crate::Level::Default.into_lz4())) {
+            pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::Lz4Encoder::new(level.into_lz4()),
+                    ),
+                }
+            }
+        });
+
+        algos!(@algo $($mod)::+; snappy ["snappy"] SnappyDecoder SnappyEncoder<$inner>
+            default(crate::codec::SnappyEncoder::new()) {
+            /// The Snappy format has no compression levels, so `level` is ignored.
+            pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::SnappyEncoder::new(),
+                    ),
+                }
+            }
+        });
+
+        algos!(@algo $($mod)::+; zlib ["zlib"] ZlibDecoder ZlibEncoder<$inner>
+            default(crate::codec::ZlibEncoder::new(crate::Level::Default.into_flate2())) {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -78,9 +314,54 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// Uses the given preset `dictionary`, its id is written in the header so that the
+            /// decoder can check it has been given the same one.
+            pub fn with_dictionary(
+                inner: $inner,
+                level: crate::Level,
+                dictionary: &[u8],
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZlibEncoder::with_dictionary(
+                            level.into_flate2(),
+                            dictionary,
+                        )?,
+                    ),
+                })
+            }
+        } decoder {
+            /// Uses the given preset `dictionary` when a stream needs one, it is an error if a
+            /// stream needs a different dictionary.
+            pub fn with_dictionary(inner: $inner, dictionary: &[u8]) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::ZlibDecoder::with_dictionary(dictionary),
+                    ),
+                }
+            }
+        } decoder {
+            /// Calls `callback` with the id of the preset dictionary when a stream needs one, see
+            /// [`zlib::dictionary_id`](crate::zlib::dictionary_id). It is an error if it returns
+            /// `None` or a dictionary with a different id.
+            pub fn with_dictionary_callback(
+                inner: $inner,
+                callback: impl FnMut(u32) -> Option<Vec<u8>> + Send + 'static,
+            ) -> Self {
+                Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::ZlibDecoder::with_dictionary_callback(callback),
+                    ),
+                }
+            }
         });
 
-        algos!(@algo zstd ["zstd"] ZstdDecoder ZstdEncoder<$inner> {
+        algos!(@algo $($mod)::+; zstd ["zstd", "ruzstd"] ZstdDecoder ZstdEncoder<$inner>
+            default(crate::codec::ZstdEncoder::new(crate::Level::Default.into_zstd())) {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -89,9 +370,138 @@ macro_rules! algos {
                     ),
                 }
             }
+        } {
+            /// Uses the given advanced compression `params`, which can fail if any of them are
+            /// out of range.
+            pub fn with_params(
+                inner: $inner,
+                params: &crate::zstd::EncoderParams,
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZstdEncoder::with_params(params)?,
+                    ),
+                })
+            }
+        } {
+            /// Uses the given pre-trained `dictionary`, which must also be given to the decoder.
+            pub fn with_dict(
+                inner: $inner,
+                level: crate::Level,
+                dictionary: &[u8],
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZstdEncoder::with_dict(level.into_zstd(), dictionary)?,
+                    ),
+                })
+            }
+        } {
+            /// Uses the given prepared `dictionary`, which can be shared between many encoders
+            /// rather than loading it again for each stream.
+            pub fn with_prepared_dict(
+                inner: $inner,
+                dictionary: &crate::zstd::EncoderDictionary,
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Encoder::new(
+                        inner,
+                        crate::codec::ZstdEncoder::with_prepared_dict(dictionary)?,
+                    ),
+                })
+            }
+        } decoder {
+            /// Uses the given advanced decompression `params`, which can fail if any of them are
+            /// out of range.
+            #[cfg(feature = "zstd")]
+            pub fn with_params(
+                inner: $inner,
+                params: &crate::zstd::DecoderParams,
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::ZstdDecoder::with_params(params)?,
+                    ),
+                })
+            }
+        } decoder {
+            /// Uses the given pre-trained `dictionary`, which must be the same as was used by the
+            /// encoder.
+            #[cfg(feature = "zstd")]
+            pub fn with_dict(inner: $inner, dictionary: &[u8]) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::ZstdDecoder::with_dict(dictionary)?,
+                    ),
+                })
+            }
+        } decoder {
+            /// Uses the given prepared `dictionary`, which can be shared between many decoders
+            /// rather than loading it again for each stream.
+            #[cfg(feature = "zstd")]
+            pub fn with_prepared_dict(
+                inner: $inner,
+                dictionary: &crate::zstd::DecoderDictionary,
+            ) -> std::io::Result<Self> {
+                Ok(Self {
+                    inner: crate::$($mod::)+generic::Decoder::new(
+                        inner,
+                        crate::codec::ZstdDecoder::with_prepared_dict(dictionary)?,
+                    ),
+                })
+            }
         });
 
-        algos!(@algo xz ["xz"] XzDecoder XzEncoder<$inner> {
+        #[cfg(feature = "zstd")]
+        encoder! {
+            /// A zstd encoder, or compressor, producing the
+            /// [seekable format](https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md),
+            /// which splits the data into independent frames followed by a seek table so that any
+            /// range of it can be decompressed without decompressing everything before it.
+            ///
+            /// The output can still be decompressed as a whole by any zstd decoder.
+            #[cfg_attr(docsrs, doc(cfg(feature = "zstd")))]
+            ZstdSeekableEncoder<$inner> {
+                pub fn new(inner: $inner) -> Self {
+                    Self::with_quality(inner, crate::Level::Default)
+                }
+            } {
+                /// Frames will contain at most 1 MiB of uncompressed data.
+                pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Encoder::new(
+                            inner,
+                            crate::codec::ZstdSeekableEncoder::new(level.into_zstd()),
+                        ),
+                    }
+                }
+            } {
+                /// Frames will contain at most `frame_size` bytes of uncompressed data, smaller
+                /// frames make seeking cheaper at the cost of a worse compression ratio.
+                ///
+                /// # Panics
+                ///
+                /// If `frame_size` is 0 or more than 1 GiB.
+                pub fn with_frame_size(inner: $inner, level: crate::Level, frame_size: u32) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Encoder::new(
+                            inner,
+                            crate::codec::ZstdSeekableEncoder::with_frame_size(
+                                level.into_zstd(),
+                                frame_size,
+                            ),
+                        ),
+                    }
+                }
+            }
+        }
+
+        algos!(@algo $($mod)::+; xz ["xz", "lzma-rs"] XzDecoder XzEncoder<$inner>
+            default(crate::codec::XzEncoder::new(crate::Level::Default.into_xz2())) {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -102,7 +512,8 @@ macro_rules! algos {
             }
         });
 
-        algos!(@algo lzma ["lzma"] LzmaDecoder LzmaEncoder<$inner> {
+        algos!(@algo $($mod)::+; lzma ["lzma", "lzma-rs"] LzmaDecoder LzmaEncoder<$inner>
+            default(crate::codec::LzmaEncoder::new(crate::Level::Default.into_xz2())) {
             pub fn with_quality(inner: $inner, level: crate::Level) -> Self {
                 Self {
                     inner: crate::$($mod::)+generic::Encoder::new(
@@ -114,3 +525,80 @@ macro_rules! algos {
         });
     }
 }
+
+macro_rules! auto_decoder {
+    ($($mod:ident)::+<$inner:ident>) => {
+        decoder! {
+            /// A decoder, or decompressor, which detects the compression format from the first
+            /// bytes of the stream.
+            ///
+            /// Gzip, zstd, xz, bzip2, lz4 and framed snappy streams are recognized by their magic
+            /// bytes and zlib streams by their header check and the start of their first block, as
+            /// long as the features for them are enabled. Formats without a reliable signature,
+            /// brotli, raw deflate and lzma, can only be decoded by giving them as the fallback.
+            AutoDecoder {
+                /// It is an [`UnrecognizedFormat`](crate::Error::UnrecognizedFormat) error if the
+                /// format is not recognized.
+                pub fn new(inner: $inner) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::AutoDecoder::new(None),
+                        ),
+                    }
+                }
+            } {
+                /// Uses `fallback` if the format is not recognized, e.g.
+                /// [`Algorithm::Identity`](crate::Algorithm::Identity) to pass through
+                /// uncompressed data unchanged.
+                pub fn with_fallback(inner: $inner, fallback: crate::Algorithm) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::AutoDecoder::new(Some(fallback)),
+                        ),
+                    }
+                }
+            }
+        }
+
+        impl<$inner> AutoDecoder<$inner> {
+            /// Returns the algorithm detected, or `None` if not enough of the stream has been read
+            /// to detect it yet.
+            pub fn detected(&self) -> Option<crate::Algorithm> {
+                self.inner.get_decoder_ref().detected()
+            }
+        }
+    };
+}
+
+macro_rules! chain_decoder {
+    ($($mod:ident)::+<$inner:ident>) => {
+        decoder! {
+            /// A decoder, or decompressor, for data which has had several algorithms applied to it
+            /// in turn, such as an HTTP body with multiple content codings.
+            ChainDecoder {
+                /// `algorithms` are in the order they were applied, so are undone in reverse
+                /// order, if there are none the data is passed through unchanged.
+                pub fn new(inner: $inner, algorithms: &[crate::Algorithm]) -> Self {
+                    Self {
+                        inner: crate::$($mod::)+generic::Decoder::new(
+                            inner,
+                            crate::codec::ChainDecoder::new(algorithms),
+                        ),
+                    }
+                }
+            } {
+                /// Undoes the codings listed in the value of a `Content-Encoding` header, see
+                /// [`http::parse_content_encoding`](crate::http::parse_content_encoding).
+                pub fn from_content_encoding(
+                    inner: $inner,
+                    content_encoding: &str,
+                ) -> Result<Self, crate::ParseAlgorithmError> {
+                    let algorithms = crate::http::parse_content_encoding(content_encoding)?;
+                    Ok(Self::new(inner, &algorithms))
+                }
+            }
+        }
+    };
+}
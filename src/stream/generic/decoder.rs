@@ -4,7 +4,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{codec::Decode, limit::Limits, util::PartialBuffer};
 use bytes_05::{Buf, Bytes, BytesMut};
 use futures_core::{ready, stream::Stream};
 use pin_project_lite::pin_project;
@@ -30,6 +30,9 @@ pin_project! {
         input: Bytes,
         output: BytesMut,
         multiple_members: bool,
+        limits: Limits,
+        total_in: u64,
+        total_out: u64,
     }
 }
 
@@ -42,6 +45,9 @@ impl<S: Stream<Item = Result<Bytes>>, D: Decode> Decoder<S, D> {
             input: Bytes::new(),
             output: BytesMut::new(),
             multiple_members: false,
+            limits: Limits::default(),
+            total_in: 0,
+            total_out: 0,
         }
     }
 
@@ -64,6 +70,32 @@ impl<S: Stream<Item = Result<Bytes>>, D: Decode> Decoder<S, D> {
     pub fn multiple_members(&mut self, enabled: bool) {
         self.multiple_members = enabled;
     }
+
+    pub fn max_output_size(&mut self, limit: Option<u64>) {
+        self.limits.max_output_size = limit;
+    }
+
+    pub fn max_ratio(&mut self, limit: Option<u64>) {
+        self.limits.max_ratio = limit;
+    }
+}
+
+impl<S, D: Decode> Decoder<S, D> {
+    pub(crate) fn get_decoder_ref(&self) -> &D {
+        &self.decoder
+    }
+
+    pub(crate) fn get_decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    pub(crate) fn total_in(&self) -> u64 {
+        self.total_in
+    }
+
+    pub(crate) fn total_out(&self) -> u64 {
+        self.total_out
+    }
 }
 
 impl<S: Stream<Item = Result<Bytes>>, D: Decode> Stream for Decoder<S, D> {
@@ -72,12 +104,15 @@ impl<S: Stream<Item = Result<Bytes>>, D: Decode> Stream for Decoder<S, D> {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
         let this = self.project();
 
-        let (mut stream, input, state, decoder, multiple_members) = (
+        let (mut stream, input, state, decoder, multiple_members, limits, total_in, total_out) = (
             this.stream,
             this.input,
             this.state,
             this.decoder,
             *this.multiple_members,
+            *this.limits,
+            this.total_in,
+            this.total_out,
         );
 
         let mut output = PartialBuffer::new(this.output);
@@ -85,6 +120,7 @@ impl<S: Stream<Item = Result<Bytes>>, D: Decode> Stream for Decoder<S, D> {
         let result = (|| loop {
             let output_capacity = output.written().len() + OUTPUT_BUFFER_SIZE;
             output.get_mut().resize(output_capacity, 0);
+            let produced = output.written().len();
 
             *state = match state {
                 State::Reading => {
@@ -106,6 +142,7 @@ impl<S: Stream<Item = Result<Bytes>>, D: Decode> Stream for Decoder<S, D> {
 
                         let input_len = input.written().len();
                         input.into_inner().advance(input_len);
+                        *total_in += input_len as u64;
 
                         if done {
                             State::Flushing
@@ -145,6 +182,9 @@ impl<S: Stream<Item = Result<Bytes>>, D: Decode> Stream for Decoder<S, D> {
                     return Poll::Ready(None);
                 }
             };
+
+            *total_out += (output.written().len() - produced) as u64;
+            limits.check(*total_in, *total_out)?;
         })();
 
         match result {
@@ -28,6 +28,8 @@ pin_project! {
         state: State,
         input: Bytes,
         output: BytesMut,
+        total_in: u64,
+        total_out: u64,
     }
 }
 
@@ -39,6 +41,8 @@ impl<S: Stream<Item = Result<Bytes>>, E: Encode> Encoder<S, E> {
             state: State::Reading,
             input: Bytes::new(),
             output: BytesMut::new(),
+            total_in: 0,
+            total_out: 0,
         }
     }
 
@@ -59,20 +63,45 @@ impl<S: Stream<Item = Result<Bytes>>, E: Encode> Encoder<S, E> {
     }
 }
 
+impl<S, E: Encode> Encoder<S, E> {
+    pub(crate) fn get_encoder_ref(&self) -> &E {
+        &self.encoder
+    }
+
+    pub(crate) fn get_encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    pub(crate) fn total_in(&self) -> u64 {
+        self.total_in
+    }
+
+    pub(crate) fn total_out(&self) -> u64 {
+        self.total_out
+    }
+}
+
 impl<S: Stream<Item = Result<Bytes>>, E: Encode> Stream for Encoder<S, E> {
     type Item = Result<Bytes>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes>>> {
         let this = self.project();
 
-        let (mut stream, input, state, encoder) =
-            (this.stream, this.input, this.state, this.encoder);
+        let (mut stream, input, state, encoder, total_in, total_out) = (
+            this.stream,
+            this.input,
+            this.state,
+            this.encoder,
+            this.total_in,
+            this.total_out,
+        );
 
         let mut output = PartialBuffer::new(this.output);
 
         let result = (|| loop {
             let output_capacity = output.written().len() + OUTPUT_BUFFER_SIZE;
             output.get_mut().resize(output_capacity, 0);
+            let produced = output.written().len();
 
             *state = match *state {
                 State::Reading => {
@@ -94,6 +123,7 @@ impl<S: Stream<Item = Result<Bytes>>, E: Encode> Stream for Encoder<S, E> {
 
                         let input_len = input.written().len();
                         input.into_inner().advance(input_len);
+                        *total_in += input_len as u64;
 
                         State::Writing
                     }
@@ -111,6 +141,8 @@ impl<S: Stream<Item = Result<Bytes>>, E: Encode> Stream for Encoder<S, E> {
                     return Poll::Ready(None);
                 }
             };
+
+            *total_out += (output.written().len() - produced) as u64;
         })();
 
         match result {
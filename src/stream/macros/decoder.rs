@@ -1,5 +1,5 @@
 macro_rules! decoder {
-    ($(#[$attr:meta])* $name:ident) => {
+    ($(#[$attr:meta])* $name:ident $({ $($constructor:tt)* })*) => {
         pin_project_lite::pin_project! {
             $(#[$attr])*
             #[derive(Debug)]
@@ -13,16 +13,12 @@ macro_rules! decoder {
         }
 
         impl<S: futures_core::stream::Stream<Item = std::io::Result<bytes_05::Bytes>>> $name<S> {
-            /// Creates a new decoder which will read compressed data from the given stream and
-            /// emit an uncompressed stream.
-            pub fn new(stream: S) -> Self {
-                Self {
-                    inner: crate::stream::Decoder::new(
-                        stream,
-                        crate::codec::$name::new(),
-                    ),
-                }
-            }
+            $(
+                /// Creates a new decoder which will read compressed data from the given stream and
+                /// emit an uncompressed stream.
+                ///
+                $($constructor)*
+            )*
 
             /// Configure multi-member/frame decoding, if enabled this will reset the decoder state
             /// when reaching the end of a compressed member/frame and expect either the end of the
@@ -31,6 +27,20 @@ macro_rules! decoder {
                 self.inner.multiple_members(enabled);
             }
 
+            /// Limits the total size of the decompressed output, over all members, it is an error
+            /// with a [`LimitExceeded`](crate::LimitExceeded) inside to produce more than `limit`
+            /// bytes.
+            pub fn max_output_size(&mut self, limit: Option<u64>) {
+                self.inner.max_output_size(limit);
+            }
+
+            /// Limits how many bytes of decompressed output can be produced per byte of
+            /// compressed input consumed, over all members, it is an error with a
+            /// [`LimitExceeded`](crate::LimitExceeded) inside to exceed it.
+            pub fn max_ratio(&mut self, limit: Option<u64>) {
+                self.inner.max_ratio(limit);
+            }
+
             /// Acquires a reference to the underlying stream that this decoder is wrapping.
             pub fn get_ref(&self) -> &S {
                 self.inner.get_ref()
@@ -61,6 +71,16 @@ macro_rules! decoder {
             pub fn into_inner(self) -> S {
                 self.inner.into_inner()
             }
+
+            /// Returns the number of bytes of compressed input consumed so far, over all members.
+            pub fn total_in(&self) -> u64 {
+                self.inner.total_in()
+            }
+
+            /// Returns the number of bytes of uncompressed output produced so far, over all members.
+            pub fn total_out(&self) -> u64 {
+                self.inner.total_out()
+            }
         }
 
         impl<S: futures_core::stream::Stream<Item = std::io::Result<bytes_05::Bytes>>>
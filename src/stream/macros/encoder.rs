@@ -50,6 +50,16 @@ macro_rules! encoder {
             pub fn into_inner(self) -> $inner {
                 self.inner.into_inner()
             }
+
+            /// Returns the number of bytes of uncompressed input consumed so far.
+            pub fn total_in(&self) -> u64 {
+                self.inner.total_in()
+            }
+
+            /// Returns the number of bytes of compressed output produced so far.
+            pub fn total_out(&self) -> u64 {
+                self.inner.total_out()
+            }
         }
 
         impl<$inner: futures_core::stream::Stream<Item = std::io::Result<bytes_05::Bytes>>>
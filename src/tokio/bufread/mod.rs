@@ -5,6 +5,8 @@
 mod macros;
 mod generic;
 
-pub(crate) use generic::{Decoder, Encoder};
+pub use self::generic::{Decoder, Encoder};
 
 algos!(tokio::bufread<R>);
+auto_decoder!(tokio::bufread<R>);
+chain_decoder!(tokio::bufread<R>);
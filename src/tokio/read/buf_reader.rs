@@ -0,0 +1,136 @@
+// Originally sourced from `futures_util::io::buf_reader`, needs to be redefined locally as
+// `futures-util` is not a dependency, and changed to allow resizing the buffer after creation.
+
+use futures_core::ready;
+use pin_project_lite::pin_project;
+use std::{
+    cmp::{max, min},
+    fmt, io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+
+const DEFAULT_BUF_SIZE: usize = 8192;
+
+pin_project! {
+    pub struct BufReader<R> {
+        #[pin]
+        inner: R,
+        buf: Box<[u8]>,
+        pos: usize,
+        filled: usize,
+    }
+}
+
+impl<R: AsyncRead> BufReader<R> {
+    /// Creates a new `BufReader` with a default buffer capacity. The default is currently 8 KB,
+    /// but may change in the future.
+    pub fn new(inner: R) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a new `BufReader` with the specified buffer capacity.
+    pub fn with_capacity(cap: usize, inner: R) -> Self {
+        assert!(cap > 0, "buffer capacity must be non-zero");
+        Self {
+            inner,
+            buf: vec![0; cap].into(),
+            pos: 0,
+            filled: 0,
+        }
+    }
+
+    /// Changes the buffer capacity, keeping any data which is currently buffered, so the buffer
+    /// may end up larger than requested.
+    pub fn set_capacity(&mut self, cap: usize) {
+        assert!(cap > 0, "buffer capacity must be non-zero");
+        let buffered = &self.buf[self.pos..self.filled];
+        let mut buf = vec![0; max(cap, buffered.len())];
+        buf[..buffered.len()].copy_from_slice(buffered);
+        self.buf = buf.into();
+        self.filled -= self.pos;
+        self.pos = 0;
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Gets a pinned mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner
+    }
+
+    /// Consumes this `BufReader`, returning the underlying reader.
+    ///
+    /// Note that any leftover data in the internal buffer is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for BufReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        // If we don't have any buffered data and we're doing a massive read (larger than our
+        // internal buffer), bypass our internal buffer entirely.
+        if self.pos == self.filled && buf.remaining() >= self.buf.len() {
+            let this = self.project();
+            *this.pos = 0;
+            *this.filled = 0;
+            return this.inner.poll_read(cx, buf);
+        }
+
+        let rem = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = min(rem.len(), buf.remaining());
+        buf.put_slice(&rem[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead> AsyncBufRead for BufReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.project();
+
+        if *this.pos >= *this.filled {
+            let mut buf = ReadBuf::new(this.buf);
+            ready!(this.inner.poll_read(cx, &mut buf))?;
+            *this.filled = buf.filled().len();
+            *this.pos = 0;
+        }
+
+        Poll::Ready(Ok(&this.buf[*this.pos..*this.filled]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        *this.pos = min(*this.pos + amt, *this.filled);
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for BufReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufReader")
+            .field("reader", &self.inner)
+            .field(
+                "buffer",
+                &format_args!("{}/{}", self.filled - self.pos, self.buf.len()),
+            )
+            .finish()
+    }
+}
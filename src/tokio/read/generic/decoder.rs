@@ -0,0 +1,150 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::Result;
+
+use super::super::BufReader;
+use crate::{codec::Decode, tokio::bufread};
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, ReadBuf};
+
+pin_project! {
+    /// A generic decoder, for any [`Decode`](crate::codec::Decode) implementation.
+    ///
+    /// This structure implements an [`AsyncRead`](tokio::io::AsyncRead) interface and will read
+    /// compressed data from an underlying stream, through an internal buffer, and emit a stream
+    /// of uncompressed data.
+    #[derive(Debug)]
+    pub struct Decoder<R, D: Decode> {
+        #[pin]
+        inner: bufread::Decoder<BufReader<R>, D>,
+    }
+}
+
+impl<R: AsyncRead, D: Decode> Decoder<R, D> {
+    /// Creates a new decoder which will read compressed data from the given stream and emit a
+    /// uncompressed stream, using `decoder` to decompress it.
+    pub fn new(reader: R, decoder: D) -> Self {
+        Self {
+            inner: bufread::Decoder::new(BufReader::new(reader), decoder),
+        }
+    }
+
+    /// Like [`new`](Self::new), but with an input buffer of `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0.
+    pub fn with_capacity(capacity: usize, reader: R, decoder: D) -> Self {
+        Self {
+            inner: bufread::Decoder::new(BufReader::with_capacity(capacity, reader), decoder),
+        }
+    }
+
+    /// Changes the capacity of the input buffer, keeping any data which is currently buffered.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0.
+    pub fn set_buffer_capacity(&mut self, capacity: usize) {
+        self.inner.get_mut().set_capacity(capacity);
+    }
+
+    /// Configure multi-member/frame decoding, if enabled this will reset the decoder state when
+    /// reaching the end of a compressed member/frame and expect either EOF or another compressed
+    /// member/frame to follow it in the stream.
+    pub fn multiple_members(&mut self, enabled: bool) {
+        self.inner.multiple_members(enabled);
+    }
+
+    /// Configure what is done with any data following the end of the stream when not decoding
+    /// multiple members, by default it is [ignored](crate::TrailingData::Ignore) and left unread.
+    pub fn trailing_data(&mut self, mode: crate::TrailingData) {
+        self.inner.trailing_data(mode);
+    }
+
+    /// Limits the total size of the decompressed output, over all members, it is an error with a
+    /// [`LimitExceeded`](crate::LimitExceeded) inside to produce more than `limit` bytes.
+    pub fn max_output_size(&mut self, limit: Option<u64>) {
+        self.inner.max_output_size(limit);
+    }
+
+    /// Limits how many bytes of decompressed output can be produced per byte of compressed input
+    /// consumed, over all members, it is an error with a [`LimitExceeded`](crate::LimitExceeded)
+    /// inside to exceed it.
+    pub fn max_ratio(&mut self, limit: Option<u64>) {
+        self.inner.max_ratio(limit);
+    }
+
+    /// Acquires a reference to the underlying reader that this decoder is wrapping.
+    pub fn get_ref(&self) -> &R {
+        self.inner.get_ref().get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying reader that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.inner.get_mut().get_mut()
+    }
+
+    /// Acquires a pinned mutable reference to the underlying reader that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().inner.get_pin_mut().get_pin_mut()
+    }
+
+    /// Consumes this decoder returning the underlying reader.
+    ///
+    /// Note that this may discard internal state of this decoder, including any buffered input such
+    /// as data after the end of the compressed stream, so care should be taken to avoid losing
+    /// resources when this is called.
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner().into_inner()
+    }
+}
+
+impl<R, D: Decode> Decoder<R, D> {
+    /// Acquires a reference to the decoder that this is using.
+    pub fn get_decoder_ref(&self) -> &D {
+        self.inner.get_decoder_ref()
+    }
+
+    /// Acquires a mutable reference to the decoder that this is using.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the decoder which may
+    /// otherwise confuse this adapter.
+    pub fn get_decoder_mut(&mut self) -> &mut D {
+        self.inner.get_decoder_mut()
+    }
+
+    /// Returns the number of bytes of compressed input consumed so far, over all members.
+    pub fn total_in(&self) -> u64 {
+        self.inner.total_in()
+    }
+
+    /// Returns the number of bytes of uncompressed output produced so far, over all members.
+    pub fn total_out(&self) -> u64 {
+        self.inner.total_out()
+    }
+
+    /// Returns the number of bytes read after the end of the stream so far, which are only read
+    /// when [`trailing_data`](Self::trailing_data) is not [`Ignore`](crate::TrailingData::Ignore).
+    pub fn trailing_bytes(&self) -> u64 {
+        self.inner.trailing_bytes()
+    }
+}
+
+impl<R: AsyncRead, D: Decode> AsyncRead for Decoder<R, D> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        self.project().inner.poll_read(cx, buf)
+    }
+}
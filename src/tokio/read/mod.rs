@@ -0,0 +1,19 @@
+//! Types which operate over [`AsyncRead`](::tokio::io::AsyncRead) streams, both encoders and
+//! decoders for various formats.
+//!
+//! Unlike the types in [`bufread`](crate::tokio::bufread) these manage their own input buffer,
+//! so the underlying reader does not need to be wrapped in a `BufReader`.
+
+#[macro_use]
+mod macros;
+mod generic;
+
+mod buf_reader;
+
+use self::buf_reader::BufReader;
+
+pub use self::generic::{Decoder, Encoder};
+
+algos!(tokio::read<R>);
+auto_decoder!(tokio::read<R>);
+chain_decoder!(tokio::read<R>);
@@ -0,0 +1,246 @@
+//! Types which decode from positions within seekable compressed streams.
+
+use core::{
+    ops::Range,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io::{Result, SeekFrom};
+
+#[cfg(feature = "zstd")]
+use crate::{
+    codec::{ZstdSeekableDecoder as Decoder, ZstdSeekableStep as Step},
+    zstd::SeekTable,
+};
+use futures_core::ready;
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+#[cfg(feature = "zstd")]
+pin_project! {
+    /// A zstd decoder for the
+    /// [seekable format](https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md),
+    /// as written by a `ZstdSeekableEncoder`.
+    ///
+    /// This structure implements an [`AsyncRead`](tokio::io::AsyncRead) interface and will read
+    /// only the uncompressed data within a given range, seeking the underlying stream to the seek
+    /// table at its end and then to the first frame containing the range, so that only the frames
+    /// covering the range are read and decompressed.
+    #[derive(Debug)]
+    pub struct ZstdSeekableDecoder<R> {
+        #[pin]
+        reader: R,
+        decoder: Decoder,
+        // Whether a seek has been started on `reader` and not yet completed.
+        seeking: bool,
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: AsyncRead + AsyncSeek> ZstdSeekableDecoder<R> {
+    /// Creates a new decoder which will read the given `range` of uncompressed data from the
+    /// given seekable stream.
+    ///
+    /// Any part of the range beyond the end of the uncompressed data is ignored.
+    pub fn new(reader: R, range: Range<u64>) -> Self {
+        Self {
+            reader,
+            decoder: Decoder::new(range),
+            seeking: false,
+        }
+    }
+
+    /// Returns the seek table of the stream, once it has been read by the first read from this
+    /// decoder.
+    pub fn seek_table(&self) -> Option<&SeekTable> {
+        self.decoder.seek_table()
+    }
+
+    /// Changes the range of uncompressed data this decoder will read, discarding any progress in
+    /// the current range. The seek table is only read once, so this is cheaper than creating a
+    /// new decoder.
+    pub fn set_range(&mut self, range: Range<u64>) -> Result<()> {
+        self.decoder.set_range(range)
+    }
+
+    /// Acquires a reference to the underlying reader that this decoder is wrapping.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Acquires a mutable reference to the underlying reader that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Acquires a pinned mutable reference to the underlying reader that this decoder is
+    /// wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().reader
+    }
+
+    /// Consumes this decoder returning the underlying reader.
+    ///
+    /// Note that this may discard internal state of this decoder, so care should be taken
+    /// to avoid losing resources when this is called.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: AsyncRead + AsyncSeek> AsyncRead for ZstdSeekableDecoder<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        let mut this = self.project();
+
+        loop {
+            match this.decoder.step() {
+                Step::Seek(position) => {
+                    if !*this.seeking {
+                        this.reader.as_mut().start_seek(position)?;
+                        *this.seeking = true;
+                    }
+                    let position = ready!(this.reader.as_mut().poll_complete(cx));
+                    *this.seeking = false;
+                    this.decoder.seeked(position?)?;
+                }
+
+                Step::Read(input) => {
+                    let mut input = ReadBuf::new(input);
+                    ready!(this.reader.as_mut().poll_read(cx, &mut input))?;
+                    let len = input.filled().len();
+                    this.decoder.read(len)?;
+                }
+
+                Step::Decode => {
+                    let len = this.decoder.decode(buf.initialize_unfilled())?;
+                    if len > 0 {
+                        buf.advance(len);
+                        return Poll::Ready(Ok(()));
+                    }
+                }
+
+                Step::Done => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "bgzf")]
+pin_project! {
+    /// A BGZF decoder which starts at a [virtual offset](crate::bgzf::VirtualOffset), as
+    /// returned by `BgzfEncoder::virtual_offset` and stored in BGZF indexes.
+    ///
+    /// This structure implements an [`AsyncRead`](tokio::io::AsyncRead) interface and will seek the
+    /// underlying stream to the start of the compressed block containing the offset on the
+    /// first read, then read through an internal buffer and emit the uncompressed data from the
+    /// offset to the end of the stream.
+    #[derive(Debug)]
+    pub struct BgzfSeekableDecoder<R> {
+        #[pin]
+        decoder: crate::tokio::read::BgzfDecoder<R>,
+        // The position to seek `reader` to before reading, until the seek completes.
+        seek: Option<u64>,
+        // Whether a seek has been started on `reader` and not yet completed.
+        seeking: bool,
+    }
+}
+
+#[cfg(feature = "bgzf")]
+impl<R: AsyncRead + AsyncSeek> BgzfSeekableDecoder<R> {
+    /// Creates a new decoder which will read the uncompressed data from `offset` onwards from
+    /// the given seekable stream.
+    pub fn new(reader: R, offset: crate::bgzf::VirtualOffset) -> Self {
+        Self {
+            decoder: crate::tokio::read::BgzfDecoder::with_virtual_offset(reader, offset),
+            seek: Some(offset.compressed()),
+            seeking: false,
+        }
+    }
+
+    /// Acquires a reference to the underlying reader that this decoder is wrapping.
+    pub fn get_ref(&self) -> &R {
+        self.decoder.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying reader that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.decoder.get_mut()
+    }
+
+    /// Acquires a pinned mutable reference to the underlying reader that this decoder is
+    /// wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
+        self.project().decoder.get_pin_mut()
+    }
+
+    /// Consumes this decoder returning the underlying reader.
+    ///
+    /// Note that this may discard internal state of this decoder, so care should be taken
+    /// to avoid losing resources when this is called.
+    pub fn into_inner(self) -> R {
+        self.decoder.into_inner()
+    }
+}
+
+#[cfg(feature = "bgzf")]
+impl<R: AsyncRead + AsyncSeek> AsyncRead for BgzfSeekableDecoder<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let mut this = self.project();
+
+        if let Some(position) = *this.seek {
+            let mut reader = this.decoder.as_mut().get_pin_mut();
+            if !*this.seeking {
+                reader.as_mut().start_seek(SeekFrom::Start(position))?;
+                *this.seeking = true;
+            }
+            let result = ready!(reader.poll_complete(cx));
+            *this.seeking = false;
+            result?;
+            *this.seek = None;
+        }
+
+        this.decoder.poll_read(cx, buf)
+    }
+}
+
+const _: () = {
+    fn _assert() {
+        use crate::util::{_assert_send, _assert_sync};
+        use core::pin::Pin;
+        use tokio::io::AsyncRead;
+
+        #[cfg(feature = "zstd")]
+        _assert_send::<ZstdSeekableDecoder<Pin<Box<dyn AsyncRead + Send>>>>();
+        #[cfg(feature = "zstd")]
+        _assert_sync::<ZstdSeekableDecoder<Pin<Box<dyn AsyncRead + Sync>>>>();
+        #[cfg(feature = "bgzf")]
+        _assert_send::<BgzfSeekableDecoder<Pin<Box<dyn AsyncRead + Send>>>>();
+        #[cfg(feature = "bgzf")]
+        _assert_sync::<BgzfSeekableDecoder<Pin<Box<dyn AsyncRead + Sync>>>>();
+    }
+};
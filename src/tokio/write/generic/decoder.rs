@@ -6,6 +6,8 @@ use std::io::{Error, ErrorKind, Result};
 
 use crate::{
     codec::Decode,
+    error,
+    limit::Limits,
     tokio::write::{AsyncBufWrite, BufWriter},
     util::PartialBuffer,
 };
@@ -18,39 +20,115 @@ enum State {
     Decoding,
     Finishing,
     Done,
+    Next,
 }
 
 pin_project! {
+    /// A generic decoder, for any [`Decode`](crate::codec::Decode) implementation.
+    ///
+    /// This structure implements an [`AsyncWrite`](tokio::io::AsyncWrite) interface and will take
+    /// in compressed data and write it uncompressed to an underlying stream.
     #[derive(Debug)]
     pub struct Decoder<W, D: Decode> {
         #[pin]
         writer: BufWriter<W>,
         decoder: D,
         state: State,
+        multiple_members: bool,
+        limits: Limits,
+        total_in: u64,
+        total_out: u64,
     }
 }
 
 impl<W: AsyncWrite, D: Decode> Decoder<W, D> {
+    /// Creates a new decoder which will take in compressed data and write it uncompressed to the
+    /// given stream, using `decoder` to decompress it.
     pub fn new(writer: W, decoder: D) -> Self {
         Self {
             writer: BufWriter::new(writer),
             decoder,
             state: State::Decoding,
+            multiple_members: false,
+            limits: Limits::default(),
+            total_in: 0,
+            total_out: 0,
         }
     }
 
+    /// Like [`new`](Self::new), but with an output buffer of `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0.
+    pub fn with_capacity(capacity: usize, writer: W, decoder: D) -> Self {
+        Self {
+            writer: BufWriter::with_capacity(capacity, writer),
+            decoder,
+            state: State::Decoding,
+            multiple_members: false,
+            limits: Limits::default(),
+            total_in: 0,
+            total_out: 0,
+        }
+    }
+
+    /// Changes the capacity of the output buffer, keeping any data which is currently buffered.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0.
+    pub fn set_buffer_capacity(&mut self, capacity: usize) {
+        self.writer.set_capacity(capacity);
+    }
+
+    /// Configure multi-member/frame decoding, if enabled this will reset the decoder state when
+    /// reaching the end of a compressed member/frame and expect either the end of the input or
+    /// another compressed member/frame to follow it.
+    ///
+    /// If disabled, writing anything after the end of the first member/frame is an error with
+    /// [`Error::TrailingData`](crate::Error) inside.
+    pub fn multiple_members(&mut self, enabled: bool) {
+        self.multiple_members = enabled;
+    }
+
+    /// Limits the total size of the decompressed output, it is an error with a
+    /// [`LimitExceeded`](crate::LimitExceeded) inside to produce more than `limit` bytes.
+    pub fn max_output_size(&mut self, limit: Option<u64>) {
+        self.limits.max_output_size = limit;
+    }
+
+    /// Limits how many bytes of decompressed output can be produced per byte of compressed input
+    /// consumed, it is an error with a [`LimitExceeded`](crate::LimitExceeded) inside to exceed it.
+    pub fn max_ratio(&mut self, limit: Option<u64>) {
+        self.limits.max_ratio = limit;
+    }
+
+    /// Acquires a reference to the underlying writer that this decoder is wrapping.
     pub fn get_ref(&self) -> &W {
         self.writer.get_ref()
     }
 
+    /// Acquires a mutable reference to the underlying writer that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this decoder.
     pub fn get_mut(&mut self) -> &mut W {
         self.writer.get_mut()
     }
 
+    /// Acquires a pinned mutable reference to the underlying writer that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this decoder.
     pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
         self.project().writer.get_pin_mut()
     }
 
+    /// Consumes this decoder returning the underlying writer.
+    ///
+    /// Note that this may discard internal state of this decoder, so care should be taken to avoid
+    /// losing resources when this is called.
     pub fn into_inner(self) -> W {
         self.writer.into_inner()
     }
@@ -65,6 +143,7 @@ impl<W: AsyncWrite, D: Decode> Decoder<W, D> {
         loop {
             let output = ready!(this.writer.as_mut().poll_partial_flush_buf(cx))?;
             let mut output = PartialBuffer::new(output);
+            let consumed = input.written().len();
 
             *this.state = match this.state {
                 State::Decoding => {
@@ -76,17 +155,29 @@ impl<W: AsyncWrite, D: Decode> Decoder<W, D> {
                 }
 
                 State::Finishing => {
-                    if this.decoder.finish(&mut output)? {
-                        State::Done
-                    } else {
+                    if !this.decoder.finish(&mut output)? {
                         State::Finishing
+                    } else if *this.multiple_members {
+                        this.decoder.reinit()?;
+                        State::Next
+                    } else {
+                        State::Done
                     }
                 }
 
-                State::Done => panic!("Write after end of stream"),
+                State::Done => {
+                    return Poll::Ready(Err(error::trailing_data(this.decoder.algorithm())))
+                }
+
+                // The decoders are never given empty input
+                State::Next if input.unwritten().is_empty() => State::Next,
+                State::Next => State::Decoding,
             };
 
             let produced = output.written().len();
+            *this.total_in += (input.written().len() - consumed) as u64;
+            *this.total_out += produced as u64;
+            this.limits.check(*this.total_in, *this.total_out)?;
             this.writer.as_mut().produce(produced);
 
             if let State::Done = this.state {
@@ -113,19 +204,25 @@ impl<W: AsyncWrite, D: Decode> Decoder<W, D> {
                 }
 
                 State::Finishing => {
-                    if this.decoder.finish(&mut output)? {
-                        (State::Done, false)
-                    } else {
+                    if !this.decoder.finish(&mut output)? {
                         (State::Finishing, false)
+                    } else if *this.multiple_members {
+                        this.decoder.reinit()?;
+                        (State::Next, false)
+                    } else {
+                        (State::Done, false)
                     }
                 }
 
                 State::Done => (State::Done, true),
+                State::Next => (State::Next, true),
             };
 
             *this.state = state;
 
             let produced = output.written().len();
+            *this.total_out += produced as u64;
+            this.limits.check(*this.total_in, *this.total_out)?;
             this.writer.as_mut().produce(produced);
 
             if done {
@@ -135,6 +232,32 @@ impl<W: AsyncWrite, D: Decode> Decoder<W, D> {
     }
 }
 
+impl<W, D: Decode> Decoder<W, D> {
+    /// Acquires a reference to the decoder that this is using.
+    pub fn get_decoder_ref(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Acquires a mutable reference to the decoder that this is using.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the decoder which may
+    /// otherwise confuse this adapter.
+    pub fn get_decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the number of bytes of compressed input consumed so far.
+    pub fn total_in(&self) -> u64 {
+        self.total_in
+    }
+
+    /// Returns the number of bytes of uncompressed output produced so far, including any still
+    /// in the output buffer.
+    pub fn total_out(&self) -> u64 {
+        self.total_out
+    }
+}
+
 impl<W: AsyncWrite, D: Decode> AsyncWrite for Decoder<W, D> {
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
         if buf.is_empty() {
@@ -162,7 +285,8 @@ impl<W: AsyncWrite, D: Decode> AsyncWrite for Decoder<W, D> {
 
         ready!(self.as_mut().do_poll_flush(cx))?;
 
-        if let State::Done = self.as_mut().project().state {
+        // Between members the stream can end
+        if let State::Done | State::Next = self.as_mut().project().state {
             ready!(self.as_mut().project().writer.as_mut().poll_shutdown(cx))?;
             Poll::Ready(Ok(()))
         } else {
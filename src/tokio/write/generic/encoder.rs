@@ -1,3 +1,5 @@
+#[cfg(feature = "tokio-time")]
+use core::{future::Future, time::Duration};
 use core::{
     pin::Pin,
     task::{Context, Poll},
@@ -5,56 +7,269 @@ use core::{
 use std::io::Result;
 
 use crate::{
-    codec::Encode,
+    codec::{reinit_unsupported, Encode},
     tokio::write::{AsyncBufWrite, BufWriter},
     util::PartialBuffer,
+    AutoFlush, FlushMode,
 };
 use futures_core::ready;
 use pin_project_lite::pin_project;
 use tokio::io::AsyncWrite;
+#[cfg(feature = "tokio-time")]
+use tokio::time::{Instant, Sleep};
 
 #[derive(Debug)]
 enum State {
     Encoding,
     Finishing,
     Done,
+    Next,
+}
+
+/// The timer used to flush when idle, which is empty without the `tokio-time` feature.
+#[derive(Debug, Default)]
+struct Idle {
+    #[cfg(feature = "tokio-time")]
+    timeout: Option<Duration>,
+    // Reset after each write while `timeout` is set.
+    #[cfg(feature = "tokio-time")]
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl Idle {
+    /// Restarts the timer, if there is one, after a write.
+    fn written(&mut self) {
+        #[cfg(feature = "tokio-time")]
+        if let Some(timeout) = self.timeout {
+            let deadline = Instant::now() + timeout;
+            match &mut self.sleep {
+                Some(sleep) => sleep.as_mut().reset(deadline),
+                None => self.sleep = Some(Box::pin(tokio::time::sleep_until(deadline))),
+            }
+        }
+    }
 }
 
 pin_project! {
+    /// A generic encoder, for any [`Encode`](crate::codec::Encode) implementation.
+    ///
+    /// This structure implements an [`AsyncWrite`](tokio::io::AsyncWrite) interface and will take
+    /// in uncompressed data and write it compressed to an underlying stream.
     #[derive(Debug)]
     pub struct Encoder<W, E: Encode> {
         #[pin]
         writer: BufWriter<W>,
         encoder: E,
         state: State,
+        flush_mode: FlushMode,
+        auto_flush: AutoFlush,
+        // Uncompressed bytes written since the last flush.
+        unflushed: u64,
+        // An auto flush which couldn't complete during the write that started it.
+        flush_pending: bool,
+        // An auto flush which failed after its write had succeeded, returned by the next call.
+        flush_error: Option<std::io::Error>,
+        idle: Idle,
+        total_in: u64,
+        total_out: u64,
     }
 }
 
 impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
+    /// Creates a new encoder which will take in uncompressed data and write it compressed to the
+    /// given stream, using `encoder` to compress it.
     pub fn new(writer: W, encoder: E) -> Self {
         Self {
             writer: BufWriter::new(writer),
             encoder,
             state: State::Encoding,
+            flush_mode: FlushMode::default(),
+            auto_flush: AutoFlush::default(),
+            unflushed: 0,
+            flush_pending: false,
+            flush_error: None,
+            idle: Idle::default(),
+            total_in: 0,
+            total_out: 0,
         }
     }
 
+    /// Like [`new`](Self::new), but with an output buffer of `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0.
+    pub fn with_capacity(capacity: usize, writer: W, encoder: E) -> Self {
+        Self {
+            writer: BufWriter::with_capacity(capacity, writer),
+            encoder,
+            state: State::Encoding,
+            flush_mode: FlushMode::default(),
+            auto_flush: AutoFlush::default(),
+            unflushed: 0,
+            flush_pending: false,
+            flush_error: None,
+            idle: Idle::default(),
+            total_in: 0,
+            total_out: 0,
+        }
+    }
+
+    /// Changes the capacity of the output buffer, keeping any data which is currently buffered.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is 0.
+    pub fn set_buffer_capacity(&mut self, capacity: usize) {
+        self.writer.set_capacity(capacity);
+    }
+
+    /// Sets the kind of flush done when this encoder is flushed, which is
+    /// [`FlushMode::Sync`](crate::FlushMode::Sync) by default.
+    pub fn flush_mode(&mut self, mode: FlushMode) {
+        self.flush_mode = mode;
+    }
+
+    /// Sets when this encoder flushes itself, which is [`AutoFlush::Never`] by default.
+    ///
+    /// An automatic flush which can't complete during the write that started it is left pending,
+    /// and is only finished by a later write, flush or idle flush, so the caller must keep driving
+    /// one of those to get the data out.
+    pub fn auto_flush(&mut self, policy: AutoFlush) {
+        self.auto_flush = policy;
+    }
+
+    /// Sets how long after the last write [`poll_idle_flush`](Self::poll_idle_flush) waits before
+    /// flushing, or `None`, the default, for it to never flush.
+    ///
+    /// This uses a [`tokio::time`] timer, so writing to this encoder while it is set must be done
+    /// within a runtime with the time driver enabled.
+    #[cfg(feature = "tokio-time")]
+    pub fn flush_when_idle(&mut self, timeout: Option<Duration>) {
+        self.idle.timeout = timeout;
+        if timeout.is_none() {
+            self.idle.sleep = None;
+        }
+    }
+
+    /// Flushes this encoder once nothing has been written to it for the duration given to
+    /// [`flush_when_idle`](Self::flush_when_idle), if anything has been written since the last
+    /// flush, otherwise this stays pending.
+    ///
+    /// The encoder can only do anything when it is polled, so this is meant to be raced against
+    /// whatever produces the data to write, e.g. in a `tokio::select!` loop. An automatic flush
+    /// which was left pending is finished straight away, without waiting.
+    #[cfg(feature = "tokio-time")]
+    pub fn poll_idle_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.as_mut().project();
+        if !*this.flush_pending {
+            match &mut this.idle.sleep {
+                Some(sleep) if *this.unflushed > 0 => ready!(sleep.as_mut().poll(cx)),
+                _ => return Poll::Pending,
+            }
+        }
+
+        self.poll_flush(cx)
+    }
+
+    /// Like [`poll_idle_flush`](Self::poll_idle_flush), as a future.
+    #[cfg(feature = "tokio-time")]
+    pub async fn idle_flush(&mut self) -> Result<()>
+    where
+        W: Unpin,
+    {
+        core::future::poll_fn(|cx| Pin::new(&mut *self).poll_idle_flush(cx)).await
+    }
+
+    /// Acquires a reference to the underlying writer that this encoder is wrapping.
     pub fn get_ref(&self) -> &W {
         self.writer.get_ref()
     }
 
+    /// Acquires a mutable reference to the underlying writer that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this encoder.
     pub fn get_mut(&mut self) -> &mut W {
         self.writer.get_mut()
     }
 
+    /// Acquires a pinned mutable reference to the underlying writer that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the writer which may
+    /// otherwise confuse this encoder.
     pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut W> {
         self.project().writer.get_pin_mut()
     }
 
+    /// Consumes this encoder returning the underlying writer.
+    ///
+    /// Note that this may discard internal state of this encoder, so care should be taken to avoid
+    /// losing resources when this is called, call [`poll_finish`](Self::poll_finish) first to
+    /// write out the end of the compressed stream.
     pub fn into_inner(self) -> W {
         self.writer.into_inner()
     }
 
+    /// Writes out the end of the compressed stream and flushes it to the underlying writer, like
+    /// [`poll_shutdown`](AsyncWrite::poll_shutdown) but leaving the underlying writer open so
+    /// that more can be written to it after [`into_inner`](Self::into_inner).
+    ///
+    /// Nothing more can be written to this encoder afterwards, though it can still be flushed.
+    pub fn poll_finish(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.as_mut().take_flush_error()?;
+        ready!(self.as_mut().do_poll_shutdown(cx, false))?;
+        ready!(self.project().writer.as_mut().poll_flush(cx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    /// Like [`poll_finish`](Self::poll_finish), as a future.
+    pub async fn finish(&mut self) -> Result<()>
+    where
+        W: Unpin,
+    {
+        core::future::poll_fn(|cx| Pin::new(&mut *self).poll_finish(cx)).await
+    }
+
+    /// Writes out the end of the current member/frame and flushes it to the underlying writer, so
+    /// that everything written so far can be decoded on its own. Anything written afterwards is
+    /// encoded in a new member/frame.
+    ///
+    /// If the encoder cannot start a new member/frame this fails without writing anything, see
+    /// [`Encode::can_reinit`](crate::codec::Encode::can_reinit).
+    pub fn poll_end_member(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if !self.encoder.can_reinit() {
+            return Poll::Ready(Err(reinit_unsupported()));
+        }
+
+        self.as_mut().take_flush_error()?;
+        ready!(self.as_mut().do_poll_shutdown(cx, true))?;
+        ready!(self.as_mut().project().writer.as_mut().poll_flush(cx))?;
+        self.flushed();
+        Poll::Ready(Ok(()))
+    }
+
+    /// Like [`poll_end_member`](Self::poll_end_member), as a future.
+    pub async fn finish_member(&mut self) -> Result<()>
+    where
+        W: Unpin,
+    {
+        core::future::poll_fn(|cx| Pin::new(&mut *self).poll_end_member(cx)).await
+    }
+
+    fn flushed(self: Pin<&mut Self>) {
+        let this = self.project();
+        *this.unflushed = 0;
+        *this.flush_pending = false;
+    }
+
+    fn take_flush_error(self: Pin<&mut Self>) -> Result<()> {
+        match self.project().flush_error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
     fn do_poll_write(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -68,7 +283,15 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
 
             *this.state = match this.state {
                 State::Encoding => {
+                    let consumed = input.written().len();
                     this.encoder.encode(input, &mut output)?;
+                    *this.total_in += (input.written().len() - consumed) as u64;
+                    State::Encoding
+                }
+
+                // Only start the next member once there is something to put in it
+                State::Next => {
+                    this.encoder.reinit()?;
                     State::Encoding
                 }
 
@@ -76,6 +299,7 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
             };
 
             let produced = output.written().len();
+            *this.total_out += produced as u64;
             this.writer.as_mut().produce(produced);
 
             if input.unwritten().is_empty() {
@@ -92,12 +316,17 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
             let mut output = PartialBuffer::new(output);
 
             let done = match this.state {
-                State::Encoding => this.encoder.flush(&mut output)?,
+                State::Encoding => this
+                    .encoder
+                    .flush_with_mode(*this.flush_mode, &mut output)?,
+                // Everything has already been written out
+                State::Next | State::Done => true,
 
-                State::Finishing | State::Done => panic!("Flush after shutdown"),
+                State::Finishing => panic!("Flush after shutdown"),
             };
 
             let produced = output.written().len();
+            *this.total_out += produced as u64;
             this.writer.as_mut().produce(produced);
 
             if done {
@@ -106,7 +335,12 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
         }
     }
 
-    fn do_poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+    /// Finishes the stream, or with `end_member` just the current member/frame of it.
+    fn do_poll_shutdown(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        end_member: bool,
+    ) -> Poll<Result<()>> {
         let mut this = self.project();
 
         loop {
@@ -115,48 +349,106 @@ impl<W: AsyncWrite, E: Encode> Encoder<W, E> {
 
             *this.state = match this.state {
                 State::Encoding | State::Finishing => {
-                    if this.encoder.finish(&mut output)? {
-                        State::Done
-                    } else {
+                    if !this.encoder.finish(&mut output)? {
                         State::Finishing
+                    } else if end_member {
+                        State::Next
+                    } else {
+                        State::Done
                     }
                 }
 
-                State::Done => State::Done,
+                State::Next if end_member => State::Next,
+                State::Next | State::Done => State::Done,
             };
 
             let produced = output.written().len();
+            *this.total_out += produced as u64;
             this.writer.as_mut().produce(produced);
 
-            if let State::Done = this.state {
+            if let State::Done | State::Next = this.state {
                 return Poll::Ready(Ok(()));
             }
         }
     }
 }
 
+impl<W, E: Encode> Encoder<W, E> {
+    /// Acquires a reference to the encoder that this is using.
+    pub fn get_encoder_ref(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Acquires a mutable reference to the encoder that this is using.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the encoder which may
+    /// otherwise confuse this adapter.
+    pub fn get_encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the number of bytes of uncompressed input consumed so far.
+    pub fn total_in(&self) -> u64 {
+        self.total_in
+    }
+
+    /// Returns the number of bytes of compressed output produced so far, including any still
+    /// in the output buffer.
+    pub fn total_out(&self) -> u64 {
+        self.total_out
+    }
+}
+
 impl<W: AsyncWrite, E: Encode> AsyncWrite for Encoder<W, E> {
-    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize>> {
         if buf.is_empty() {
             return Poll::Ready(Ok(0));
         }
 
+        self.as_mut().take_flush_error()?;
+        if self.flush_pending {
+            ready!(self.as_mut().poll_flush(cx))?;
+        }
+
         let mut input = PartialBuffer::new(buf);
 
-        match self.do_poll_write(cx, &mut input)? {
-            Poll::Pending if input.written().is_empty() => Poll::Pending,
-            _ => Poll::Ready(Ok(input.written().len())),
+        let poll = self.as_mut().do_poll_write(cx, &mut input)?;
+        if poll.is_pending() && input.written().is_empty() {
+            return Poll::Pending;
         }
+
+        let written = input.written().len();
+        let this = self.as_mut().project();
+        *this.unflushed += written as u64;
+        this.idle.written();
+        if this.auto_flush.is_due(*this.unflushed) {
+            // The written input is already in the encoder, so this must report it as written
+            // whatever happens to the flush. One which fails is reported by the next call, and
+            // one which can't complete now is left pending for the next call to finish.
+            *this.flush_pending = true;
+            if let Poll::Ready(Err(error)) = self.as_mut().poll_flush(cx) {
+                *self.as_mut().project().flush_error = Some(error);
+            }
+        }
+
+        Poll::Ready(Ok(written))
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.as_mut().take_flush_error()?;
         ready!(self.as_mut().do_poll_flush(cx))?;
-        ready!(self.project().writer.as_mut().poll_flush(cx))?;
+        ready!(self.as_mut().project().writer.as_mut().poll_flush(cx))?;
+        self.flushed();
         Poll::Ready(Ok(()))
     }
 
     fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
-        ready!(self.as_mut().do_poll_shutdown(cx))?;
+        self.as_mut().take_flush_error()?;
+        ready!(self.as_mut().do_poll_shutdown(cx, false))?;
         ready!(self.project().writer.as_mut().poll_shutdown(cx))?;
         Poll::Ready(Ok(()))
     }
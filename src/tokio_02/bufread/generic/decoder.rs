@@ -4,7 +4,7 @@ use core::{
 };
 use std::io::Result;
 
-use crate::{codec::Decode, util::PartialBuffer};
+use crate::{codec::Decode, error, limit::Limits, util::PartialBuffer, TrailingData};
 use futures_core::ready;
 use pin_project_lite::pin_project;
 use tokio_02::io::{AsyncBufRead, AsyncRead};
@@ -13,11 +13,16 @@ use tokio_02::io::{AsyncBufRead, AsyncRead};
 enum State {
     Decoding,
     Flushing,
+    Trailing,
     Done,
     Next,
 }
 
 pin_project! {
+    /// A generic decoder, for any [`Decode`](crate::codec::Decode) implementation.
+    ///
+    /// This structure implements an [`AsyncRead`](tokio_02::io::AsyncRead) interface and will read
+    /// compressed data from an underlying stream and emit a stream of uncompressed data.
     #[derive(Debug)]
     pub struct Decoder<R, D: Decode> {
         #[pin]
@@ -25,39 +30,86 @@ pin_project! {
         decoder: D,
         state: State,
         multiple_members: bool,
+        trailing_data: TrailingData,
+        trailing_bytes: u64,
+        limits: Limits,
+        total_in: u64,
+        total_out: u64,
     }
 }
 
 impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
+    /// Creates a new decoder which will read compressed data from the given stream and emit a
+    /// uncompressed stream, using `decoder` to decompress it.
     pub fn new(reader: R, decoder: D) -> Self {
         Self {
             reader,
             decoder,
             state: State::Decoding,
             multiple_members: false,
+            trailing_data: TrailingData::default(),
+            trailing_bytes: 0,
+            limits: Limits::default(),
+            total_in: 0,
+            total_out: 0,
         }
     }
 
+    /// Acquires a reference to the underlying reader that this decoder is wrapping.
     pub fn get_ref(&self) -> &R {
         &self.reader
     }
 
+    /// Acquires a mutable reference to the underlying reader that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
     pub fn get_mut(&mut self) -> &mut R {
         &mut self.reader
     }
 
+    /// Acquires a pinned mutable reference to the underlying reader that this decoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this decoder.
     pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
         self.project().reader
     }
 
+    /// Consumes this decoder returning the underlying reader.
+    ///
+    /// Note that this may discard internal state of this decoder, so care should be taken to avoid
+    /// losing resources when this is called.
     pub fn into_inner(self) -> R {
         self.reader
     }
 
+    /// Configure multi-member/frame decoding, if enabled this will reset the decoder state when
+    /// reaching the end of a compressed member/frame and expect either EOF or another compressed
+    /// member/frame to follow it in the stream.
     pub fn multiple_members(&mut self, enabled: bool) {
         self.multiple_members = enabled;
     }
 
+    /// Configure what is done with any data following the end of the stream when not decoding
+    /// multiple members, by default it is [ignored](TrailingData::Ignore) and left unread.
+    pub fn trailing_data(&mut self, mode: TrailingData) {
+        self.trailing_data = mode;
+    }
+
+    /// Limits the total size of the decompressed output, over all members, it is an error with a
+    /// [`LimitExceeded`](crate::LimitExceeded) inside to produce more than `limit` bytes.
+    pub fn max_output_size(&mut self, limit: Option<u64>) {
+        self.limits.max_output_size = limit;
+    }
+
+    /// Limits how many bytes of decompressed output can be produced per byte of compressed input
+    /// consumed, over all members, it is an error with a [`LimitExceeded`](crate::LimitExceeded)
+    /// inside to exceed it.
+    pub fn max_ratio(&mut self, limit: Option<u64>) {
+        self.limits.max_ratio = limit;
+    }
+
     fn do_poll_read(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -66,6 +118,8 @@ impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
         let mut this = self.project();
 
         loop {
+            let produced = output.written().len();
+
             *this.state = match this.state {
                 State::Decoding => {
                     let input = ready!(this.reader.as_mut().poll_fill_buf(cx))?;
@@ -76,6 +130,7 @@ impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
                         let done = this.decoder.decode(&mut input, output)?;
                         let len = input.written().len();
                         this.reader.as_mut().consume(len);
+                        *this.total_in += len as u64;
                         if done {
                             State::Flushing
                         } else {
@@ -89,14 +144,35 @@ impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
                         if *this.multiple_members {
                             this.decoder.reinit()?;
                             State::Next
-                        } else {
+                        } else if *this.trailing_data == TrailingData::Ignore {
                             State::Done
+                        } else {
+                            State::Trailing
                         }
                     } else {
                         State::Flushing
                     }
                 }
 
+                State::Trailing => {
+                    let input = ready!(this.reader.as_mut().poll_fill_buf(cx))?;
+                    if input.is_empty() {
+                        State::Done
+                    } else {
+                        if *this.trailing_data == TrailingData::Strict
+                            && input.iter().any(|&byte| byte != 0)
+                        {
+                            return Poll::Ready(Err(error::trailing_data(
+                                this.decoder.algorithm(),
+                            )));
+                        }
+                        let len = input.len();
+                        this.reader.as_mut().consume(len);
+                        *this.trailing_bytes += len as u64;
+                        State::Trailing
+                    }
+                }
+
                 State::Done => State::Done,
 
                 State::Next => {
@@ -109,6 +185,9 @@ impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
                 }
             };
 
+            *this.total_out += (output.written().len() - produced) as u64;
+            this.limits.check(*this.total_in, *this.total_out)?;
+
             if let State::Done = *this.state {
                 return Poll::Ready(Ok(()));
             }
@@ -119,6 +198,37 @@ impl<R: AsyncBufRead, D: Decode> Decoder<R, D> {
     }
 }
 
+impl<R, D: Decode> Decoder<R, D> {
+    /// Acquires a reference to the decoder that this is using.
+    pub fn get_decoder_ref(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Acquires a mutable reference to the decoder that this is using.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the decoder which may
+    /// otherwise confuse this adapter.
+    pub fn get_decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the number of bytes of compressed input consumed so far, over all members.
+    pub fn total_in(&self) -> u64 {
+        self.total_in
+    }
+
+    /// Returns the number of bytes of uncompressed output produced so far, over all members.
+    pub fn total_out(&self) -> u64 {
+        self.total_out
+    }
+
+    /// Returns the number of bytes read after the end of the stream so far, which are only read
+    /// when [`trailing_data`](Self::trailing_data) is not [`Ignore`](TrailingData::Ignore).
+    pub fn trailing_bytes(&self) -> u64 {
+        self.trailing_bytes
+    }
+}
+
 impl<R: AsyncBufRead, D: Decode> AsyncRead for Decoder<R, D> {
     fn poll_read(
         self: Pin<&mut Self>,
@@ -50,6 +50,25 @@ macro_rules! encoder {
             pub fn into_inner(self) -> $inner {
                 self.inner.into_inner()
             }
+
+            /// Ends the current member/frame at the current position in the input, so that
+            /// everything read from the underlying reader so far can be decoded on its own. The
+            /// next reads emit the end of it, then encode any more input in a new member/frame.
+            ///
+            /// If the encoder cannot start a new member/frame this fails without ending it.
+            pub fn finish_member(&mut self) -> std::io::Result<()> {
+                self.inner.finish_member()
+            }
+
+            /// Returns the number of bytes of uncompressed input consumed so far.
+            pub fn total_in(&self) -> u64 {
+                self.inner.total_in()
+            }
+
+            /// Returns the number of bytes of compressed output produced so far.
+            pub fn total_out(&self) -> u64 {
+                self.inner.total_out()
+            }
         }
 
         impl<$inner: tokio_02::io::AsyncBufRead> tokio_02::io::AsyncRead for $name<$inner> {
@@ -1,4 +1,5 @@
 //! Implementations for IO traits exported by [`tokio` v0.2](::tokio_02).
 
 pub mod bufread;
+pub mod read;
 pub mod write;
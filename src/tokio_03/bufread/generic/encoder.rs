@@ -4,7 +4,10 @@ use core::{
 };
 use std::io::Result;
 
-use crate::{codec::Encode, util::PartialBuffer};
+use crate::{
+    codec::{reinit_unsupported, Encode},
+    util::PartialBuffer,
+};
 use futures_core::ready;
 use pin_project_lite::pin_project;
 use tokio_03::io::{AsyncBufRead, AsyncRead, ReadBuf};
@@ -12,45 +15,87 @@ use tokio_03::io::{AsyncBufRead, AsyncRead, ReadBuf};
 #[derive(Debug)]
 enum State {
     Encoding,
+    EndingMember,
+    Next,
     Flushing,
     Done,
 }
 
 pin_project! {
+    /// A generic encoder, for any [`Encode`](crate::codec::Encode) implementation.
+    ///
+    /// This structure implements an [`AsyncRead`](tokio_03::io::AsyncRead) interface and will read
+    /// uncompressed data from an underlying stream and emit a stream of compressed data.
     #[derive(Debug)]
     pub struct Encoder<R, E: Encode> {
         #[pin]
         reader: R,
         encoder: E,
         state: State,
+        total_in: u64,
+        total_out: u64,
     }
 }
 
 impl<R: AsyncBufRead, E: Encode> Encoder<R, E> {
+    /// Creates a new encoder which will read uncompressed data from the given stream and emit a
+    /// compressed stream, using `encoder` to compress it.
     pub fn new(reader: R, encoder: E) -> Self {
         Self {
             reader,
             encoder,
             state: State::Encoding,
+            total_in: 0,
+            total_out: 0,
         }
     }
 
+    /// Acquires a reference to the underlying reader that this encoder is wrapping.
     pub fn get_ref(&self) -> &R {
         &self.reader
     }
 
+    /// Acquires a mutable reference to the underlying reader that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this encoder.
     pub fn get_mut(&mut self) -> &mut R {
         &mut self.reader
     }
 
+    /// Acquires a pinned mutable reference to the underlying reader that this encoder is wrapping.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the reader which may
+    /// otherwise confuse this encoder.
     pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut R> {
         self.project().reader
     }
 
+    /// Consumes this encoder returning the underlying reader.
+    ///
+    /// Note that this may discard internal state of this encoder, so care should be taken to avoid
+    /// losing resources when this is called.
     pub fn into_inner(self) -> R {
         self.reader
     }
 
+    /// Ends the current member/frame at the current position in the input, so that everything
+    /// read from the underlying reader so far can be decoded on its own. The next reads emit the
+    /// end of it, then encode any more input in a new member/frame.
+    ///
+    /// If the encoder cannot start a new member/frame this fails without ending it, see
+    /// [`Encode::can_reinit`](crate::codec::Encode::can_reinit).
+    pub fn finish_member(&mut self) -> Result<()> {
+        if !self.encoder.can_reinit() {
+            return Err(reinit_unsupported());
+        }
+
+        if let State::Encoding = self.state {
+            self.state = State::EndingMember;
+        }
+        Ok(())
+    }
+
     fn do_poll_read(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -59,6 +104,8 @@ impl<R: AsyncBufRead, E: Encode> Encoder<R, E> {
         let mut this = self.project();
 
         loop {
+            let produced = output.written().len();
+
             *this.state = match this.state {
                 State::Encoding => {
                     let input = ready!(this.reader.as_mut().poll_fill_buf(cx))?;
@@ -69,6 +116,26 @@ impl<R: AsyncBufRead, E: Encode> Encoder<R, E> {
                         this.encoder.encode(&mut input, output)?;
                         let len = input.written().len();
                         this.reader.as_mut().consume(len);
+                        *this.total_in += len as u64;
+                        State::Encoding
+                    }
+                }
+
+                State::EndingMember => {
+                    if this.encoder.finish(output)? {
+                        State::Next
+                    } else {
+                        State::EndingMember
+                    }
+                }
+
+                // Only start the next member once there is something to put in it
+                State::Next => {
+                    let input = ready!(this.reader.as_mut().poll_fill_buf(cx))?;
+                    if input.is_empty() {
+                        State::Done
+                    } else {
+                        this.encoder.reinit()?;
                         State::Encoding
                     }
                 }
@@ -84,6 +151,8 @@ impl<R: AsyncBufRead, E: Encode> Encoder<R, E> {
                 State::Done => State::Done,
             };
 
+            *this.total_out += (output.written().len() - produced) as u64;
+
             if let State::Done = *this.state {
                 return Poll::Ready(Ok(()));
             }
@@ -94,6 +163,31 @@ impl<R: AsyncBufRead, E: Encode> Encoder<R, E> {
     }
 }
 
+impl<R, E: Encode> Encoder<R, E> {
+    /// Acquires a reference to the encoder that this is using.
+    pub fn get_encoder_ref(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Acquires a mutable reference to the encoder that this is using.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the encoder which may
+    /// otherwise confuse this adapter.
+    pub fn get_encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the number of bytes of uncompressed input consumed so far.
+    pub fn total_in(&self) -> u64 {
+        self.total_in
+    }
+
+    /// Returns the number of bytes of compressed output produced so far.
+    pub fn total_out(&self) -> u64 {
+        self.total_out
+    }
+}
+
 impl<R: AsyncBufRead, E: Encode> AsyncRead for Encoder<R, E> {
     fn poll_read(
         self: Pin<&mut Self>,
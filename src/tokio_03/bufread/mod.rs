@@ -5,6 +5,8 @@
 mod macros;
 mod generic;
 
-pub(crate) use generic::{Decoder, Encoder};
+pub use self::generic::{Decoder, Encoder};
 
 algos!(tokio_03::bufread<R>);
+auto_decoder!(tokio_03::bufread<R>);
+chain_decoder!(tokio_03::bufread<R>);
@@ -1,4 +1,5 @@
 //! Implementations for IO traits exported by [`tokio` v0.3](::tokio_03).
 
 pub mod bufread;
+pub mod read;
 pub mod write;
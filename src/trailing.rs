@@ -0,0 +1,15 @@
+/// What a decoder does with any data following the end of the compressed stream, when it is not
+/// decoding multiple members, see `trailing_data` on each decoder.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingData {
+    /// Stop at the end of the stream, leaving anything after it unread in the underlying reader.
+    #[default]
+    Ignore,
+    /// Read to the end of the underlying reader, counting the bytes after the stream, which are
+    /// then given by `trailing_bytes`.
+    Lenient,
+    /// Like [`Lenient`](Self::Lenient), but fail with [`Error::TrailingData`](crate::Error)
+    /// inside if any of the bytes after the stream are not zero bytes of padding.
+    Strict,
+}
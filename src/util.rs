@@ -1,6 +1,11 @@
 pub fn _assert_send<T: Send>() {}
 pub fn _assert_sync<T: Sync>() {}
 
+/// A buffer with a position marking how much of it has been used.
+///
+/// This is used for both the input and output of [`Encode`](crate::codec::Encode) and
+/// [`Decode`](crate::codec::Decode), for input the "written" part is the data which has been
+/// consumed, and for output it is the data which has been produced.
 #[derive(Debug, Default)]
 pub struct PartialBuffer<B: AsRef<[u8]>> {
     buffer: B,
@@ -8,37 +13,52 @@ pub struct PartialBuffer<B: AsRef<[u8]>> {
 }
 
 impl<B: AsRef<[u8]>> PartialBuffer<B> {
-    pub(crate) fn new(buffer: B) -> Self {
+    /// Wraps `buffer`, with none of it written yet.
+    pub fn new(buffer: B) -> Self {
         Self { buffer, index: 0 }
     }
 
-    pub(crate) fn written(&self) -> &[u8] {
+    /// The part of the buffer before the position.
+    pub fn written(&self) -> &[u8] {
         &self.buffer.as_ref()[..self.index]
     }
 
-    pub(crate) fn unwritten(&self) -> &[u8] {
+    /// The part of the buffer after the position.
+    pub fn unwritten(&self) -> &[u8] {
         &self.buffer.as_ref()[self.index..]
     }
 
-    pub(crate) fn advance(&mut self, amount: usize) {
+    /// Moves the position forward by `amount`.
+    ///
+    /// # Panics
+    ///
+    /// If that would move it past the end of the buffer.
+    pub fn advance(&mut self, amount: usize) {
+        assert!(amount <= self.unwritten().len());
         self.index += amount;
     }
 
-    pub(crate) fn get_mut(&mut self) -> &mut B {
+    /// Acquires a mutable reference to the whole underlying buffer.
+    pub fn get_mut(&mut self) -> &mut B {
         &mut self.buffer
     }
 
-    pub(crate) fn into_inner(self) -> B {
+    /// Consumes this returning the whole underlying buffer.
+    pub fn into_inner(self) -> B {
         self.buffer
     }
 }
 
 impl<B: AsRef<[u8]> + AsMut<[u8]>> PartialBuffer<B> {
-    pub(crate) fn unwritten_mut(&mut self) -> &mut [u8] {
+    /// The part of the buffer after the position, to write output into before calling
+    /// [`advance`](Self::advance).
+    pub fn unwritten_mut(&mut self) -> &mut [u8] {
         &mut self.buffer.as_mut()[self.index..]
     }
 
-    pub(crate) fn copy_unwritten_from<C: AsRef<[u8]>>(&mut self, other: &mut PartialBuffer<C>) {
+    /// Copies as much as fits from the unwritten part of `other` into the unwritten part of this
+    /// buffer, advancing both.
+    pub fn copy_unwritten_from<C: AsRef<[u8]>>(&mut self, other: &mut PartialBuffer<C>) {
         let len = std::cmp::min(self.unwritten().len(), other.unwritten().len());
 
         self.unwritten_mut()[..len].copy_from_slice(&other.unwritten()[..len]);
@@ -49,7 +69,8 @@ impl<B: AsRef<[u8]> + AsMut<[u8]>> PartialBuffer<B> {
 }
 
 impl<B: AsRef<[u8]> + Default> PartialBuffer<B> {
-    pub(crate) fn take(&mut self) -> Self {
+    /// Takes the buffer, leaving a default one with nothing written in its place.
+    pub fn take(&mut self) -> Self {
         std::mem::replace(self, Self::new(B::default()))
     }
 }
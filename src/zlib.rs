@@ -0,0 +1,10 @@
+//! Types which are specific to the zlib format.
+
+/// Calculates the id of a preset dictionary, the Adler-32 checksum of it, which zlib streams
+/// using it store in their header.
+///
+/// This can be used to pick the right dictionary in a
+/// [`with_dictionary_callback`](?search=with_dictionary_callback) callback.
+pub fn dictionary_id(dictionary: &[u8]) -> u32 {
+    crate::codec::adler32(dictionary)
+}
@@ -0,0 +1,312 @@
+//! Types which are specific to the zstd algorithm.
+
+use crate::{error, Algorithm, Level};
+use std::{fmt, io::Result, sync::Arc};
+
+/// A zstd dictionary prepared for compression at a specific level.
+///
+/// Preparing a dictionary is relatively expensive, so this can be created once and then cheaply
+/// cloned to share it between many encoders.
+#[derive(Clone)]
+pub struct EncoderDictionary {
+    pub(crate) inner: Arc<libzstd::dict::EncoderDictionary<'static>>,
+}
+
+impl EncoderDictionary {
+    /// Prepares `dictionary` for compressing at the given `level`, the dictionary data is copied
+    /// internally.
+    pub fn new(dictionary: &[u8], level: Level) -> Self {
+        Self {
+            inner: Arc::new(libzstd::dict::EncoderDictionary::copy(
+                dictionary,
+                level.into_zstd(),
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for EncoderDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncoderDictionary").finish()
+    }
+}
+
+/// A zstd dictionary prepared for decompression.
+///
+/// Preparing a dictionary is relatively expensive, so this can be created once and then cheaply
+/// cloned to share it between many decoders.
+#[derive(Clone)]
+pub struct DecoderDictionary {
+    pub(crate) inner: Arc<libzstd::dict::DecoderDictionary<'static>>,
+}
+
+impl DecoderDictionary {
+    /// Prepares `dictionary` for decompressing, the dictionary data is copied internally.
+    pub fn new(dictionary: &[u8]) -> Self {
+        Self {
+            inner: Arc::new(libzstd::dict::DecoderDictionary::copy(dictionary)),
+        }
+    }
+}
+
+impl fmt::Debug for DecoderDictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecoderDictionary").finish()
+    }
+}
+
+/// Advanced zstd compression parameters, for use with `ZstdEncoder::with_params`.
+///
+/// Any parameter that is not set keeps zstd's default value.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EncoderParams {
+    pub(crate) level: Option<i32>,
+    pub(crate) window_log: Option<u32>,
+    pub(crate) long_distance_matching: Option<bool>,
+    pub(crate) checksum: Option<bool>,
+    pub(crate) pledged_src_size: Option<u64>,
+}
+
+impl EncoderParams {
+    /// Creates a new set of parameters with everything left at zstd's defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the compression level directly, unlike [`Level`] this allows using zstd's negative
+    /// "fast" levels.
+    pub fn level(mut self, level: i32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Sets the base 2 logarithm of the maximum back-reference distance, larger windows can
+    /// improve the compression ratio of big inputs but require more memory to decompress.
+    ///
+    /// Decoders refuse windows larger than 2^27 bytes unless configured otherwise with
+    /// [`DecoderParams::window_log_max`].
+    pub fn window_log(mut self, window_log: u32) -> Self {
+        self.window_log = Some(window_log);
+        self
+    }
+
+    /// Enables long distance matching, which finds matches further back than the normal search
+    /// does, equivalent to the `--long` command line flag.
+    ///
+    /// This also increases the default window log to 27 if it is not set explicitly.
+    pub fn long_distance_matching(mut self, enabled: bool) -> Self {
+        self.long_distance_matching = Some(enabled);
+        self
+    }
+
+    /// Sets whether a checksum of the uncompressed data is written at the end of the frame.
+    pub fn checksum(mut self, enabled: bool) -> Self {
+        self.checksum = Some(enabled);
+        self
+    }
+
+    /// Declares the total size of the uncompressed data up front, allowing zstd to tune its
+    /// parameters and record the size in the frame header.
+    ///
+    /// If the actual amount of data written differs from this then finishing the stream will
+    /// fail with an error.
+    pub fn pledged_src_size(mut self, size: u64) -> Self {
+        self.pledged_src_size = Some(size);
+        self
+    }
+}
+
+/// Advanced zstd decompression parameters, for use with `ZstdDecoder::with_params`.
+///
+/// Any parameter that is not set keeps zstd's default value.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DecoderParams {
+    pub(crate) window_log_max: Option<u32>,
+}
+
+impl DecoderParams {
+    /// Creates a new set of parameters with everything left at zstd's defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the base 2 logarithm of the largest window the decoder will accept, frames
+    /// requiring a larger window fail to decode rather than allocating the memory for it.
+    ///
+    /// The default is 27, streams compressed with `--long=31` or a similarly large
+    /// [`EncoderParams::window_log`] need this raised to be decoded.
+    pub fn window_log_max(mut self, window_log_max: u32) -> Self {
+        self.window_log_max = Some(window_log_max);
+        self
+    }
+}
+
+// Magic number of the skippable frame holding the seek table.
+const SEEK_TABLE_FRAME_MAGIC: u32 = 0x184d_2a5e;
+
+// Magic number at the very end of the seek table.
+const SEEKABLE_MAGIC: u32 = 0x8f92_eab1;
+
+/// The location of one frame within a zstd seekable format stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeekableFrame {
+    compressed_offset: u64,
+    compressed_size: u32,
+    decompressed_offset: u64,
+    decompressed_size: u32,
+}
+
+impl SeekableFrame {
+    /// The offset of the start of this frame in the compressed stream.
+    pub fn compressed_offset(&self) -> u64 {
+        self.compressed_offset
+    }
+
+    /// The size of this frame in the compressed stream.
+    pub fn compressed_size(&self) -> u32 {
+        self.compressed_size
+    }
+
+    /// The offset of the start of this frame's data in the uncompressed data.
+    pub fn decompressed_offset(&self) -> u64 {
+        self.decompressed_offset
+    }
+
+    /// The size of this frame's uncompressed data.
+    pub fn decompressed_size(&self) -> u32 {
+        self.decompressed_size
+    }
+}
+
+/// The seek table at the end of a zstd seekable format stream, listing the independent frames it
+/// is made of.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SeekTable {
+    frames: Vec<SeekableFrame>,
+}
+
+impl SeekTable {
+    // The size of the fixed footer at the end of the seek table.
+    pub(crate) const FOOTER_SIZE: usize = 9;
+
+    /// The frames of the stream, in order.
+    pub fn frames(&self) -> &[SeekableFrame] {
+        &self.frames
+    }
+
+    /// The total size of the compressed frames, not including the seek table.
+    pub fn compressed_size(&self) -> u64 {
+        self.frames.last().map_or(0, |frame| {
+            frame.compressed_offset + u64::from(frame.compressed_size)
+        })
+    }
+
+    /// The total size of the uncompressed data.
+    pub fn decompressed_size(&self) -> u64 {
+        self.frames.last().map_or(0, |frame| {
+            frame.decompressed_offset + u64::from(frame.decompressed_size)
+        })
+    }
+
+    /// The index of the frame containing the given offset into the uncompressed data, if it is
+    /// within the data.
+    pub fn frame_index(&self, decompressed_offset: u64) -> Option<usize> {
+        if decompressed_offset >= self.decompressed_size() {
+            return None;
+        }
+
+        match self
+            .frames
+            .binary_search_by_key(&decompressed_offset, |frame| frame.decompressed_offset)
+        {
+            Ok(mut index) => {
+                // Skip past any empty frames to the one actually containing the data
+                while self.frames[index].decompressed_size == 0 {
+                    index += 1;
+                }
+                Some(index)
+            }
+            Err(index) => Some(index - 1),
+        }
+    }
+
+    pub(crate) fn push(&mut self, compressed_size: u32, decompressed_size: u32) {
+        let (compressed_offset, decompressed_offset) = self.frames.last().map_or((0, 0), |frame| {
+            (
+                frame.compressed_offset + u64::from(frame.compressed_size),
+                frame.decompressed_offset + u64::from(frame.decompressed_size),
+            )
+        });
+
+        self.frames.push(SeekableFrame {
+            compressed_offset,
+            compressed_size,
+            decompressed_offset,
+            decompressed_size,
+        });
+    }
+
+    /// Serializes this as a skippable frame, without per-frame checksums.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let len = self.frames.len() * 8 + Self::FOOTER_SIZE;
+
+        let mut output = Vec::with_capacity(8 + len);
+        output.extend_from_slice(&SEEK_TABLE_FRAME_MAGIC.to_le_bytes());
+        output.extend_from_slice(&(len as u32).to_le_bytes());
+
+        for frame in &self.frames {
+            output.extend_from_slice(&frame.compressed_size.to_le_bytes());
+            output.extend_from_slice(&frame.decompressed_size.to_le_bytes());
+        }
+
+        output.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        output.push(0);
+        output.extend_from_slice(&SEEKABLE_MAGIC.to_le_bytes());
+
+        output
+    }
+
+    /// Parses the footer at the end of a seekable stream, returning the total size of the
+    /// skippable frame containing the seek table.
+    pub(crate) fn parse_footer(footer: &[u8; Self::FOOTER_SIZE]) -> Result<usize> {
+        if footer[5..9] != SEEKABLE_MAGIC.to_le_bytes() {
+            return Err(error::corrupt(
+                Algorithm::Zstd,
+                "zstd seekable format magic number not found",
+            ));
+        }
+
+        let frames = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]) as usize;
+        let entry_size = if footer[4] & 0x80 != 0 { 12 } else { 8 };
+
+        Ok(8 + frames * entry_size + Self::FOOTER_SIZE)
+    }
+
+    /// Parses the whole skippable frame containing the seek table.
+    pub(crate) fn parse(input: &[u8]) -> Result<Self> {
+        let invalid = || error::corrupt(Algorithm::Zstd, "invalid zstd seek table");
+
+        if input.len() < 8 + Self::FOOTER_SIZE
+            || input[0..4] != SEEK_TABLE_FRAME_MAGIC.to_le_bytes()
+            || input[4..8] != ((input.len() - 8) as u32).to_le_bytes()
+        {
+            return Err(invalid());
+        }
+
+        let (entries, footer) = input[8..].split_at(input.len() - 8 - Self::FOOTER_SIZE);
+        let entry_size = if footer[4] & 0x80 != 0 { 12 } else { 8 };
+        if entries.len() % entry_size != 0 {
+            return Err(invalid());
+        }
+
+        let mut table = SeekTable::default();
+        for entry in entries.chunks(entry_size) {
+            table.push(
+                u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]),
+                u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]),
+            );
+        }
+
+        Ok(table)
+    }
+}
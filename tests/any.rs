@@ -0,0 +1,119 @@
+#[macro_use]
+mod utils;
+
+use async_compression::{
+    futures::{bufread, write},
+    Algorithm,
+};
+use futures::io::AsyncWriteExt as _;
+use utils::{algos, block_on, impls::futures::read::to_vec, one_to_six, one_to_six_stream, Level};
+
+#[test]
+fn parse() {
+    assert_eq!("gzip".parse(), Ok(Algorithm::Gzip));
+    assert_eq!("GZ".parse(), Ok(Algorithm::Gzip));
+    assert_eq!("br".parse(), Ok(Algorithm::Brotli));
+    assert_eq!("zst".parse(), Ok(Algorithm::Zstd));
+    assert_eq!("identity".parse(), Ok(Algorithm::Identity));
+    assert_eq!("zlib".parse(), Ok(Algorithm::Zlib));
+
+    let err = "nope".parse::<Algorithm>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown or disabled compression algorithm `nope`"
+    );
+}
+
+#[test]
+fn display_round_trips() {
+    for algorithm in &[
+        Algorithm::Identity,
+        Algorithm::Brotli,
+        Algorithm::Gzip,
+        Algorithm::Zlib,
+        Algorithm::Zstd,
+    ] {
+        assert_eq!(algorithm.to_string().parse(), Ok(*algorithm));
+    }
+}
+
+#[test]
+fn content_encoding() {
+    assert_eq!(
+        Algorithm::from_content_encoding(" GZip "),
+        Some(Algorithm::Gzip)
+    );
+    assert_eq!(
+        Algorithm::from_content_encoding("x-gzip"),
+        Some(Algorithm::Gzip)
+    );
+    assert_eq!(
+        Algorithm::from_content_encoding("deflate"),
+        Some(Algorithm::Zlib)
+    );
+    assert_eq!(
+        Algorithm::from_content_encoding("br"),
+        Some(Algorithm::Brotli)
+    );
+    assert_eq!(Algorithm::from_content_encoding("compress"), None);
+
+    assert_eq!(Algorithm::Zlib.content_encoding(), Some("deflate"));
+    assert_eq!(Algorithm::Brotli.content_encoding(), Some("br"));
+    assert_eq!(Algorithm::Identity.content_encoding(), Some("identity"));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_compress_gzip() {
+    let input = one_to_six_stream();
+    let encoder = bufread::AnyEncoder::new(
+        utils::impls::futures::bufread::from(&input),
+        Algorithm::Gzip,
+    )
+    .unwrap();
+    assert_eq!(encoder.algorithm(), Algorithm::Gzip);
+
+    let compressed = to_vec(encoder);
+    assert_eq!(algos::gzip::sync::decompress(&compressed), one_to_six());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_decompress_zstd() {
+    let compressed = algos::zstd::sync::compress(one_to_six());
+    let decoder = bufread::AnyDecoder::new(&compressed[..], Algorithm::Zstd);
+    assert_eq!(decoder.algorithm(), Algorithm::Zstd);
+
+    assert_eq!(to_vec(decoder), one_to_six());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_identity() {
+    let input = one_to_six_stream();
+    let encoder = bufread::AnyEncoder::with_quality(
+        utils::impls::futures::bufread::from(&input),
+        Algorithm::Identity,
+        Level::Best,
+    )
+    .unwrap();
+    let output = to_vec(encoder);
+    assert_eq!(output, one_to_six());
+
+    let decoder = bufread::AnyDecoder::new(&output[..], Algorithm::Identity);
+    assert_eq!(to_vec(decoder), one_to_six());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_round_trip_brotli() {
+    let mut encoder = write::AnyEncoder::new(Vec::new(), Algorithm::Brotli).unwrap();
+    block_on(encoder.write_all(one_to_six())).unwrap();
+    block_on(encoder.close()).unwrap();
+    let compressed = encoder.into_inner();
+
+    let mut decoder = write::AnyDecoder::new(Vec::new(), Algorithm::Brotli);
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.close()).unwrap();
+    assert_eq!(decoder.into_inner(), one_to_six());
+}
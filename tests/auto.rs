@@ -0,0 +1,160 @@
+#[macro_use]
+mod utils;
+
+use async_compression::{futures::bufread::AutoDecoder, Algorithm, Error};
+use futures::io::AsyncReadExt as _;
+use utils::{algos, block_on, data, impls::futures::bufread::from, read_to_end, InputStream};
+
+/// Splits `bytes` into single bytes, so that the signature is spread across many reads.
+fn bytewise(bytes: &[u8]) -> InputStream {
+    InputStream::new(bytes.iter().map(|&b| vec![b]).collect())
+}
+
+macro_rules! detect_tests {
+    ($($name:ident: $algo:ident => $variant:ident,)*) => {
+        $(
+            #[test]
+            #[ntest::timeout(1000)]
+            fn $name() {
+                let compressed = algos::$algo::sync::compress(&data());
+                let input = bytewise(&compressed);
+                let mut decoder = AutoDecoder::new(Box::pin(from(&input)));
+                assert_eq!(decoder.detected(), None);
+
+                let mut output = Vec::new();
+                block_on(decoder.read_to_end(&mut output)).unwrap();
+                assert_eq!(output, data());
+                assert_eq!(decoder.detected(), Some(Algorithm::$variant));
+            }
+        )*
+    };
+}
+
+detect_tests! {
+    detect_bzip2: bzip2 => Bzip2,
+    detect_gzip: gzip => Gzip,
+    detect_lz4: lz4 => Lz4,
+    detect_snappy: snappy => Snappy,
+    detect_xz: xz => Xz,
+    detect_zlib: zlib => Zlib,
+    detect_zstd: zstd => Zstd,
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn fallback_brotli() {
+    let compressed = algos::brotli::sync::compress(&data());
+    let input = InputStream::new(vec![compressed]);
+    let decoder = AutoDecoder::with_fallback(Box::pin(from(&input)), Algorithm::Brotli);
+
+    assert_eq!(read_to_end(decoder).unwrap(), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn passthrough() {
+    let input = bytewise(b"plain text, not compressed");
+    let decoder = AutoDecoder::with_fallback(Box::pin(from(&input)), Algorithm::Identity);
+
+    assert_eq!(read_to_end(decoder).unwrap(), b"plain text, not compressed");
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn passthrough_shorter_than_signature() {
+    // A prefix of the xz signature
+    let input = InputStream::new(vec![vec![0xfd, 0x37]]);
+    let decoder = AutoDecoder::with_fallback(Box::pin(from(&input)), Algorithm::Identity);
+
+    assert_eq!(read_to_end(decoder).unwrap(), [0xfd, 0x37]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn passthrough_valid_zlib_header() {
+    // "x^" is a valid zlib header, but "n" is not a valid start of a deflate block
+    let input = bytewise(b"x^n + y^n");
+    let decoder = AutoDecoder::with_fallback(Box::pin(from(&input)), Algorithm::Identity);
+
+    assert_eq!(read_to_end(decoder).unwrap(), b"x^n + y^n");
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn zlib_with_identity_fallback() {
+    let compressed = algos::zlib::sync::compress(&data());
+    let input = InputStream::new(vec![compressed]);
+    let mut decoder = AutoDecoder::with_fallback(Box::pin(from(&input)), Algorithm::Identity);
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, data());
+    assert_eq!(decoder.detected(), Some(Algorithm::Zlib));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn passthrough_empty() {
+    let input = InputStream::new(vec![]);
+    let decoder = AutoDecoder::with_fallback(Box::pin(from(&input)), Algorithm::Identity);
+
+    assert_eq!(read_to_end(decoder).unwrap(), b"");
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn unrecognized() {
+    let input = InputStream::new(vec![b"plain text, not compressed".to_vec()]);
+    let decoder = AutoDecoder::new(Box::pin(from(&input)));
+
+    let error = read_to_end(decoder).unwrap_err();
+
+    assert_eq!(Error::from_io(&error), Some(&Error::UnrecognizedFormat));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn empty() {
+    let input = InputStream::new(vec![]);
+    let decoder = AutoDecoder::new(Box::pin(from(&input)));
+
+    let error = read_to_end(decoder).unwrap_err();
+
+    assert_eq!(Error::from_io(&error), Some(&Error::UnrecognizedFormat));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn multiple_members() {
+    let compressed = [
+        algos::gzip::sync::compress(&data()),
+        algos::gzip::sync::compress(&data()),
+    ]
+    .concat();
+    let input = InputStream::new(vec![compressed]);
+    let mut decoder = AutoDecoder::new(Box::pin(from(&input)));
+    decoder.multiple_members(true);
+
+    assert_eq!(read_to_end(decoder).unwrap(), [data(), data()].concat());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn strict_trailing_data_after_short_member() {
+    use async_compression::TrailingData;
+
+    // An empty zstd frame, which with the trailing data is as long as the longest signature
+    let compressed = b"\x28\xb5\x2f\xfd\x20\x00\x01\x00\x00x";
+
+    let input = InputStream::new(vec![compressed.to_vec()]);
+    let mut decoder = AutoDecoder::new(Box::pin(from(&input)));
+    decoder.trailing_data(TrailingData::Strict);
+    let error = read_to_end(decoder).unwrap_err();
+
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::TrailingData {
+            algorithm: Algorithm::Zstd
+        })
+    );
+}
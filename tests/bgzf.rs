@@ -0,0 +1,122 @@
+#[macro_use]
+mod utils;
+
+test_cases!(bgzf);
+
+#[allow(unused)]
+use utils::{algos::bgzf::sync, InputStream};
+
+#[cfg(feature = "futures-io")]
+use utils::algos::bgzf::futures::{bufread, read};
+
+#[allow(unused)]
+fn record(i: usize) -> Vec<u8> {
+    format!("record {:06}: {}\n", i, "ACGT".repeat(i % 50)).into_bytes()
+}
+
+#[cfg(feature = "futures-io")]
+fn indexed_file() -> (
+    Vec<u8>,
+    Vec<(async_compression::bgzf::VirtualOffset, usize)>,
+    Vec<u8>,
+) {
+    use futures::io::AsyncWriteExt as _;
+
+    let mut encoder = async_compression::futures::write::BgzfEncoder::new(Vec::new());
+    let mut offsets = Vec::new();
+    let mut uncompressed = Vec::new();
+    for i in 0..2000 {
+        offsets.push((encoder.virtual_offset(), uncompressed.len()));
+        uncompressed.extend(record(i));
+        utils::block_on(encoder.write_all(&record(i))).unwrap();
+    }
+    utils::block_on(encoder.close()).unwrap();
+    (encoder.into_inner(), offsets, uncompressed)
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn bgzf_write_empty_is_eof_marker() {
+    use futures::io::AsyncWriteExt as _;
+
+    let mut encoder = async_compression::futures::write::BgzfEncoder::new(Vec::new());
+    utils::block_on(encoder.close()).unwrap();
+
+    assert_eq!(encoder.into_inner(), sync::EOF_MARKER);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "futures-io")]
+fn bgzf_decompress_from_virtual_offsets() {
+    use async_compression::bgzf::VirtualOffset;
+
+    let (compressed, offsets, uncompressed) = indexed_file();
+
+    assert_eq!(offsets[0].0, VirtualOffset::new(0, 0));
+    assert!(offsets.last().unwrap().0.compressed() > 0);
+    assert_eq!(sync::decompress(&compressed), uncompressed);
+
+    for &(offset, start) in offsets.iter().step_by(97) {
+        // Seeking to the start of the block is left to the caller
+        let block = &compressed[offset.compressed() as usize..];
+        assert_eq!(block[..4], [0x1f, 0x8b, 0x08, 0x04]);
+
+        let input = InputStream::new(vec![block.to_vec()]);
+        let decoder = bufread::Decoder::with_virtual_offset(bufread::from(&input), offset);
+        let output = read::to_vec(decoder);
+
+        assert_eq!(output, &uncompressed[start..]);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(feature = "futures-io")]
+fn bgzf_seekable_decompress_from_virtual_offsets() {
+    use async_compression::futures::seekable::BgzfSeekableDecoder;
+    use futures::io::{AsyncReadExt as _, Cursor};
+
+    let (compressed, offsets, uncompressed) = indexed_file();
+
+    for &(offset, start) in offsets.iter().step_by(97) {
+        let mut decoder = BgzfSeekableDecoder::new(Cursor::new(&compressed), offset);
+        let mut output = Vec::new();
+        utils::block_on(decoder.read_to_end(&mut output)).unwrap();
+
+        assert_eq!(output, &uncompressed[start..]);
+    }
+}
+
+#[test]
+#[ntest::timeout(5000)]
+#[cfg(all(feature = "futures-io", feature = "tokio"))]
+fn bgzf_tokio_seekable_decompress_from_virtual_offsets() {
+    use async_compression::tokio::seekable::BgzfSeekableDecoder;
+    use std::io::Cursor;
+    use tokio::io::AsyncReadExt as _;
+
+    let (compressed, offsets, uncompressed) = indexed_file();
+
+    for &(offset, start) in offsets.iter().step_by(97) {
+        let mut decoder = BgzfSeekableDecoder::new(Cursor::new(&compressed), offset);
+        let mut output = Vec::new();
+        utils::block_on(decoder.read_to_end(&mut output)).unwrap();
+
+        assert_eq!(output, &uncompressed[start..]);
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bgzf_virtual_offset_packing() {
+    use async_compression::bgzf::VirtualOffset;
+
+    let offset = VirtualOffset::new(0x1234_5678, 0x9abc);
+
+    assert_eq!(u64::from(offset), 0x1234_5678_9abc);
+    assert_eq!(VirtualOffset::from(0x1234_5678_9abc), offset);
+    assert_eq!(offset.compressed(), 0x1234_5678);
+    assert_eq!(offset.uncompressed(), 0x9abc);
+}
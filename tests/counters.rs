@@ -0,0 +1,94 @@
+#[macro_use]
+mod utils;
+
+use async_compression::futures::{bufread, read, write};
+use futures::io::{AsyncReadExt as _, AsyncWriteExt as _};
+use utils::{algos, block_on, data, impls::futures::bufread::from, InputStream};
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_encoder() {
+    let input = InputStream::new(data().chunks(1000).map(<[u8]>::to_vec).collect());
+    let mut encoder = bufread::GzipEncoder::new(from(&input));
+    assert_eq!((encoder.total_in(), encoder.total_out()), (0, 0));
+
+    let mut compressed = Vec::new();
+    block_on(encoder.read_to_end(&mut compressed)).unwrap();
+    assert_eq!(encoder.total_in(), data().len() as u64);
+    assert_eq!(encoder.total_out(), compressed.len() as u64);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_decoder_multiple_members() {
+    let compressed = algos::zstd::sync::compress(&data());
+    let input = InputStream::new(vec![compressed.clone(), compressed.clone()]);
+    let mut decoder = bufread::ZstdDecoder::new(from(&input));
+    decoder.multiple_members(true);
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(decoder.total_in(), 2 * compressed.len() as u64);
+    assert_eq!(decoder.total_out(), 2 * data().len() as u64);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_decoder_ignores_trailing_data() {
+    let compressed = algos::gzip::sync::compress(&data());
+    let input = InputStream::new(vec![[&compressed[..], b"trailing"].concat()]);
+    let mut decoder = bufread::GzipDecoder::new(from(&input));
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(decoder.total_in(), compressed.len() as u64);
+    assert_eq!(decoder.total_out(), data().len() as u64);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn read_round_trip() {
+    let data = data();
+    let mut encoder = read::ZstdEncoder::new(data.as_slice());
+    let mut compressed = Vec::new();
+    block_on(encoder.read_to_end(&mut compressed)).unwrap();
+    assert_eq!(encoder.total_in(), data.len() as u64);
+    assert_eq!(encoder.total_out(), compressed.len() as u64);
+
+    let mut decoder = read::ZstdDecoder::new(compressed.as_slice());
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(decoder.total_in(), compressed.len() as u64);
+    assert_eq!(decoder.total_out(), data.len() as u64);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_round_trip() {
+    let mut encoder = write::GzipEncoder::new(Vec::new());
+    for chunk in data().chunks(1000) {
+        block_on(encoder.write_all(chunk)).unwrap();
+    }
+    block_on(encoder.close()).unwrap();
+    assert_eq!(encoder.total_in(), data().len() as u64);
+    assert_eq!(encoder.total_out(), encoder.get_ref().len() as u64);
+
+    let compressed = encoder.into_inner();
+    let mut decoder = write::GzipDecoder::new(Vec::new());
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.close()).unwrap();
+    assert_eq!(decoder.total_in(), compressed.len() as u64);
+    assert_eq!(decoder.total_out(), data().len() as u64);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn tokio_write_encoder() {
+    use tokio::io::AsyncWriteExt as _;
+
+    let mut encoder = async_compression::tokio::write::ZstdEncoder::new(Vec::new());
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.shutdown()).unwrap();
+    assert_eq!(encoder.total_in(), data().len() as u64);
+    assert_eq!(encoder.total_out(), encoder.get_ref().len() as u64);
+}
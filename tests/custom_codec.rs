@@ -0,0 +1,266 @@
+//! Tests plugging a codec implemented outside the crate into the generic adapters.
+
+use async_compression::codec::{Decode, Encode, PartialBuffer};
+use futures::{
+    executor::block_on,
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+};
+use std::io::{Error, ErrorKind, Result};
+
+/// A minimal run-length encoding, each run is written as a count and the repeated byte, with a
+/// count of 0 marking the end of the stream.
+#[derive(Debug, Default)]
+struct RleEncoder {
+    run: Option<(u8, u8)>,
+    pending: PartialBuffer<Vec<u8>>,
+    finished: bool,
+}
+
+impl RleEncoder {
+    fn write_run(&mut self) {
+        if let Some((count, byte)) = self.run.take() {
+            self.pending.get_mut().extend_from_slice(&[count, byte]);
+        }
+    }
+
+    /// Returns whether all pending output has been written.
+    fn drain(&mut self, output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>) -> bool {
+        output.copy_unwritten_from(&mut self.pending);
+        if self.pending.unwritten().is_empty() {
+            self.pending = PartialBuffer::default();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Encode for RleEncoder {
+    fn encode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<()> {
+        while self.drain(output) && !input.unwritten().is_empty() {
+            let byte = input.unwritten()[0];
+            input.advance(1);
+            match &mut self.run {
+                Some((count, current)) if *current == byte && *count < u8::MAX => *count += 1,
+                _ => {
+                    self.write_run();
+                    self.run = Some((1, byte));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        self.write_run();
+        Ok(self.drain(output))
+    }
+
+    fn finish(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if !self.finished {
+            self.write_run();
+            self.pending.get_mut().extend_from_slice(&[0, 0]);
+            self.finished = true;
+        }
+        Ok(self.drain(output))
+    }
+}
+
+#[derive(Debug, Default)]
+struct RleDecoder {
+    // A run read partially, or not yet completely written out.
+    header: PartialBuffer<[u8; 2]>,
+    done: bool,
+}
+
+impl Decode for RleDecoder {
+    fn reinit(&mut self) -> Result<()> {
+        *self = Self::default();
+        Ok(())
+    }
+
+    fn decode(
+        &mut self,
+        input: &mut PartialBuffer<impl AsRef<[u8]>>,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        while !self.done {
+            if !self.flush(output)? {
+                return Ok(false);
+            }
+
+            self.header.copy_unwritten_from(input);
+            if !self.header.unwritten().is_empty() {
+                return Ok(false);
+            }
+
+            if self.header.get_mut()[0] == 0 {
+                self.done = true;
+            }
+        }
+        Ok(true)
+    }
+
+    fn flush(
+        &mut self,
+        output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if !self.header.unwritten().is_empty() || self.done {
+            return Ok(true);
+        }
+
+        let [count, byte] = *self.header.get_mut();
+        let len = std::cmp::min(usize::from(count), output.unwritten().len());
+        output.unwritten_mut()[..len]
+            .iter_mut()
+            .for_each(|b| *b = byte);
+        output.advance(len);
+        self.header.get_mut()[0] -= len as u8;
+
+        if self.header.get_mut()[0] == 0 {
+            self.header = PartialBuffer::default();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn finish(
+        &mut self,
+        _output: &mut PartialBuffer<impl AsRef<[u8]> + AsMut<[u8]>>,
+    ) -> Result<bool> {
+        if self.done {
+            Ok(true)
+        } else {
+            Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "reached unexpected EOF",
+            ))
+        }
+    }
+}
+
+/// Runs of 300 repeated bytes, longer than the encoder can fit in a single run.
+fn runs() -> Vec<u8> {
+    (0..2000u32).map(|i| (i / 300) as u8).collect()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_round_trip() {
+    use async_compression::futures::bufread::{Decoder, Encoder};
+
+    let mut compressed = Vec::new();
+    block_on(Encoder::new(&runs()[..], RleEncoder::default()).read_to_end(&mut compressed))
+        .unwrap();
+    // Runs longer than 255 bytes are split in two
+    assert_eq!(compressed.len(), 2 * 13 + 2);
+
+    let mut output = Vec::new();
+    block_on(Decoder::new(&compressed[..], RleDecoder::default()).read_to_end(&mut output))
+        .unwrap();
+    assert_eq!(output, runs());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_decompress_multiple_members() {
+    use async_compression::futures::bufread::Decoder;
+
+    let compressed = [3, b'a', 0, 0, 2, b'b', 0, 0];
+    let mut decoder = Decoder::new(&compressed[..], RleDecoder::default());
+    decoder.multiple_members(true);
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, b"aaabb");
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_decompress_truncated() {
+    use async_compression::futures::bufread::Decoder;
+
+    let compressed = [3, b'a', 2];
+    let mut output = Vec::new();
+    let result =
+        block_on(Decoder::new(&compressed[..], RleDecoder::default()).read_to_end(&mut output));
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn read_round_trip_with_capacity() {
+    use async_compression::futures::read::{Decoder, Encoder};
+
+    let mut compressed = Vec::new();
+    block_on(
+        Encoder::with_capacity(5, &runs()[..], RleEncoder::default()).read_to_end(&mut compressed),
+    )
+    .unwrap();
+
+    let mut output = Vec::new();
+    block_on(
+        Decoder::with_capacity(1, &compressed[..], RleDecoder::default()).read_to_end(&mut output),
+    )
+    .unwrap();
+    assert_eq!(output, runs());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_round_trip() {
+    use async_compression::futures::write::{Decoder, Encoder};
+
+    let mut encoder = Encoder::new(Vec::new(), RleEncoder::default());
+    for chunk in runs().chunks(7) {
+        block_on(encoder.write_all(chunk)).unwrap();
+    }
+    block_on(encoder.close()).unwrap();
+    let compressed = encoder.into_inner();
+
+    let mut decoder = Decoder::new(Vec::new(), RleDecoder::default());
+    for chunk in compressed.chunks(3) {
+        block_on(decoder.write_all(chunk)).unwrap();
+    }
+    block_on(decoder.close()).unwrap();
+    assert_eq!(decoder.into_inner(), runs());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_round_trip_with_capacity() {
+    use async_compression::futures::write::{Decoder, Encoder};
+
+    let mut encoder = Encoder::with_capacity(1, Vec::new(), RleEncoder::default());
+    block_on(encoder.write_all(&runs())).unwrap();
+    block_on(encoder.close()).unwrap();
+    let compressed = encoder.into_inner();
+
+    let mut decoder = Decoder::with_capacity(3, Vec::new(), RleDecoder::default());
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.close()).unwrap();
+    assert_eq!(decoder.into_inner(), runs());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_get_encoder_ref() {
+    use async_compression::futures::write::Encoder;
+
+    let mut encoder = Encoder::new(Vec::new(), RleEncoder::default());
+    block_on(encoder.write_all(b"aaa")).unwrap();
+
+    assert_eq!(encoder.get_encoder_ref().run, Some((3, b'a')));
+}
@@ -2,3 +2,42 @@
 mod utils;
 
 test_cases!(deflate);
+
+#[allow(unused)]
+use utils::{text, InputStream, Level, DICTIONARY};
+
+#[cfg(feature = "futures-io")]
+use utils::algos::deflate::futures::{bufread, read};
+
+/// `text()` compressed by Python's `zlib` module as raw deflate with `DICTIONARY` as the preset
+/// dictionary.
+#[allow(unused)]
+const COMPRESSED_WITH_DICTIONARY: &[u8] = include_bytes!("artifacts/dictionary.deflate");
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn deflate_bufread_decompress_with_dictionary() {
+    let input = InputStream::from(COMPRESSED_WITH_DICTIONARY.chunks(10));
+    let decoder = bufread::Decoder::with_dictionary(bufread::from(&input), DICTIONARY).unwrap();
+    let output = read::to_vec(decoder);
+
+    assert_eq!(output, text());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn deflate_bufread_dictionary_round_trip() {
+    let input = InputStream::from(vec![text()]);
+    let encoder =
+        bufread::Encoder::with_dictionary(bufread::from(&input), Level::Default, DICTIONARY)
+            .unwrap();
+    let compressed = read::to_vec(encoder);
+
+    let input = InputStream::from(compressed.chunks(10));
+    let decoder = bufread::Decoder::with_dictionary(bufread::from(&input), DICTIONARY).unwrap();
+    let output = read::to_vec(decoder);
+
+    assert_eq!(output, text());
+}
@@ -0,0 +1,171 @@
+#[macro_use]
+mod utils;
+
+use async_compression::{
+    futures::{bufread, write},
+    Algorithm, Error, Level,
+};
+use futures::io::{AsyncReadExt as _, AsyncWriteExt as _};
+use std::io;
+use utils::{algos, block_on, data, impls::futures::bufread::from, read_to_end, InputStream};
+
+#[test]
+#[ntest::timeout(1000)]
+fn gzip_corrupt_header() {
+    let input = InputStream::new(vec![b"this is not gzip data".to_vec()]);
+    let error = read_to_end(bufread::GzipDecoder::new(from(&input))).unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    match Error::from_io(&error) {
+        Some(Error::Corrupt { algorithm, .. }) => assert_eq!(*algorithm, Algorithm::Gzip),
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn gzip_checksum_mismatch() {
+    let mut compressed = algos::gzip::sync::compress(&data());
+    let crc = compressed.len() - 8;
+    compressed[crc] ^= 0xff;
+
+    let input = InputStream::new(vec![compressed]);
+    let error = read_to_end(bufread::GzipDecoder::new(from(&input))).unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::ChecksumMismatch {
+            algorithm: Algorithm::Gzip
+        })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn gzip_truncated() {
+    let compressed = algos::gzip::sync::compress(&data());
+    let input = InputStream::new(vec![compressed[..compressed.len() / 2].to_vec()]);
+    let error = read_to_end(bufread::GzipDecoder::new(from(&input))).unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::Truncated {
+            algorithm: Algorithm::Gzip
+        })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn zstd_truncated() {
+    let compressed = algos::zstd::sync::compress(&data());
+    let input = InputStream::new(vec![compressed[..compressed.len() / 2].to_vec()]);
+    let error = read_to_end(bufread::ZstdDecoder::new(from(&input))).unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::Truncated {
+            algorithm: Algorithm::Zstd
+        })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn zstd_checksum_mismatch() {
+    let input = InputStream::new(vec![data()]);
+    let params = async_compression::zstd::EncoderParams::new().checksum(true);
+    let encoder = bufread::ZstdEncoder::with_params(from(&input), &params).unwrap();
+    let mut compressed = read_to_end(encoder).unwrap();
+    let checksum = compressed.len() - 4;
+    compressed[checksum] ^= 0xff;
+
+    let input = InputStream::new(vec![compressed]);
+    let error = read_to_end(bufread::ZstdDecoder::new(from(&input))).unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::ChecksumMismatch {
+            algorithm: Algorithm::Zstd
+        })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn zstd_memory_limit() {
+    let input = InputStream::new(vec![data()]);
+    let params = async_compression::zstd::EncoderParams::new().window_log(24);
+    let encoder = bufread::ZstdEncoder::with_params(from(&input), &params).unwrap();
+    let compressed = read_to_end(encoder).unwrap();
+
+    let input = InputStream::new(vec![compressed]);
+    let params = async_compression::zstd::DecoderParams::new().window_log_max(20);
+    let decoder = bufread::ZstdDecoder::with_params(from(&input), &params).unwrap();
+    let error = read_to_end(decoder).unwrap_err();
+
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::MemoryLimit {
+            algorithm: Algorithm::Zstd
+        })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn zlib_unknown_dictionary() {
+    let input = InputStream::new(vec![data()]);
+    let encoder =
+        bufread::ZlibEncoder::with_dictionary(from(&input), Level::Default, b"a dictionary")
+            .unwrap();
+    let compressed = read_to_end(encoder).unwrap();
+
+    let input = InputStream::new(vec![compressed]);
+    let error = read_to_end(bufread::ZlibDecoder::new(from(&input))).unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    match Error::from_io(&error) {
+        Some(Error::Unsupported { algorithm, .. }) => assert_eq!(*algorithm, Algorithm::Zlib),
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_decoder_truncated() {
+    let compressed = algos::zlib::sync::compress(&data());
+    let mut decoder = write::ZlibDecoder::new(Vec::new());
+    block_on(decoder.write_all(&compressed[..compressed.len() / 2])).unwrap();
+    let error = block_on(decoder.close()).unwrap_err();
+
+    assert_eq!(
+        Error::from_io(&error).map(Error::algorithm),
+        Some(Algorithm::Zlib)
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn reader_errors_are_not_wrapped() {
+    let input = InputStream::new(vec![algos::gzip::sync::compress(&data())]);
+    let reader = from(&input).chain(futures::io::AllowStdIo::new(ErrorReader));
+    let mut decoder = bufread::GzipDecoder::new(futures::io::BufReader::new(reader));
+    decoder.multiple_members(true);
+
+    let error = read_to_end(decoder).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+    assert_eq!(Error::from_io(&error), None);
+}
+
+struct ErrorReader;
+
+impl io::Read for ErrorReader {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::ConnectionReset.into())
+    }
+}
@@ -0,0 +1,108 @@
+#[macro_use]
+mod utils;
+
+use async_compression::{futures::write, FlushMode};
+use futures::io::AsyncWriteExt as _;
+use std::io;
+use utils::{algos, block_on, data};
+
+#[test]
+#[ntest::timeout(1000)]
+fn gzip_full_flush_resyncs() {
+    let mut encoder = write::GzipEncoder::new(Vec::new());
+    encoder.flush_mode(FlushMode::Full);
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.flush()).unwrap();
+    let resync = encoder.get_ref().len();
+    block_on(encoder.write_all(&data()[..1000])).unwrap();
+    block_on(encoder.close()).unwrap();
+
+    let output = encoder.into_inner();
+    assert_eq!(
+        algos::gzip::sync::decompress(&output),
+        [data(), data()[..1000].to_vec()].concat()
+    );
+    // The deflate data after a full flush decodes without the dictionary before it, up to the
+    // gzip footer.
+    assert_eq!(
+        algos::deflate::sync::decompress(&output[resync..output.len() - 8]),
+        &data()[..1000]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn gzip_full_flush_after_sync_flush() {
+    let mut encoder = write::GzipEncoder::new(Vec::new());
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.flush()).unwrap();
+    encoder.flush_mode(FlushMode::Full);
+    block_on(encoder.flush()).unwrap();
+    let resync = encoder.get_ref().len();
+    block_on(encoder.write_all(&data()[..1000])).unwrap();
+    block_on(encoder.close()).unwrap();
+
+    // The full flush is still done after the sync flush, even with nothing written in between.
+    let output = encoder.into_inner();
+    assert_eq!(
+        algos::deflate::sync::decompress(&output[resync..output.len() - 8]),
+        &data()[..1000]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn gzip_partial_flush() {
+    let mut encoder = write::GzipEncoder::new(Vec::new());
+    encoder.flush_mode(FlushMode::Partial);
+    for chunk in data().chunks(1000) {
+        block_on(encoder.write_all(chunk)).unwrap();
+        block_on(encoder.flush()).unwrap();
+    }
+    block_on(encoder.close()).unwrap();
+
+    assert_eq!(algos::gzip::sync::decompress(encoder.get_ref()), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn zstd_block_flush_ends_frame() {
+    let mut encoder = write::ZstdEncoder::new(Vec::new());
+    encoder.flush_mode(FlushMode::Block);
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.flush()).unwrap();
+    let end = encoder.get_ref().len();
+    block_on(encoder.flush()).unwrap();
+    assert_eq!(encoder.get_ref().len(), end);
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.close()).unwrap();
+
+    let output = encoder.into_inner();
+    assert_eq!(algos::zstd::sync::decompress(&output[..end]), data());
+    assert_eq!(algos::zstd::sync::decompress(&output[end..]), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn xz_full_flush() {
+    let mut encoder = write::XzEncoder::new(Vec::new());
+    encoder.flush_mode(FlushMode::Full);
+    for chunk in data().chunks(5000) {
+        block_on(encoder.write_all(chunk)).unwrap();
+        block_on(encoder.flush()).unwrap();
+    }
+    block_on(encoder.close()).unwrap();
+
+    assert_eq!(algos::xz::sync::decompress(encoder.get_ref()), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn brotli_full_flush_unsupported() {
+    let mut encoder = write::BrotliEncoder::new(Vec::new());
+    encoder.flush_mode(FlushMode::Full);
+    block_on(encoder.write_all(&data())).unwrap();
+    let error = block_on(encoder.flush()).unwrap_err();
+
+    assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+}
@@ -164,3 +164,210 @@ fn gzip_bufread_chunks_decompress_with_extra_header() {
 
     assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
 }
+
+#[allow(unused)]
+fn compress_with_metadata(data: &[u8], filename: &str) -> Vec<u8> {
+    use flate2::{Compression, GzBuilder};
+    use std::io::Write;
+
+    // A single subfield long enough that the extra field length needs both bytes
+    let mut extra = vec![b'A', b'B'];
+    extra.extend_from_slice(&296u16.to_le_bytes());
+    extra.extend_from_slice(&[7; 296]);
+
+    let mut bytes = Vec::new();
+    {
+        let mut gz = GzBuilder::new()
+            .filename(filename)
+            .comment("test file, please delete")
+            .mtime(1_234_567_890)
+            .operating_system(3)
+            .extra(extra)
+            .write(&mut bytes, Compression::fast());
+
+        gz.write_all(data).unwrap();
+    }
+
+    bytes
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_bufread_decompress_header() {
+    use utils::{algos::gzip::futures::read, pin_mut};
+
+    let bytes = compress_with_metadata(&[1, 2, 3, 4, 5, 6], "hello_world.txt");
+
+    let input = InputStream::from(bytes.chunks(1));
+    let decoder = bufread::Decoder::new(bufread::from(&input));
+    pin_mut!(decoder);
+    assert_eq!(decoder.header(), None);
+
+    let output = read::to_vec(decoder.as_mut());
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let header = decoder.header().unwrap();
+    assert_eq!(header.filename(), Some(&b"hello_world.txt"[..]));
+    assert_eq!(header.comment(), Some(&b"test file, please delete"[..]));
+    assert_eq!(header.mtime(), 1_234_567_890);
+    assert_eq!(header.operating_system(), 3);
+    assert!(!header.is_text());
+    assert_eq!(header.extra().map(<[u8]>::len), Some(300));
+    assert_eq!(
+        header.extra_subfields().collect::<Vec<_>>(),
+        [(*b"AB", &[7; 296][..])]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_bufread_decompress_member_headers() {
+    use std::sync::{Arc, Mutex};
+    use utils::algos::gzip::futures::read;
+
+    let mut bytes = compress_with_metadata(&[1, 2, 3], "first.txt");
+    bytes.extend(compress_with_metadata(&[4, 5, 6], "second.txt"));
+
+    let filenames = Arc::new(Mutex::new(Vec::new()));
+
+    let input = InputStream::from(vec![bytes]);
+    let mut decoder = bufread::Decoder::new(bufread::from(&input));
+    decoder.multiple_members(true);
+    decoder.on_member_header({
+        let filenames = filenames.clone();
+        move |header| {
+            let filename = header.filename().unwrap().to_vec();
+            filenames.lock().unwrap().push(filename);
+        }
+    });
+    let output = read::to_vec(decoder);
+
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+    assert_eq!(
+        *filenames.lock().unwrap(),
+        [b"first.txt".to_vec(), b"second.txt".to_vec()]
+    );
+}
+
+#[allow(unused)]
+fn full_header() -> async_compression::gzip::GzipHeader {
+    async_compression::gzip::GzipHeaderBuilder::new()
+        .filename("hello_world.txt")
+        .comment("test file, please delete")
+        .mtime(1_234_567_890)
+        .operating_system(3)
+        .extra_subfield(*b"AB", &[1, 2, 3])
+        .extra_subfield(*b"CD", &[4, 5])
+        .text(true)
+        .header_crc(true)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn gzip_header_builder_invalid() {
+    use async_compression::gzip::GzipHeaderBuilder;
+    use std::io::ErrorKind;
+
+    let error = GzipHeaderBuilder::new()
+        .filename(&b"nul\0name"[..])
+        .build()
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let error = GzipHeaderBuilder::new()
+        .comment(&b"nul\0comment"[..])
+        .build()
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let error = GzipHeaderBuilder::new()
+        .extra_subfield(*b"AB", &[0; 40_000])
+        .extra_subfield(*b"CD", &[0; 40_000])
+        .build()
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    assert!(GzipHeaderBuilder::new()
+        .extra_subfield(*b"AB", &[0; 65_531])
+        .build()
+        .is_ok());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_bufread_compress_with_header() {
+    use std::io::Read;
+    use utils::{algos::gzip::futures::read, Level};
+
+    let input = InputStream::from(vec![vec![1, 2, 3, 4, 5, 6]]);
+    let encoder =
+        bufread::Encoder::with_header(bufread::from(&input), Level::Default, &full_header());
+    let compressed = read::to_vec(encoder);
+
+    let mut decoder = flate2::read::GzDecoder::new(&compressed[..]);
+    let mut output = Vec::new();
+    decoder.read_to_end(&mut output).unwrap();
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+
+    let header = decoder.header().unwrap();
+    assert_eq!(header.filename(), Some(&b"hello_world.txt"[..]));
+    assert_eq!(header.comment(), Some(&b"test file, please delete"[..]));
+    assert_eq!(header.mtime(), 1_234_567_890);
+    assert_eq!(header.operating_system(), 3);
+    assert_eq!(
+        header.extra(),
+        Some(&b"AB\x03\x00\x01\x02\x03CD\x02\x00\x04\x05"[..])
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_bufread_header_round_trip() {
+    use utils::{algos::gzip::futures::read, pin_mut, Level};
+
+    let input = InputStream::from(vec![vec![1, 2, 3, 4, 5, 6]]);
+    let encoder =
+        bufread::Encoder::with_header(bufread::from(&input), Level::Default, &full_header());
+    let compressed = read::to_vec(encoder);
+
+    let input = InputStream::from(vec![compressed]);
+    let decoder = bufread::Decoder::new(bufread::from(&input));
+    pin_mut!(decoder);
+    let output = read::to_vec(decoder.as_mut());
+
+    assert_eq!(output, &[1, 2, 3, 4, 5, 6][..]);
+    assert_eq!(decoder.header(), Some(&full_header()));
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn gzip_bufread_decompress_bad_header_crc() {
+    use async_compression::{Algorithm, Error};
+    use utils::{algos::gzip::futures::read, Level};
+
+    let input = InputStream::from(vec![vec![1, 2, 3, 4, 5, 6]]);
+    let encoder =
+        bufread::Encoder::with_header(bufread::from(&input), Level::Default, &full_header());
+    let mut compressed = read::to_vec(encoder);
+
+    // The header CRC is the last part of the header, right before the deflate stream
+    let crc_offset = 10 + 2 + 14 + 16 + 25;
+    compressed[crc_offset] ^= 0xff;
+
+    let input = InputStream::from(vec![compressed]);
+    let decoder = bufread::Decoder::new(bufread::from(&input));
+    let error = read::poll_read(decoder, &mut [0; 6]).unwrap_err();
+
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::ChecksumMismatch {
+            algorithm: Algorithm::Gzip
+        })
+    );
+}
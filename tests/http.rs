@@ -0,0 +1,167 @@
+#[macro_use]
+mod utils;
+
+use async_compression::{
+    futures::{bufread::ChainDecoder, write::AnyEncoder},
+    http::{negotiate, parse_content_encoding},
+    Algorithm,
+};
+use futures::io::{AsyncReadExt as _, AsyncWriteExt as _};
+use utils::{algos, block_on, data, impls::futures::bufread::from, InputStream};
+
+#[test]
+fn parse_content_encoding_list() {
+    assert_eq!(
+        parse_content_encoding("gzip, br"),
+        Ok(vec![Algorithm::Gzip, Algorithm::Brotli])
+    );
+    assert_eq!(
+        parse_content_encoding(" X-GZIP ,, identity"),
+        Ok(vec![Algorithm::Gzip])
+    );
+    assert_eq!(parse_content_encoding(""), Ok(vec![]));
+    assert_eq!(
+        parse_content_encoding("gzip, compress")
+            .unwrap_err()
+            .to_string(),
+        "unknown or disabled compression algorithm `compress`"
+    );
+}
+
+#[test]
+fn negotiate_q_values() {
+    let preferences = [Algorithm::Zstd, Algorithm::Brotli, Algorithm::Gzip];
+
+    assert_eq!(negotiate("gzip, br", &preferences), Some(Algorithm::Brotli));
+    assert_eq!(
+        negotiate("gzip;q=1.0, br;q=0.5", &preferences),
+        Some(Algorithm::Gzip)
+    );
+    assert_eq!(
+        negotiate("GZIP;Q=0.5, deflate", &preferences),
+        Some(Algorithm::Gzip)
+    );
+    assert_eq!(negotiate("x-gzip", &preferences), Some(Algorithm::Gzip));
+    assert_eq!(negotiate("*", &preferences), Some(Algorithm::Zstd));
+    assert_eq!(
+        negotiate("zstd;q=0, *;q=0.8", &preferences),
+        Some(Algorithm::Brotli)
+    );
+}
+
+#[test]
+fn negotiate_identity() {
+    let preferences = [Algorithm::Brotli, Algorithm::Gzip];
+
+    assert_eq!(negotiate("", &preferences), Some(Algorithm::Identity));
+    assert_eq!(
+        negotiate("compress", &preferences),
+        Some(Algorithm::Identity)
+    );
+    assert_eq!(
+        negotiate("gzip;q=0.1, identity;q=0.5", &preferences),
+        Some(Algorithm::Identity)
+    );
+    assert_eq!(negotiate("identity;q=0", &preferences), None);
+    assert_eq!(negotiate("*;q=0", &preferences), None);
+    assert_eq!(
+        negotiate("*;q=0, identity", &preferences),
+        Some(Algorithm::Identity)
+    );
+    assert_eq!(
+        negotiate("identity;q=0, gzip", &preferences),
+        Some(Algorithm::Gzip)
+    );
+}
+
+#[test]
+fn negotiate_ignores_malformed() {
+    let preferences = [Algorithm::Gzip];
+
+    assert_eq!(
+        negotiate("gzip;q=2", &preferences),
+        Some(Algorithm::Identity)
+    );
+    assert_eq!(
+        negotiate("gzip;q=0.0001", &preferences),
+        Some(Algorithm::Identity)
+    );
+    assert_eq!(negotiate("gzip;q", &preferences), Some(Algorithm::Identity));
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn chain_decoder() {
+    let compressed = algos::brotli::sync::compress(&algos::gzip::sync::compress(&data()));
+    let input = InputStream::new(compressed.chunks(1000).map(<[u8]>::to_vec).collect());
+    let mut decoder =
+        ChainDecoder::from_content_encoding(Box::pin(from(&input)), "gzip, br").unwrap();
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn chain_decoder_inner_members() {
+    let members = [
+        algos::gzip::sync::compress(b"first member "),
+        algos::gzip::sync::compress(b"second member"),
+    ]
+    .concat();
+    let compressed = algos::brotli::sync::compress(&members);
+    let input = InputStream::new(vec![compressed]);
+    let mut decoder =
+        ChainDecoder::from_content_encoding(Box::pin(from(&input)), "gzip, br").unwrap();
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, b"first member second member");
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn chain_decoder_empty() {
+    let input = InputStream::new(vec![b"not encoded".to_vec()]);
+    let mut decoder = ChainDecoder::from_content_encoding(Box::pin(from(&input)), "").unwrap();
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, b"not encoded");
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn chain_decoder_truncated() {
+    let compressed = algos::zstd::sync::compress(&algos::zlib::sync::compress(&data()));
+    let input = InputStream::new(vec![compressed[..compressed.len() / 2].to_vec()]);
+    let mut decoder =
+        ChainDecoder::new(Box::pin(from(&input)), &[Algorithm::Zlib, Algorithm::Zstd]);
+
+    let mut output = Vec::new();
+    assert!(block_on(decoder.read_to_end(&mut output)).is_err());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn negotiate_encoder() {
+    let mut encoder = AnyEncoder::negotiate(
+        Vec::new(),
+        "br;q=0.9, gzip",
+        &[Algorithm::Brotli, Algorithm::Gzip],
+    )
+    .unwrap();
+    assert_eq!(encoder.algorithm().content_encoding(), Some("gzip"));
+
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.close()).unwrap();
+    assert_eq!(algos::gzip::sync::decompress(&encoder.into_inner()), data());
+}
+
+#[test]
+fn negotiate_encoder_not_acceptable() {
+    let encoder = AnyEncoder::negotiate(Vec::new(), "br, identity;q=0", &[Algorithm::Gzip]);
+
+    assert!(encoder.is_none());
+}
@@ -0,0 +1,148 @@
+#[macro_use]
+mod utils;
+
+use async_compression::{
+    futures::{bufread, read, write},
+    LimitExceeded,
+};
+use futures::io::AsyncWriteExt as _;
+use std::io;
+use utils::{algos, block_on, impls::futures::bufread::from, read_to_end, InputStream};
+
+/// Compresses extremely well.
+fn zeros() -> Vec<u8> {
+    vec![0; 1_000_000]
+}
+
+/// Compresses reasonably, at a ratio of much less than 100.
+fn scrambled() -> Vec<u8> {
+    (0..20_000u32)
+        .flat_map(|i| i.wrapping_mul(2_654_435_761).to_le_bytes())
+        .collect()
+}
+
+fn limit_exceeded(error: &io::Error) -> Option<LimitExceeded> {
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    error.get_ref()?.downcast_ref::<LimitExceeded>().copied()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_output_size() {
+    let input = InputStream::new(vec![algos::gzip::sync::compress(&zeros())]);
+    let mut decoder = bufread::GzipDecoder::new(from(&input));
+    decoder.max_output_size(Some(10_000));
+
+    let error = read_to_end(decoder).unwrap_err();
+    assert_eq!(
+        limit_exceeded(&error),
+        Some(LimitExceeded::OutputSize { limit: 10_000 })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_output_size_exact() {
+    let input = InputStream::new(vec![algos::gzip::sync::compress(&zeros())]);
+    let mut decoder = bufread::GzipDecoder::new(from(&input));
+    decoder.max_output_size(Some(zeros().len() as u64));
+
+    assert_eq!(read_to_end(decoder).unwrap(), zeros());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_output_size_multiple_members() {
+    let compressed = algos::zstd::sync::compress(&scrambled());
+    let input = InputStream::new(vec![compressed.clone(), compressed]);
+    let mut decoder = bufread::ZstdDecoder::new(from(&input));
+    decoder.multiple_members(true);
+    decoder.max_output_size(Some(scrambled().len() as u64 + 1));
+
+    let error = read_to_end(decoder).unwrap_err();
+    assert!(limit_exceeded(&error).is_some());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_ratio() {
+    let input = InputStream::new(vec![algos::zstd::sync::compress(&zeros())]);
+    let mut decoder = bufread::ZstdDecoder::new(from(&input));
+    decoder.max_ratio(Some(100));
+
+    let error = read_to_end(decoder).unwrap_err();
+    assert_eq!(
+        limit_exceeded(&error),
+        Some(LimitExceeded::Ratio { limit: 100 })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn bufread_ratio_within_limit() {
+    let input = InputStream::new(vec![algos::zstd::sync::compress(&scrambled())]);
+    let mut decoder = bufread::ZstdDecoder::new(from(&input));
+    decoder.max_ratio(Some(100));
+
+    assert_eq!(read_to_end(decoder).unwrap(), scrambled());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn read_output_size() {
+    let compressed = algos::bzip2::sync::compress(&zeros());
+    let mut decoder = read::BzDecoder::new(compressed.as_slice());
+    decoder.max_output_size(Some(10_000));
+
+    let error = read_to_end(decoder).unwrap_err();
+    assert!(limit_exceeded(&error).is_some());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_output_size() {
+    let compressed = algos::gzip::sync::compress(&zeros());
+    let mut decoder = write::GzipDecoder::new(Vec::new());
+    decoder.max_output_size(Some(10_000));
+
+    let error = block_on(async {
+        decoder.write_all(&compressed).await?;
+        decoder.close().await
+    })
+    .unwrap_err();
+    assert_eq!(
+        limit_exceeded(&error),
+        Some(LimitExceeded::OutputSize { limit: 10_000 })
+    );
+    assert!(decoder.get_ref().len() <= 10_000);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_ratio() {
+    let compressed = algos::bzip2::sync::compress(&zeros());
+    let mut decoder = write::BzDecoder::new(Vec::new());
+    decoder.max_ratio(Some(100));
+
+    let error = block_on(async {
+        decoder.write_all(&compressed).await?;
+        decoder.close().await
+    })
+    .unwrap_err();
+    assert_eq!(
+        limit_exceeded(&error),
+        Some(LimitExceeded::Ratio { limit: 100 })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_ratio_within_limit() {
+    let compressed = algos::gzip::sync::compress(&scrambled());
+    let mut decoder = write::GzipDecoder::new(Vec::new());
+    decoder.max_ratio(Some(100));
+
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.close()).unwrap();
+    assert_eq!(decoder.into_inner(), scrambled());
+}
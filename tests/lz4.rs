@@ -0,0 +1,4 @@
+#[macro_use]
+mod utils;
+
+test_cases!(lz4);
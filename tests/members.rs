@@ -0,0 +1,100 @@
+#[macro_use]
+mod utils;
+
+use async_compression::futures::{bufread, read, write};
+use futures::io::{AsyncReadExt as _, AsyncWriteExt as _};
+use utils::{algos, block_on, data, impls::futures::bufread::from, InputStream};
+
+macro_rules! write_members {
+    ($($name:ident: $encoder:ident, $decoder:ident, $algo:ident;)*) => {
+        $(
+            #[test]
+            #[ntest::timeout(1000)]
+            fn $name() {
+                let mut encoder = write::$encoder::new(Vec::new());
+                block_on(encoder.write_all(&data())).unwrap();
+                block_on(encoder.finish_member()).unwrap();
+                let first = encoder.get_ref().len();
+                block_on(encoder.write_all(&data()[..1000])).unwrap();
+                block_on(encoder.close()).unwrap();
+
+                let output = encoder.into_inner();
+                assert_eq!(algos::$algo::sync::decompress(&output[..first]), data());
+
+                let input = InputStream::new(vec![output]);
+                let mut decoder = bufread::$decoder::new(from(&input));
+                decoder.multiple_members(true);
+                let mut decoded = Vec::new();
+                block_on(decoder.read_to_end(&mut decoded)).unwrap();
+                assert_eq!(decoded, [data(), data()[..1000].to_vec()].concat());
+            }
+        )*
+    };
+}
+
+write_members! {
+    write_gzip_members: GzipEncoder, GzipDecoder, gzip;
+    write_zlib_members: ZlibEncoder, ZlibDecoder, zlib;
+    write_zstd_members: ZstdEncoder, ZstdDecoder, zstd;
+    write_xz_members: XzEncoder, XzDecoder, xz;
+    write_bzip2_members: BzEncoder, BzDecoder, bzip2;
+    write_bgzf_members: BgzfEncoder, BgzfDecoder, bgzf;
+    write_snappy_members: SnappyEncoder, SnappyDecoder, snappy;
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_member_unsupported() {
+    let mut encoder = write::ZstdSeekableEncoder::new(Vec::new());
+    block_on(encoder.write_all(&data())).unwrap();
+    let len = encoder.get_ref().len();
+
+    let error = block_on(encoder.finish_member()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+    assert_eq!(encoder.get_ref().len(), len);
+
+    block_on(encoder.close()).unwrap();
+    assert_eq!(algos::zstd::sync::decompress(encoder.get_ref()), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn write_no_empty_member_at_end() {
+    let mut encoder = write::GzipEncoder::new(Vec::new());
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.finish_member()).unwrap();
+    let len = encoder.get_ref().len();
+    block_on(encoder.flush()).unwrap();
+    block_on(encoder.close()).unwrap();
+
+    assert_eq!(encoder.get_ref().len(), len);
+    assert_eq!(algos::gzip::sync::decompress(encoder.get_ref()), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn read_members() {
+    let input = InputStream::new(data().chunks(1000).map(<[u8]>::to_vec).collect());
+    let mut encoder = read::ZstdEncoder::new(from(&input));
+
+    let mut output = Vec::new();
+    let mut buf = [0; 10];
+    while encoder.total_in() < 5000 {
+        let len = block_on(encoder.read(&mut buf)).unwrap();
+        output.extend_from_slice(&buf[..len]);
+    }
+    let split = encoder.total_in() as usize;
+    encoder.finish_member().unwrap();
+    block_on(encoder.read_to_end(&mut output)).unwrap();
+
+    let input = InputStream::new(vec![output]);
+    let mut first = Vec::new();
+    block_on(bufread::ZstdDecoder::new(from(&input)).read_to_end(&mut first)).unwrap();
+    assert_eq!(first, data()[..split]);
+
+    let mut decoder = bufread::ZstdDecoder::new(from(&input));
+    decoder.multiple_members(true);
+    let mut decoded = Vec::new();
+    block_on(decoder.read_to_end(&mut decoded)).unwrap();
+    assert_eq!(decoded, data());
+}
@@ -165,6 +165,9 @@ macro_rules! tests {
 }
 
 mod proptest {
+    #[cfg(feature = "bgzf")]
+    tests!(bgzf);
+
     #[cfg(feature = "brotli")]
     tests!(brotli);
 
@@ -177,9 +180,15 @@ mod proptest {
     #[cfg(feature = "gzip")]
     tests!(gzip);
 
+    #[cfg(feature = "lz4")]
+    tests!(lz4);
+
     #[cfg(feature = "lzma")]
     tests!(lzma);
 
+    #[cfg(feature = "snappy")]
+    tests!(snappy);
+
     #[cfg(feature = "xz")]
     tests!(xz);
 
@@ -0,0 +1,73 @@
+#[macro_use]
+mod utils;
+
+use async_compression::futures::read::{GzipDecoder, GzipEncoder, ZstdDecoder};
+use futures_test::io::AsyncReadTestExt as _;
+use utils::{algos, block_on, data, impls::futures::bufread::from, read_to_end, InputStream};
+
+#[test]
+#[ntest::timeout(1000)]
+fn decode() {
+    let compressed = algos::gzip::sync::compress(&data());
+    let input = InputStream::new(compressed.chunks(1000).map(<[u8]>::to_vec).collect());
+    let decoder = GzipDecoder::new(Box::pin(from(&input)).interleave_pending());
+
+    assert_eq!(read_to_end(decoder).unwrap(), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn decode_with_buffer_capacity() {
+    let compressed = algos::zstd::sync::compress(&data());
+    let decoder =
+        ZstdDecoder::new(compressed.as_slice().interleave_pending()).with_buffer_capacity(3);
+
+    assert_eq!(read_to_end(decoder).unwrap(), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn decode_multiple_members() {
+    let compressed = [
+        algos::gzip::sync::compress(&data()),
+        algos::gzip::sync::compress(&data()),
+    ]
+    .concat();
+    let mut decoder = GzipDecoder::new(compressed.as_slice()).with_buffer_capacity(100);
+    decoder.multiple_members(true);
+
+    assert_eq!(read_to_end(decoder).unwrap(), [data(), data()].concat());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn decode_truncated() {
+    let compressed = algos::gzip::sync::compress(&data());
+    let decoder = GzipDecoder::new(&compressed[..compressed.len() / 2]);
+
+    assert!(read_to_end(decoder).is_err());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn encode() {
+    let data = data();
+    let encoder = GzipEncoder::new(data.as_slice().interleave_pending()).with_buffer_capacity(7);
+
+    let compressed = read_to_end(encoder).unwrap();
+    assert_eq!(algos::gzip::sync::decompress(&compressed), data);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn tokio_decode() {
+    use tokio::io::AsyncReadExt as _;
+
+    let compressed = algos::gzip::sync::compress(&data());
+    let mut decoder = async_compression::tokio::read::GzipDecoder::new(compressed.as_slice())
+        .with_buffer_capacity(100);
+
+    let mut output = Vec::new();
+    block_on(decoder.read_to_end(&mut output)).unwrap();
+    assert_eq!(output, data());
+}
@@ -0,0 +1,212 @@
+//! Tests for the pure-Rust backends, these also pass when the C-backed features are enabled, but
+//! to test the pure-Rust implementations run them with only the pure-Rust features, e.g.
+//!
+//! ```sh
+//! cargo test --test rust_backends --features futures-io,deflate,miniz_oxide,ruzstd,lzma-rs
+//! ```
+
+#[macro_use]
+mod utils;
+
+use async_compression::futures::{bufread, write};
+use futures::io::AsyncWriteExt as _;
+use utils::{block_on, read_to_end, InputStream};
+
+const FRAMES_ZST: &[u8] = include_bytes!("artifacts/frames.zst");
+const TEXT_LZMA: &[u8] = include_bytes!("artifacts/text.lzma");
+const TEXT_XZ: &[u8] = include_bytes!("artifacts/text.xz");
+
+/// The uncompressed data in the artifacts, larger than the largest zstd block to test data being
+/// split across blocks.
+fn text(lines: usize) -> Vec<u8> {
+    (0..lines)
+        .flat_map(|i| {
+            format!(
+                "{}: the quick brown fox jumps over the lazy dog {}\n",
+                i,
+                i * 7919 % 1000
+            )
+            .into_bytes()
+        })
+        .collect()
+}
+
+fn chunked(bytes: &[u8]) -> InputStream {
+    InputStream::new(bytes.chunks(1000).map(|chunk| chunk.to_vec()).collect())
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn zstd_decompress_frames() {
+    let input = chunked(FRAMES_ZST);
+    let mut decoder = bufread::ZstdDecoder::new(utils::impls::futures::bufread::from(&input));
+    decoder.multiple_members(true);
+    let output = read_to_end(Box::pin(decoder)).unwrap();
+
+    assert_eq!(output, [text(4000), text(100)].concat());
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn zstd_decompress_first_frame() {
+    let input = chunked(FRAMES_ZST);
+    let decoder = bufread::ZstdDecoder::new(utils::impls::futures::bufread::from(&input));
+    let output = read_to_end(Box::pin(decoder)).unwrap();
+
+    assert_eq!(output, text(4000));
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn zstd_decompress_bad_checksum() {
+    // The first frame has a checksum in its last 4 bytes, just before the 13 byte skippable
+    // frame and the second frame
+    let mut compressed = FRAMES_ZST.to_vec();
+    let checksum = compressed.len() - 437 - 13 - 1;
+    compressed[checksum] ^= 0xff;
+
+    let input = chunked(&compressed);
+    let decoder = bufread::ZstdDecoder::new(utils::impls::futures::bufread::from(&input));
+    let result = read_to_end(Box::pin(decoder));
+
+    assert!(result.is_err());
+}
+
+// The libzstd backed decoder does not currently detect truncated frames
+#[cfg(not(feature = "zstd"))]
+#[test]
+#[ntest::timeout(5000)]
+fn zstd_decompress_truncated() {
+    let input = chunked(&FRAMES_ZST[..FRAMES_ZST.len() / 2]);
+    let decoder = bufread::ZstdDecoder::new(utils::impls::futures::bufread::from(&input));
+    let result = read_to_end(Box::pin(decoder));
+
+    assert!(result.is_err());
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn lzma_decompress() {
+    let input = chunked(TEXT_LZMA);
+    let decoder = bufread::LzmaDecoder::new(utils::impls::futures::bufread::from(&input));
+    let output = read_to_end(Box::pin(decoder)).unwrap();
+
+    assert_eq!(output, text(4000));
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn xz_decompress() {
+    let input = chunked(TEXT_XZ);
+    let decoder = bufread::XzDecoder::new(utils::impls::futures::bufread::from(&input));
+    let output = read_to_end(Box::pin(decoder)).unwrap();
+
+    assert_eq!(output, text(4000));
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn xz_decompress_corrupt() {
+    let mut compressed = TEXT_XZ.to_vec();
+    compressed[100] ^= 0xff;
+
+    let input = chunked(&compressed);
+    let decoder = bufread::XzDecoder::new(utils::impls::futures::bufread::from(&input));
+    let result = read_to_end(Box::pin(decoder));
+
+    assert!(result.is_err());
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn xz_decompress_multiple_members() {
+    let input = chunked(&[TEXT_XZ, TEXT_XZ].concat());
+    let mut decoder = bufread::XzDecoder::new(utils::impls::futures::bufread::from(&input));
+    decoder.multiple_members(true);
+    let output = read_to_end(Box::pin(decoder)).unwrap();
+
+    assert_eq!(output, [text(4000), text(4000)].concat());
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn xz_decompress_strict_trailing_data() {
+    use async_compression::{Algorithm, Error, TrailingData};
+
+    let input = chunked(&[TEXT_XZ, b"garbage"].concat());
+    let mut decoder = bufread::XzDecoder::new(utils::impls::futures::bufread::from(&input));
+    decoder.trailing_data(TrailingData::Strict);
+    let error = read_to_end(Box::pin(decoder)).unwrap_err();
+
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::TrailingData {
+            algorithm: Algorithm::Xz
+        })
+    );
+}
+
+// Only the lzma-rs backed decoder buffers whole streams, so has to bound their size
+#[cfg(not(feature = "xz"))]
+#[test]
+#[ntest::timeout(5000)]
+fn xz_decompress_too_large() {
+    use async_compression::{Algorithm, Error};
+
+    // A stream header, then a block of lzma2 chunks each claiming to decompress to 2 MiB
+    let mut compressed = vec![0xfd, b'7', b'z', b'X', b'Z', 0, 0, 1, 0, 0, 0, 0];
+    compressed.extend([2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    compressed.extend([0xff, 0xff, 0xff, 0, 0, 0x5d, 0].repeat(1000));
+
+    let input = chunked(&compressed);
+    let decoder = bufread::XzDecoder::new(utils::impls::futures::bufread::from(&input));
+    let error = read_to_end(Box::pin(decoder)).unwrap_err();
+
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::MemoryLimit {
+            algorithm: Algorithm::Xz
+        })
+    );
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn deflate_round_trip() {
+    let mut encoder = write::DeflateEncoder::new(Vec::new());
+    block_on(encoder.write_all(&text(4000))).unwrap();
+    block_on(encoder.close()).unwrap();
+    let compressed = encoder.into_inner();
+
+    assert_eq!(
+        miniz_oxide::inflate::decompress_to_vec(&compressed).unwrap(),
+        text(4000)
+    );
+
+    let input = chunked(&compressed);
+    let decoder = bufread::DeflateDecoder::new(utils::impls::futures::bufread::from(&input));
+    let output = read_to_end(Box::pin(decoder)).unwrap();
+
+    assert_eq!(output, text(4000));
+}
+
+#[test]
+#[cfg(not(feature = "xz"))]
+fn xz_any_encoder_unavailable() {
+    let result = write::AnyEncoder::new(Vec::new(), async_compression::Algorithm::Xz);
+
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+#[ntest::timeout(5000)]
+fn xz_any_decoder() {
+    let input = chunked(TEXT_XZ);
+    let decoder = bufread::AnyDecoder::new(
+        utils::impls::futures::bufread::from(&input),
+        async_compression::Algorithm::Xz,
+    );
+    let output = read_to_end(Box::pin(decoder)).unwrap();
+
+    assert_eq!(output, text(4000));
+}
@@ -0,0 +1,46 @@
+#[macro_use]
+mod utils;
+
+// The framing format has no end of stream marker, so any trailing data is read as more chunks.
+test_cases!(snappy, trailer: #[ignore = "snappy streams are not self-delimiting"]);
+
+#[allow(unused)]
+use utils::{algos::snappy::sync, one_to_six, InputStream};
+
+#[cfg(feature = "futures-io")]
+use utils::algos::snappy::futures::{bufread, read};
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn snappy_bufread_decompress_skippable_chunks() {
+    let compressed = sync::compress(&[1, 2, 3, 4, 5, 6]);
+    let (identifier, data) = compressed.split_at(10);
+
+    // A padding chunk and a reserved skippable chunk between the stream identifier and the data
+    let input = InputStream::new(vec![
+        identifier.into(),
+        vec![0xfe, 2, 0, 0, 0, 0],
+        vec![0x80, 1, 0, 0, 42],
+        data.into(),
+    ]);
+    let output = bufread::decompress(bufread::from(&input));
+
+    assert_eq!(output, one_to_six());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn snappy_bufread_decompress_bad_checksum() {
+    let mut compressed = sync::compress(&[1, 2, 3, 4, 5, 6]);
+
+    // Corrupt the checksum of the first data chunk, after the identifier and chunk header
+    compressed[14] ^= 0xff;
+
+    let input = InputStream::new(vec![compressed]);
+    let decoder = bufread::Decoder::new(bufread::from(&input));
+    let result = read::poll_read(decoder, &mut [0; 6]);
+
+    assert!(result.is_err());
+}
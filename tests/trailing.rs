@@ -0,0 +1,96 @@
+#[macro_use]
+mod utils;
+
+use async_compression::{
+    futures::{bufread, read},
+    Algorithm, Error, TrailingData,
+};
+use std::io;
+use utils::{algos, data, impls::futures::bufread::from, read_to_end, InputStream};
+
+/// A gzip member followed by `trailing`, split into a few chunks.
+fn gzip_with(trailing: &[u8]) -> InputStream {
+    let mut input = algos::gzip::sync::compress(&data());
+    input.extend_from_slice(trailing);
+    InputStream::new(input.chunks(1000).map(<[u8]>::to_vec).collect())
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn ignored_by_default() {
+    let input = gzip_with(b"garbage");
+    let mut decoder = bufread::GzipDecoder::new(from(&input));
+
+    assert_eq!(read_to_end(&mut decoder).unwrap(), data());
+    assert_eq!(decoder.trailing_bytes(), 0);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn lenient_counts() {
+    let input = gzip_with(b"garbage");
+    let mut decoder = bufread::GzipDecoder::new(from(&input));
+    decoder.trailing_data(TrailingData::Lenient);
+
+    assert_eq!(read_to_end(&mut decoder).unwrap(), data());
+    assert_eq!(decoder.trailing_bytes(), 7);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn strict_rejects_garbage() {
+    let input = gzip_with(&[0, 0, 0, b'x']);
+    let mut decoder = bufread::GzipDecoder::new(from(&input));
+    decoder.trailing_data(TrailingData::Strict);
+
+    let error = read_to_end(&mut decoder).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::TrailingData {
+            algorithm: Algorithm::Gzip
+        })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn strict_allows_padding() {
+    let input = gzip_with(&[0; 2000]);
+    let mut decoder = bufread::GzipDecoder::new(from(&input));
+    decoder.trailing_data(TrailingData::Strict);
+
+    assert_eq!(read_to_end(&mut decoder).unwrap(), data());
+    assert_eq!(decoder.trailing_bytes(), 2000);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn strict_with_multiple_members() {
+    let compressed = algos::gzip::sync::compress(&data());
+    let input = InputStream::new(vec![compressed.clone(), compressed]);
+    let mut decoder = bufread::GzipDecoder::new(from(&input));
+    decoder.multiple_members(true);
+    decoder.trailing_data(TrailingData::Strict);
+
+    assert_eq!(
+        read_to_end(&mut decoder).unwrap(),
+        [data(), data()].concat()
+    );
+    assert_eq!(decoder.trailing_bytes(), 0);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn read_strict_rejects_second_frame() {
+    let compressed = algos::zstd::sync::compress(&data());
+    let input = InputStream::new(vec![compressed.clone(), compressed]);
+    let mut decoder = read::ZstdDecoder::new(from(&input));
+    decoder.trailing_data(TrailingData::Strict);
+
+    let error = read_to_end(&mut decoder).unwrap_err();
+    assert_eq!(
+        Error::from_io(&error).map(Error::algorithm),
+        Some(Algorithm::Zstd)
+    );
+}
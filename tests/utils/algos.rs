@@ -92,6 +92,44 @@ macro_rules! algos {
 }
 
 algos! {
+    pub mod bgzf("bgzf", BgzfEncoder, BgzfDecoder) {
+        pub mod sync {
+            pub use crate::utils::impls::sync::to_vec;
+
+            pub const EOF_MARKER: [u8; 28] = [
+                0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 0x06, 0, b'B', b'C', 0x02, 0, 0x1b, 0,
+                0x03, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            ];
+
+            pub fn compress(bytes: &[u8]) -> Vec<u8> {
+                use flate2::{Compression, GzBuilder};
+                use std::io::Write;
+
+                let mut output = Vec::new();
+                for chunk in bytes.chunks(0xff00) {
+                    let mut block = Vec::new();
+                    let mut gz = GzBuilder::new()
+                        .extra(&b"BC\x02\x00\x00\x00"[..])
+                        .write(&mut block, Compression::fast());
+                    gz.write_all(chunk).unwrap();
+                    gz.finish().unwrap();
+
+                    // Fill in the block size subfield now that it's known
+                    let size = (block.len() - 1) as u16;
+                    block[16..18].copy_from_slice(&size.to_le_bytes());
+                    output.extend(block);
+                }
+                output.extend(&EOF_MARKER);
+                output
+            }
+
+            pub fn decompress(bytes: &[u8]) -> Vec<u8> {
+                use flate2::bufread::MultiGzDecoder;
+                to_vec(MultiGzDecoder::new(bytes))
+            }
+        }
+    }
+
     pub mod brotli("brotli", BrotliEncoder, BrotliDecoder) {
         pub mod sync {
             pub use crate::utils::impls::sync::to_vec;
@@ -141,6 +179,28 @@ algos! {
         }
     }
 
+    pub mod lz4("lz4", Lz4Encoder, Lz4Decoder) {
+        pub mod sync {
+            pub use crate::utils::impls::sync::to_vec;
+
+            pub fn compress(bytes: &[u8]) -> Vec<u8> {
+                use lz4::EncoderBuilder;
+                use std::io::Write;
+
+                let mut encoder = EncoderBuilder::new().level(1).build(Vec::new()).unwrap();
+                encoder.write_all(bytes).unwrap();
+                let (output, result) = encoder.finish();
+                result.unwrap();
+                output
+            }
+
+            pub fn decompress(bytes: &[u8]) -> Vec<u8> {
+                use lz4::Decoder;
+                to_vec(Decoder::new(bytes).unwrap())
+            }
+        }
+    }
+
     pub mod zlib("zlib", ZlibEncoder, ZlibDecoder) {
         pub mod sync {
             pub use crate::utils::impls::sync::to_vec;
@@ -190,6 +250,22 @@ algos! {
         }
     }
 
+    pub mod snappy("snappy", SnappyEncoder, SnappyDecoder) {
+        pub mod sync {
+            pub use crate::utils::impls::sync::to_vec;
+
+            pub fn compress(bytes: &[u8]) -> Vec<u8> {
+                use snap::read::FrameEncoder;
+                to_vec(FrameEncoder::new(bytes))
+            }
+
+            pub fn decompress(bytes: &[u8]) -> Vec<u8> {
+                use snap::read::FrameDecoder;
+                to_vec(FrameDecoder::new(bytes))
+            }
+        }
+    }
+
     pub mod xz("xz", XzEncoder, XzDecoder) {
         pub mod sync {
             pub use crate::utils::impls::sync::to_vec;
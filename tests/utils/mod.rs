@@ -26,3 +26,28 @@ pub fn one_to_six_stream() -> InputStream {
 pub fn one_to_six() -> &'static [u8] {
     &[1, 2, 3, 4, 5, 6]
 }
+
+/// Enough compressible data to need several reads and writes of the default buffer sizes.
+pub fn data() -> Vec<u8> {
+    (0..20_000u32)
+        .flat_map(|i| (i % 1013).to_le_bytes())
+        .collect()
+}
+
+/// A preset dictionary for the formats which support one, sharing words with [`text`].
+pub const DICTIONARY: &[u8] = b"the quick brown fox jumps over the lazy dog";
+
+/// Text which compresses well with [`DICTIONARY`].
+pub fn text() -> Vec<u8> {
+    (0..100)
+        .flat_map(|i| format!("{}: the lazy dog jumps over the quick brown fox\n", i).into_bytes())
+        .collect()
+}
+
+pub fn read_to_end(mut reader: impl futures::io::AsyncRead + Unpin) -> Result<Vec<u8>> {
+    use futures::io::AsyncReadExt;
+
+    let mut output = Vec::new();
+    block_on(reader.read_to_end(&mut output))?;
+    Ok(output)
+}
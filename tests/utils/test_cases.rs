@@ -1,5 +1,5 @@
 macro_rules! io_test_cases {
-    ($impl:ident, $variant:ident) => {
+    ($impl:ident, $variant:ident $(, trailer: #[$trailer:meta])?) => {
         mod $impl {
             mod bufread {
                 mod compress {
@@ -176,6 +176,7 @@ macro_rules! io_test_cases {
 
                     #[test]
                     #[ntest::timeout(1000)]
+                    $(#[$trailer])?
                     fn trailer() {
                         let mut compressed = sync::compress(&[1, 2, 3, 4, 5, 6]);
 
@@ -445,7 +446,7 @@ macro_rules! io_test_cases {
 }
 
 macro_rules! test_cases {
-    ($variant:ident) => {
+    ($variant:ident $(, trailer: #[$trailer:meta])?) => {
         mod $variant {
             #[cfg(feature = "stream")]
             #[allow(deprecated)]
@@ -614,6 +615,7 @@ macro_rules! test_cases {
 
                     #[test]
                     #[ntest::timeout(1000)]
+                    $(#[$trailer])?
                     fn trailer() {
                         // Currently there is no way to get any partially consumed stream item from
                         // the decoder, for now we just guarantee that if the compressed frame
@@ -692,16 +694,16 @@ macro_rules! test_cases {
             }
 
             #[cfg(feature = "futures-io")]
-            io_test_cases!(futures, $variant);
+            io_test_cases!(futures, $variant $(, trailer: #[$trailer])?);
 
             #[cfg(feature = "tokio-02")]
-            io_test_cases!(tokio_02, $variant);
+            io_test_cases!(tokio_02, $variant $(, trailer: #[$trailer])?);
 
             #[cfg(feature = "tokio-03")]
-            io_test_cases!(tokio_03, $variant);
+            io_test_cases!(tokio_03, $variant $(, trailer: #[$trailer])?);
 
             #[cfg(feature = "tokio")]
-            io_test_cases!(tokio, $variant);
+            io_test_cases!(tokio, $variant $(, trailer: #[$trailer])?);
         }
     };
 }
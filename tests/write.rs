@@ -0,0 +1,307 @@
+#[macro_use]
+mod utils;
+
+use async_compression::{
+    futures::write::{GzipDecoder, GzipEncoder, ZstdEncoder},
+    Algorithm, AutoFlush, Error,
+};
+use futures::io::{AsyncWrite as _, AsyncWriteExt as _};
+use futures_test::{io::AsyncWriteTestExt as _, task::new_count_waker};
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use utils::{algos, block_on, data};
+
+#[test]
+#[ntest::timeout(1000)]
+fn encode_with_small_buffer_capacity() {
+    let mut encoder = GzipEncoder::with_capacity(3, Vec::new().interleave_pending_write());
+    for chunk in data().chunks(1000) {
+        block_on(encoder.write_all(chunk)).unwrap();
+    }
+    block_on(encoder.close()).unwrap();
+
+    let compressed = encoder.into_inner().into_inner();
+    assert_eq!(algos::gzip::sync::decompress(&compressed), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn encode_with_large_buffer_capacity() {
+    let mut encoder = ZstdEncoder::new(Vec::new().limited_write(100)).with_buffer_capacity(1 << 20);
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.close()).unwrap();
+
+    let compressed = encoder.into_inner().into_inner();
+    assert_eq!(algos::zstd::sync::decompress(&compressed), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn decode_with_buffer_capacity() {
+    let compressed = algos::gzip::sync::compress(&data());
+    let mut decoder = GzipDecoder::with_capacity(10, Vec::new());
+    for chunk in compressed.chunks(7) {
+        block_on(decoder.write_all(chunk)).unwrap();
+    }
+    block_on(decoder.close()).unwrap();
+
+    assert_eq!(decoder.into_inner(), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn buffer_capacity_keeps_buffered_data() {
+    let mut encoder = GzipEncoder::new(Vec::new());
+    block_on(encoder.write_all(&data())).unwrap();
+    assert!(encoder.get_ref().is_empty());
+    let mut encoder = encoder.with_buffer_capacity(1);
+    block_on(encoder.close()).unwrap();
+
+    assert_eq!(algos::gzip::sync::decompress(encoder.get_ref()), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn tokio_encode_with_buffer_capacity() {
+    use tokio::io::AsyncWriteExt as _;
+
+    let mut encoder =
+        async_compression::tokio::write::GzipEncoder::new(Vec::new()).with_buffer_capacity(5);
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.shutdown()).unwrap();
+
+    assert_eq!(algos::gzip::sync::decompress(encoder.get_ref()), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn decode_multiple_members() {
+    let compressed = algos::gzip::sync::compress(&data());
+    let mut decoder = GzipDecoder::new(Vec::new());
+    decoder.multiple_members(true);
+    for chunk in [compressed.clone(), compressed].concat().chunks(1000) {
+        block_on(decoder.write_all(chunk)).unwrap();
+    }
+    block_on(decoder.close()).unwrap();
+
+    assert_eq!(decoder.into_inner(), [data(), data()].concat());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn decode_after_end_without_multiple_members() {
+    let compressed = algos::gzip::sync::compress(&data());
+    let mut decoder = GzipDecoder::new(Vec::new());
+    let error =
+        block_on(decoder.write_all(&[compressed.clone(), compressed].concat())).unwrap_err();
+
+    assert_eq!(
+        Error::from_io(&error),
+        Some(&Error::TrailingData {
+            algorithm: Algorithm::Gzip
+        })
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn tokio_decode_multiple_frames() {
+    use tokio::io::AsyncWriteExt as _;
+
+    let compressed = algos::zstd::sync::compress(&data());
+    let mut decoder = async_compression::tokio::write::ZstdDecoder::new(Vec::new());
+    decoder.multiple_members(true);
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.flush()).unwrap();
+    block_on(decoder.write_all(&compressed)).unwrap();
+    block_on(decoder.shutdown()).unwrap();
+
+    assert_eq!(decoder.into_inner(), [data(), data()].concat());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn finish_leaves_writer_open() {
+    let mut encoder = GzipEncoder::new(Vec::new());
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.finish()).unwrap();
+
+    let mut encoder = GzipEncoder::new(encoder.into_inner());
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.finish()).unwrap();
+
+    let mut decoder = GzipDecoder::new(Vec::new());
+    decoder.multiple_members(true);
+    block_on(decoder.write_all(&encoder.into_inner())).unwrap();
+    block_on(decoder.close()).unwrap();
+
+    assert_eq!(decoder.into_inner(), [data(), data()].concat());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn flush_after_finish() {
+    let mut encoder = GzipEncoder::new(Vec::new());
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.finish()).unwrap();
+    block_on(encoder.flush()).unwrap();
+
+    assert_eq!(algos::gzip::sync::decompress(&encoder.into_inner()), data());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn tokio_finish_flushes_inner_writer() {
+    use tokio::io::AsyncWriteExt as _;
+
+    let writer = tokio::io::BufWriter::new(Vec::new());
+    let mut encoder = async_compression::tokio::write::ZstdEncoder::new(writer);
+    block_on(encoder.write_all(&data())).unwrap();
+    block_on(encoder.finish()).unwrap();
+
+    let writer = encoder.into_inner();
+    assert_eq!(algos::zstd::sync::decompress(writer.get_ref()), data());
+}
+
+/// Decodes as much as can be decoded from the start of a gzip stream.
+fn gzip_prefix(compressed: &[u8]) -> Vec<u8> {
+    let mut decoder = GzipDecoder::new(Vec::new());
+    block_on(decoder.write_all(compressed)).unwrap();
+    block_on(decoder.flush()).unwrap();
+    decoder.into_inner()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn auto_flush_every_write() {
+    let mut encoder = GzipEncoder::new(Vec::new());
+    encoder.auto_flush(AutoFlush::EveryWrite);
+    for (i, chunk) in data().chunks(1000).enumerate().take(5) {
+        block_on(encoder.write_all(chunk)).unwrap();
+        assert_eq!(gzip_prefix(encoder.get_ref()), data()[..(i + 1) * 1000]);
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn auto_flush_after_bytes() {
+    let mut encoder = GzipEncoder::new(Vec::new().interleave_pending_write());
+    encoder.auto_flush(AutoFlush::Bytes(5000));
+    for chunk in data()[..4000].chunks(1000) {
+        block_on(encoder.write_all(chunk)).unwrap();
+    }
+    assert!(gzip_prefix(encoder.get_ref().get_ref()).len() < 4000);
+
+    block_on(encoder.write_all(&data()[4000..5000])).unwrap();
+    block_on(encoder.write_all(&data()[5000..5001])).unwrap();
+    assert_eq!(gzip_prefix(encoder.get_ref().get_ref()), data()[..5000]);
+}
+
+/// A writer whose flushes fail, or are pending without waking the task, the first time.
+#[derive(Default)]
+struct FlakyFlush {
+    written: Vec<u8>,
+    pending: bool,
+    flushed: bool,
+}
+
+impl futures::io::AsyncWrite for FlakyFlush {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.written.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.flushed {
+            Poll::Ready(Ok(()))
+        } else if self.pending {
+            self.flushed = true;
+            Poll::Pending
+        } else {
+            self.flushed = true;
+            Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "flush failed",
+            )))
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn auto_flush_error_reported_by_next_write() {
+    let mut encoder = GzipEncoder::new(FlakyFlush::default());
+    encoder.auto_flush(AutoFlush::EveryWrite);
+
+    assert_eq!(block_on(encoder.write(&data()[..1000])).unwrap(), 1000);
+    let error = block_on(encoder.write(&data()[1000..2000])).unwrap_err();
+    assert_eq!(error.to_string(), "flush failed");
+
+    block_on(encoder.write_all(&data()[1000..2000])).unwrap();
+    block_on(encoder.close()).unwrap();
+    assert_eq!(
+        algos::gzip::sync::decompress(&encoder.get_ref().written),
+        data()[..2000]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+fn auto_flush_pending_finished_by_flush() {
+    let (waker, count) = new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut encoder = GzipEncoder::new(FlakyFlush {
+        pending: true,
+        ..FlakyFlush::default()
+    });
+    encoder.auto_flush(AutoFlush::EveryWrite);
+
+    let poll = Pin::new(&mut encoder).poll_write(&mut cx, &data()[..1000]);
+    assert!(matches!(poll, Poll::Ready(Ok(1000))));
+    let poll = Pin::new(&mut encoder).poll_flush(&mut cx);
+    assert!(matches!(poll, Poll::Ready(Ok(()))));
+    assert_eq!(count.get(), 0);
+    assert_eq!(gzip_prefix(&encoder.get_ref().written), data()[..1000]);
+
+    block_on(encoder.write_all(&data()[1000..2000])).unwrap();
+    block_on(encoder.flush()).unwrap();
+    assert_eq!(gzip_prefix(&encoder.get_ref().written), data()[..2000]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "tokio-time")]
+fn tokio_flush_when_idle() {
+    use std::time::Duration;
+    use tokio::io::AsyncWriteExt as _;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let mut encoder = async_compression::tokio::write::GzipEncoder::new(Vec::new());
+        encoder.flush_when_idle(Some(Duration::from_millis(10)));
+
+        let nothing = tokio::time::timeout(Duration::from_millis(50), encoder.idle_flush());
+        assert!(nothing.await.is_err());
+
+        encoder.write_all(&data()[..1000]).await.unwrap();
+        assert!(encoder.get_ref().is_empty());
+        encoder.idle_flush().await.unwrap();
+        assert_eq!(gzip_prefix(encoder.get_ref()), data()[..1000]);
+    });
+}
@@ -2,3 +2,77 @@
 mod utils;
 
 test_cases!(zlib);
+
+#[allow(unused)]
+use utils::{text, InputStream, Level, DICTIONARY};
+
+#[cfg(feature = "futures-io")]
+use utils::algos::zlib::futures::{bufread, read};
+
+/// `text()` compressed by Python's `zlib` module with `DICTIONARY` as the preset dictionary.
+#[allow(unused)]
+const COMPRESSED_WITH_DICTIONARY: &[u8] = include_bytes!("artifacts/dictionary.zlib");
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zlib_bufread_decompress_with_dictionary() {
+    let input = InputStream::from(COMPRESSED_WITH_DICTIONARY.chunks(10));
+    let decoder = bufread::Decoder::with_dictionary(bufread::from(&input), DICTIONARY);
+    let output = read::to_vec(decoder);
+
+    assert_eq!(output, text());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zlib_bufread_decompress_with_dictionary_callback() {
+    let input = InputStream::from(COMPRESSED_WITH_DICTIONARY.chunks(10));
+    let decoder = bufread::Decoder::with_dictionary_callback(bufread::from(&input), |id| {
+        assert_eq!(id, async_compression::zlib::dictionary_id(DICTIONARY));
+        Some(DICTIONARY.to_vec())
+    });
+    let output = read::to_vec(decoder);
+
+    assert_eq!(output, text());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zlib_bufread_decompress_without_dictionary() {
+    let input = InputStream::from(vec![COMPRESSED_WITH_DICTIONARY.to_vec()]);
+    let decoder = bufread::Decoder::new(bufread::from(&input));
+    let result = read::poll_read(decoder, &mut [0; 1024]);
+
+    assert!(result.is_err());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zlib_bufread_decompress_with_wrong_dictionary() {
+    let input = InputStream::from(vec![COMPRESSED_WITH_DICTIONARY.to_vec()]);
+    let decoder = bufread::Decoder::with_dictionary(bufread::from(&input), b"the wrong one");
+    let result = read::poll_read(decoder, &mut [0; 1024]);
+
+    assert!(result.is_err());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zlib_bufread_dictionary_round_trip() {
+    let input = InputStream::from(vec![text()]);
+    let encoder =
+        bufread::Encoder::with_dictionary(bufread::from(&input), Level::Default, DICTIONARY)
+            .unwrap();
+    let compressed = read::to_vec(encoder);
+
+    let input = InputStream::from(compressed.chunks(10));
+    let decoder = bufread::Decoder::with_dictionary(bufread::from(&input), DICTIONARY);
+    let output = read::to_vec(decoder);
+
+    assert_eq!(output, text());
+}
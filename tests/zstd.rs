@@ -2,3 +2,303 @@
 mod utils;
 
 test_cases!(zstd);
+
+#[allow(unused)]
+use utils::{algos::zstd::sync, InputStream, Level};
+
+#[cfg(feature = "futures-io")]
+use utils::algos::zstd::futures::{bufread, read, write};
+
+#[allow(unused)]
+const DICTIONARY: &[u8] = b"{\"id\": 0, \"name\": \"\", \"tags\": [], \"active\": false}";
+
+#[allow(unused)]
+fn documents() -> Vec<u8> {
+    (0..50)
+        .flat_map(|i| {
+            format!(
+                "{{\"id\": {}, \"name\": \"doc\", \"tags\": [], \"active\": true}}",
+                i
+            )
+            .into_bytes()
+        })
+        .collect()
+}
+
+#[allow(unused)]
+fn sync_compress_with_dict(bytes: &[u8]) -> Vec<u8> {
+    let encoder = libzstd::stream::read::Encoder::with_dictionary(bytes, 0, DICTIONARY);
+    sync::to_vec(encoder.unwrap())
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_bufread_compress_with_dict() {
+    let input = InputStream::new(vec![documents()]);
+    let encoder =
+        bufread::Encoder::with_dict(bufread::from(&input), Level::Default, DICTIONARY).unwrap();
+    let compressed = read::to_vec(encoder);
+
+    let decoder = libzstd::stream::read::Decoder::with_dictionary(&compressed[..], DICTIONARY);
+    let output = sync::to_vec(decoder.unwrap());
+
+    assert_eq!(output, documents());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_bufread_decompress_with_prepared_dict() {
+    let compressed = sync_compress_with_dict(&documents());
+
+    let dictionary = async_compression::zstd::DecoderDictionary::new(DICTIONARY);
+    let output = (0..2)
+        .map(|_| {
+            let input = InputStream::new(vec![compressed.clone()]);
+            let decoder =
+                bufread::Decoder::with_prepared_dict(bufread::from(&input), &dictionary).unwrap();
+            read::to_vec(decoder)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(output, [documents(), documents()]);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_write_round_trip_with_prepared_dict() {
+    let encoder_dictionary =
+        async_compression::zstd::EncoderDictionary::new(DICTIONARY, Level::Default);
+    let compressed = write::to_vec(
+        &[documents()],
+        |input| Box::pin(write::Encoder::with_prepared_dict(input, &encoder_dictionary).unwrap()),
+        65_536,
+    );
+
+    let output = write::to_vec(
+        &[compressed],
+        |input| Box::pin(write::Decoder::with_dict(input, DICTIONARY).unwrap()),
+        65_536,
+    );
+
+    assert_eq!(output, documents());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_bufread_decompress_missing_dict() {
+    let compressed = sync_compress_with_dict(&documents());
+
+    let input = InputStream::new(vec![compressed]);
+    let decoder = bufread::Decoder::new(bufread::from(&input));
+    let result = read::poll_read(decoder, &mut [0; 1024]);
+
+    assert!(result.is_err());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_bufread_negative_level() {
+    let params = async_compression::zstd::EncoderParams::new().level(-5);
+
+    let input = InputStream::new(vec![documents()]);
+    let encoder = bufread::Encoder::with_params(bufread::from(&input), &params).unwrap();
+    let output = sync::decompress(&read::to_vec(encoder));
+
+    assert_eq!(output, documents());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_bufread_long_window() {
+    let params = async_compression::zstd::EncoderParams::new()
+        .window_log(28)
+        .long_distance_matching(true);
+
+    let input = InputStream::new(vec![documents()]);
+    let encoder = bufread::Encoder::with_params(bufread::from(&input), &params).unwrap();
+    let compressed = read::to_vec(encoder);
+
+    // The default decoder refuses windows larger than 2^27
+    let input = InputStream::new(vec![compressed.clone()]);
+    let decoder = bufread::Decoder::new(bufread::from(&input));
+    assert!(read::poll_read(decoder, &mut [0; 1024]).is_err());
+
+    let params = async_compression::zstd::DecoderParams::new().window_log_max(28);
+    let input = InputStream::new(vec![compressed]);
+    let decoder = bufread::Decoder::with_params(bufread::from(&input), &params).unwrap();
+    let output = read::to_vec(decoder);
+
+    assert_eq!(output, documents());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_bufread_pledged_src_size() {
+    let params = async_compression::zstd::EncoderParams::new()
+        .checksum(true)
+        .pledged_src_size(documents().len() as u64);
+
+    let input = InputStream::new(vec![documents()]);
+    let encoder = bufread::Encoder::with_params(bufread::from(&input), &params).unwrap();
+    let compressed = read::to_vec(encoder);
+
+    assert_eq!(
+        zstd_safe::get_frame_content_size(&compressed),
+        documents().len() as u64
+    );
+    assert_eq!(sync::decompress(&compressed), documents());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_bufread_wrong_pledged_src_size() {
+    use futures::io::AsyncReadExt as _;
+
+    let params = async_compression::zstd::EncoderParams::new().pledged_src_size(10);
+
+    let input = InputStream::new(vec![documents()]);
+    let mut encoder = bufread::Encoder::with_params(bufread::from(&input), &params).unwrap();
+    let result = utils::block_on(encoder.read_to_end(&mut Vec::new()));
+
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "futures-io")]
+fn seekable_compress(bytes: &[u8], frame_size: u32) -> Vec<u8> {
+    use futures::io::AsyncWriteExt as _;
+
+    let mut encoder = async_compression::futures::write::ZstdSeekableEncoder::with_frame_size(
+        Vec::new(),
+        Level::Default,
+        frame_size,
+    );
+    utils::block_on(encoder.write_all(bytes)).unwrap();
+    utils::block_on(encoder.close()).unwrap();
+    encoder.into_inner()
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_seekable_compress_seek_table() {
+    let compressed = seekable_compress(&documents(), 1000);
+
+    // The frames and the skippable seek table can be decompressed by any zstd decoder
+    assert_eq!(sync::decompress(&compressed), documents());
+
+    let mut decoder = async_compression::futures::seekable::ZstdSeekableDecoder::new(
+        futures::io::Cursor::new(&compressed),
+        0..0,
+    );
+    assert!(decoder.seek_table().is_none());
+    assert_eq!(read::poll_read(&mut decoder, &mut [0; 1024]).unwrap(), 0);
+
+    let table = decoder.seek_table().unwrap();
+    let frames = table.frames();
+    assert_eq!(frames.len(), documents().chunks(1000).count());
+    assert_eq!(table.decompressed_size(), documents().len() as u64);
+    assert_eq!(frames[0].compressed_offset(), 0);
+    assert_eq!(frames[1].decompressed_offset(), 1000);
+    assert!(frames.iter().all(|frame| frame.decompressed_size() <= 1000));
+    assert_eq!(table.frame_index(2500), Some(2));
+    assert_eq!(table.frame_index(documents().len() as u64), None);
+
+    // Each frame is independently decompressible
+    let frame = frames[1];
+    let start = frame.compressed_offset() as usize;
+    let end = start + frame.compressed_size() as usize;
+    assert_eq!(
+        sync::decompress(&compressed[start..end]),
+        &documents()[1000..2000]
+    );
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_seekable_decompress_ranges() {
+    use futures::io::AsyncReadExt as _;
+
+    let compressed = seekable_compress(&documents(), 1000);
+    let len = documents().len() as u64;
+
+    let mut decoder = async_compression::futures::seekable::ZstdSeekableDecoder::new(
+        futures::io::Cursor::new(&compressed),
+        0..len,
+    );
+
+    let ranges = [
+        0..len,
+        0..10,
+        10..1000,
+        999..1001,
+        1500..2500,
+        len - 5..len + 100,
+    ];
+    for range in &ranges {
+        decoder.set_range(range.clone()).unwrap();
+        let mut output = Vec::new();
+        utils::block_on(decoder.read_to_end(&mut output)).unwrap();
+
+        let end = range.end.min(len) as usize;
+        assert_eq!(output, &documents()[range.start as usize..end]);
+    }
+
+    decoder.set_range(len + 1..len + 10).unwrap();
+    assert_eq!(read::poll_read(&mut decoder, &mut [0; 1024]).unwrap(), 0);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_seekable_compress_empty() {
+    let compressed = seekable_compress(&[], 1000);
+
+    let mut decoder = async_compression::futures::seekable::ZstdSeekableDecoder::new(
+        futures::io::Cursor::new(&compressed),
+        0..10,
+    );
+    assert_eq!(read::poll_read(&mut decoder, &mut [0; 1024]).unwrap(), 0);
+    assert!(decoder.seek_table().unwrap().frames().is_empty());
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(feature = "futures-io")]
+fn zstd_seekable_decompress_not_seekable() {
+    let compressed = sync::compress(&documents());
+
+    let mut decoder = async_compression::futures::seekable::ZstdSeekableDecoder::new(
+        futures::io::Cursor::new(&compressed),
+        0..10,
+    );
+    let result = read::poll_read(&mut decoder, &mut [0; 1024]);
+
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[ntest::timeout(1000)]
+#[cfg(all(feature = "futures-io", feature = "tokio"))]
+fn zstd_seekable_tokio_decompress_range() {
+    use tokio::io::AsyncReadExt as _;
+
+    let compressed = seekable_compress(&documents(), 1000);
+
+    let mut decoder = async_compression::tokio::seekable::ZstdSeekableDecoder::new(
+        std::io::Cursor::new(&compressed),
+        1500..2500,
+    );
+    let mut output = Vec::new();
+    utils::block_on(decoder.read_to_end(&mut output)).unwrap();
+
+    assert_eq!(output, &documents()[1500..2500]);
+}